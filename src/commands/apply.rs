@@ -0,0 +1,137 @@
+use std::{fs, io::Write};
+
+use mdref::{MdrefError, MovePreview, Result, apply_move_preview};
+use serde::Serialize;
+
+use super::{
+    JsonMove, JsonMoveChange, OutputFormat, json_move_changes, json_moves, write_json_output,
+};
+
+pub fn run(plan: String, root: Option<String>, format: OutputFormat) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(plan, root, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    plan: String,
+    root: Option<String>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+    let content = fs::read_to_string(&plan).map_err(|e| MdrefError::IoRead {
+        path: plan.clone().into(),
+        source: e,
+    })?;
+    let preview: MovePreview =
+        serde_json::from_str(&content).map_err(|e| MdrefError::PathValidation {
+            path: plan.clone().into(),
+            details: format!("invalid plan file: {e}"),
+        })?;
+
+    apply_move_preview(&preview, &root)?;
+
+    match format {
+        OutputFormat::Human => {
+            for (source, destination) in &preview.moves {
+                writeln!(
+                    writer,
+                    "Moved {} -> {}",
+                    source.display(),
+                    destination.display()
+                )?;
+            }
+            writeln!(
+                writer,
+                "Applied {plan}: updated links in {} file(s)",
+                preview.changes.len()
+            )?;
+            Ok(())
+        }
+        OutputFormat::Json => {
+            let payload = ApplyCommandOutput {
+                operation: "apply",
+                plan,
+                moves: json_moves(&preview),
+                changes: json_move_changes(&preview),
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ApplyCommandOutput {
+    operation: &'static str,
+    plan: String,
+    moves: Vec<JsonMove>,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::{NoopProgress, preview_move, test_utils::write_file};
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[allow(clippy::unwrap_used)]
+    fn save_plan(root: &std::path::Path, source: &str, dest: &str) -> String {
+        let preview =
+            preview_move(root.join(source), root.join(dest), root, &NoopProgress).unwrap();
+        let plan_path = root.join("plan.json");
+        fs::write(&plan_path, serde_json::to_string(&preview).unwrap()).unwrap();
+        plan_path.to_str().unwrap().to_string()
+    }
+
+    #[allow(clippy::unwrap_used)]
+    fn root_arg(root: &std::path::Path) -> Option<String> {
+        Some(root.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_apply_command_applies_saved_plan() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        let plan = save_plan(root, "a.md", "docs/a.md");
+
+        let mut output = Vec::new();
+        run_with_writer(plan, root_arg(root), OutputFormat::Json, &mut output).unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "apply");
+        assert_eq!(payload["changes"].as_array().unwrap().len(), 1);
+        assert!(root.join("docs/a.md").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[A](docs/a.md)"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_apply_command_rejects_plan_after_file_changed() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        let plan = save_plan(root, "a.md", "docs/a.md");
+        write_file(root.join("index.md"), "New line\n[A](a.md)");
+
+        let mut output = Vec::new();
+        let error =
+            run_with_writer(plan, root_arg(root), OutputFormat::Human, &mut output).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("changed since they were scanned")
+        );
+        assert!(root.join("a.md").exists());
+        assert!(!root.join("docs/a.md").exists());
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::Write;
+
+use mdref::{BrokenLink, BrokenLinkKind, MdrefError, Result, check_links};
+use serde::Serialize;
+
+use super::{OutputFormat, progress::Spinner, write_json_output};
+
+pub fn run(
+    root: Option<String>,
+    anchors: bool,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(root, anchors, show_progress, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    anchors: bool,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+
+    let spinner = Spinner::new(show_progress);
+
+    let broken_links = check_links(&root, anchors, spinner.as_reporter())?;
+
+    spinner.finish();
+
+    match format {
+        OutputFormat::Human => write_human_output(&root, &broken_links, writer)?,
+        OutputFormat::Json => {
+            let payload = CheckOutput {
+                operation: "check",
+                root: &root,
+                broken_links: broken_links.iter().map(JsonBrokenLink::from).collect(),
+            };
+            write_json_output(writer, &payload)?;
+        }
+    }
+
+    if broken_links.is_empty() {
+        Ok(())
+    } else {
+        Err(MdrefError::BrokenLinks {
+            count: broken_links.len(),
+        })
+    }
+}
+
+fn write_human_output<W: Write>(
+    root: &str,
+    broken_links: &[BrokenLink],
+    writer: &mut W,
+) -> Result<()> {
+    if broken_links.is_empty() {
+        writeln!(writer, "No broken links found in {root}")?;
+        return Ok(());
+    }
+
+    writeln!(writer, "Broken links in {root}:")?;
+    for broken_link in broken_links {
+        writeln!(writer, "{}", broken_link)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct CheckOutput<'a> {
+    operation: &'static str,
+    root: &'a str,
+    broken_links: Vec<JsonBrokenLink>,
+}
+
+#[derive(Serialize)]
+struct JsonBrokenLink {
+    path: String,
+    line: usize,
+    column: usize,
+    link_text: String,
+    kind: &'static str,
+}
+
+impl From<&BrokenLink> for JsonBrokenLink {
+    fn from(broken_link: &BrokenLink) -> Self {
+        Self {
+            path: broken_link.path.display().to_string(),
+            line: broken_link.line,
+            column: broken_link.column,
+            link_text: broken_link.link_text.clone(),
+            kind: match broken_link.kind {
+                BrokenLinkKind::MissingTarget => "missing_target",
+                BrokenLinkKind::MissingAnchor => "missing_anchor",
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::{MdrefError, test_utils::write_file};
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use crate::commands::{OutputFormat, check::run_with_writer};
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_command_reports_no_broken_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("index.md"), "[Guide](guide.md)");
+        write_file(root.join("guide.md"), "# Guide");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("No broken links found"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_command_lists_broken_links_and_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("index.md"), "[Gone](gone.md)");
+
+        let mut output = Vec::new();
+        let error = run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, MdrefError::BrokenLinks { count: 1 }));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Broken links in"));
+        assert!(output.contains("index.md:1:1 - gone.md"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_command_writes_json_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let index = root.join("index.md");
+        write_file(&index, "# Index\n\n![Logo](logo.png)");
+
+        let mut output = Vec::new();
+        let result = run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            false,
+            OutputFormat::Json,
+            &mut output,
+        );
+
+        assert!(result.is_err());
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "check");
+        assert_eq!(payload["root"], root.to_str().unwrap());
+        let broken_links = payload["broken_links"].as_array().unwrap();
+        assert_eq!(broken_links.len(), 1);
+        assert_eq!(broken_links[0]["path"], index.to_str().unwrap());
+        assert_eq!(broken_links[0]["line"], 3);
+        assert_eq!(broken_links[0]["link_text"], "logo.png");
+        assert_eq!(broken_links[0]["kind"], "missing_target");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_command_anchors_flag_reports_missing_anchor() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("index.md"), "# Index\n\n[Typo](#indx)");
+
+        let mut output = Vec::new();
+        let result = run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        );
+
+        assert!(matches!(result, Err(MdrefError::BrokenLinks { count: 1 })));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("#indx (missing anchor)"));
+    }
+}
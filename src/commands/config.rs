@@ -0,0 +1,252 @@
+//! `mdref config`, and the `.mdref.toml` defaults applied to the command line.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::{Command, ValueEnum};
+use mdref::{MdrefError, Result, core::config::Config, diff_paths};
+use serde::Serialize;
+
+use super::{OutputFormat, write_json_output};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ConfigAction {
+    /// Print the configuration in effect for the current directory
+    Show,
+}
+
+/// The configuration file governing the current directory, if any.
+pub fn discover() -> Result<Option<(PathBuf, Config)>> {
+    Config::discover(".")
+}
+
+/// Make the `root` and `format` of `loaded` the defaults of every subcommand
+/// that takes them, so flags given on the command line still win.
+///
+/// A format is only applied to subcommands that offer it, and one that no
+/// subcommand offers is an invalid configuration.
+pub fn apply_defaults(command: Command, loaded: Option<&(PathBuf, Config)>) -> Result<Command> {
+    let Some((path, config)) = loaded else {
+        return Ok(command);
+    };
+    let root = config.root.as_deref().map(|root| default_root(path, root));
+    let format = config.format.as_deref();
+    if let Some(format) = format {
+        check_format(&command, path, format)?;
+    }
+
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    Ok(names.into_iter().fold(command, |command, name| {
+        command.mut_subcommand(name, |mut subcommand| {
+            if let Some(root) = &root
+                && subcommand.get_arguments().any(|arg| arg.get_id() == "root")
+            {
+                subcommand = subcommand.mut_arg("root", |arg| arg.default_value(root.clone()));
+            }
+            if let Some(format) = format
+                && subcommand.get_arguments().any(|arg| {
+                    arg.get_id() == "format"
+                        && arg
+                            .get_possible_values()
+                            .iter()
+                            .any(|value| value.matches(format, false))
+                })
+            {
+                subcommand =
+                    subcommand.mut_arg("format", |arg| arg.default_value(format.to_string()));
+            }
+            subcommand
+        })
+    }))
+}
+
+/// Fail unless some subcommand of `command` offers `format`.
+fn check_format(command: &Command, path: &Path, format: &str) -> Result<()> {
+    let mut known: Vec<String> = Vec::new();
+    for subcommand in command.get_subcommands() {
+        for arg in subcommand.get_arguments() {
+            if arg.get_id() != "format" {
+                continue;
+            }
+            for value in arg.get_possible_values() {
+                if value.matches(format, false) {
+                    return Ok(());
+                }
+                if !known.iter().any(|name| name == value.get_name()) {
+                    known.push(value.get_name().to_string());
+                }
+            }
+        }
+    }
+    Err(MdrefError::Config {
+        path: path.to_path_buf(),
+        details: format!(
+            "unknown format `{format}`, expected one of: {}",
+            known.join(", ")
+        ),
+    })
+}
+
+/// The configured `root`, which is relative to the configuration file, as a
+/// path relative to the current directory.
+fn default_root(config_path: &Path, root: &Path) -> String {
+    let base = config_path.parent().unwrap_or(Path::new("."));
+    let root = base.join(root);
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| diff_paths(&root, cwd))
+        .unwrap_or(root);
+    if relative.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        relative.display().to_string()
+    }
+}
+
+pub fn run(action: ConfigAction, format: OutputFormat) -> Result<()> {
+    let loaded = discover()?;
+    let mut stdout = std::io::stdout();
+    run_with_writer(action, loaded, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    action: ConfigAction,
+    loaded: Option<(PathBuf, Config)>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let ConfigAction::Show = action;
+    let (path, mut config) = match loaded {
+        Some((path, config)) => (Some(path), config),
+        None => (None, Config::default()),
+    };
+    if let (Some(path), Some(root)) = (&path, &config.root) {
+        config.root = Some(PathBuf::from(default_root(path, root)));
+    }
+
+    match format {
+        OutputFormat::Human => {
+            match &path {
+                Some(path) => writeln!(writer, "# Loaded from {}", path.display())?,
+                None => writeln!(writer, "# No .mdref.toml found; showing the defaults")?,
+            }
+            let toml =
+                toml::to_string(&config).map_err(|error| MdrefError::SerializationFailed {
+                    details: format!("failed to write TOML output: {error}"),
+                })?;
+            write!(writer, "{toml}")?;
+        }
+        OutputFormat::Json => {
+            let payload = ConfigOutput {
+                operation: "config",
+                action: "show",
+                path: path.map(|path| path.display().to_string()),
+                config,
+            };
+            write_json_output(writer, &payload)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ConfigOutput {
+    operation: &'static str,
+    action: &'static str,
+    path: Option<String>,
+    config: Config,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Arg, ArgMatches};
+
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("mdref")
+            .subcommand(
+                Command::new("check")
+                    .arg(Arg::new("root").long("root"))
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .value_parser(["human", "json"])
+                            .default_value("human"),
+                    ),
+            )
+            .subcommand(
+                Command::new("graph").arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["dot", "mermaid"])
+                        .default_value("dot"),
+                ),
+            )
+    }
+
+    fn value(matches: &ArgMatches, subcommand: &str, id: &str) -> Option<String> {
+        matches
+            .subcommand_matches(subcommand)?
+            .get_one::<String>(id)
+            .cloned()
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_apply_defaults_keeps_explicit_flags_and_unknown_formats() {
+        let cwd = std::env::current_dir().unwrap();
+        let config = Config {
+            root: Some(PathBuf::from("docs")),
+            format: Some("json".to_string()),
+            ..Config::default()
+        };
+        let loaded = (cwd.join(".mdref.toml"), config);
+        let command = apply_defaults(command(), Some(&loaded)).unwrap();
+
+        let matches = command
+            .clone()
+            .try_get_matches_from(["mdref", "check"])
+            .unwrap();
+        assert_eq!(value(&matches, "check", "root").as_deref(), Some("docs"));
+        assert_eq!(value(&matches, "check", "format").as_deref(), Some("json"));
+
+        let matches = command
+            .clone()
+            .try_get_matches_from(["mdref", "check", "--root", "notes", "--format", "human"])
+            .unwrap();
+        assert_eq!(value(&matches, "check", "root").as_deref(), Some("notes"));
+        assert_eq!(value(&matches, "check", "format").as_deref(), Some("human"));
+
+        let matches = command.try_get_matches_from(["mdref", "graph"]).unwrap();
+        assert_eq!(value(&matches, "graph", "format").as_deref(), Some("dot"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_apply_defaults_rejects_format_no_command_offers() {
+        let config = Config {
+            format: Some("yaml".to_string()),
+            ..Config::default()
+        };
+        let loaded = (PathBuf::from("/project/.mdref.toml"), config);
+
+        let result = apply_defaults(command(), Some(&loaded));
+
+        match result {
+            Err(MdrefError::Config { path, details }) => {
+                assert_eq!(path, PathBuf::from("/project/.mdref.toml"));
+                assert_eq!(
+                    details,
+                    "unknown format `yaml`, expected one of: human, json, dot, mermaid"
+                );
+            }
+            other => panic!("expected a configuration error, got {other:?}"),
+        }
+    }
+}
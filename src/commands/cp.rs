@@ -0,0 +1,150 @@
+use std::io::Write;
+
+use mdref::{IntraCopyLinks, Result, cp, preview_copy};
+use serde::Serialize;
+
+use crate::commands::{JsonMoveChange, OutputFormat, write_json_output, write_move_changes_human};
+
+pub fn run(
+    source: String,
+    dest: String,
+    to_copy: bool,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(source, dest, to_copy, dry_run, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    source: String,
+    dest: String,
+    to_copy: bool,
+    dry_run: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let intra_links = if to_copy {
+        IntraCopyLinks::ToCopy
+    } else {
+        IntraCopyLinks::ToOriginal
+    };
+
+    match format {
+        OutputFormat::Human => {
+            if dry_run {
+                let preview = preview_copy(&source, &dest, intra_links)?;
+                writeln!(
+                    writer,
+                    "[dry-run] Would copy: {} -> {}",
+                    preview.source.display(),
+                    preview.destination.display()
+                )?;
+                return write_move_changes_human(&preview.changes, writer);
+            }
+
+            writeln!(writer, "Copy {source} -> {dest}")?;
+            cp(&source, &dest, intra_links, false)
+        }
+        OutputFormat::Json => {
+            let preview = preview_copy(&source, &dest, intra_links)?;
+
+            if !dry_run {
+                cp(&source, &dest, intra_links, false)?;
+            }
+
+            let payload = CopyCommandOutput {
+                operation: "cp",
+                source,
+                destination: preview.destination.display().to_string(),
+                dry_run,
+                changes: preview.changes.iter().map(JsonMoveChange::from).collect(),
+            };
+
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CopyCommandOutput {
+    operation: &'static str,
+    source: String,
+    destination: String,
+    dry_run: bool,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_cp_command_prints_summary_and_copies_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("guide.md");
+        write_file(&source, "[Index](index.md)\n");
+        write_file(root.join("index.md"), "[Guide](guide.md)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            source.to_str().unwrap().to_string(),
+            root.join("archive/guide.md").to_str().unwrap().to_string(),
+            false,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        assert!(String::from_utf8(output).unwrap().starts_with("Copy "));
+        assert_eq!(fs::read_to_string(&source).unwrap(), "[Index](index.md)\n");
+        assert_eq!(
+            fs::read_to_string(root.join("archive/guide.md")).unwrap(),
+            "[Index](../index.md)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](guide.md)\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_cp_command_dry_run_writes_json_without_copying() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("guide.md");
+        write_file(&source, "[Index](index.md)\n");
+        write_file(root.join("index.md"), "# Index\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            source.to_str().unwrap().to_string(),
+            root.join("archive/guide.md").to_str().unwrap().to_string(),
+            false,
+            true,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "cp");
+        assert_eq!(payload["dry_run"], true);
+        assert_eq!(payload["changes"][0]["kind"], "copied_file_update");
+        assert_eq!(
+            payload["changes"][0]["replacements"][0]["new_pattern"],
+            "](../index.md)"
+        );
+        assert!(!root.join("archive").exists());
+    }
+}
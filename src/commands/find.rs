@@ -1,9 +1,13 @@
-use std::io::Write;
+use std::{io::Write, path::Path};
 
-use mdref::{MdrefError, Reference, Result, find_links, find_references};
+use mdref::{
+    MdrefError, Reference, Result,
+    core::{config::ScanConfig, util::collect_files},
+    find_links, find_references, find_references_many,
+};
 use serde::Serialize;
 
-use super::{OutputFormat, progress::Spinner};
+use super::{JsonReference, OutputFormat, progress::Spinner};
 
 pub fn run(
     path: String,
@@ -40,6 +44,108 @@ fn run_with_writer<W: Write>(
     }
 }
 
+/// Report the references to every non-Markdown file under `dir`, and the
+/// assets nothing links to.
+pub fn run_assets(
+    dir: String,
+    root_dir: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_assets_with_writer(dir, root_dir, show_progress, format, &mut stdout)
+}
+
+fn run_assets_with_writer<W: Write>(
+    dir: String,
+    root_dir: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root_path = root_dir.unwrap_or_else(|| ".".to_string());
+
+    let scan = ScanConfig::for_path(Path::new(&dir));
+    let mut assets: Vec<_> = collect_files(Path::new(&dir))
+        .into_iter()
+        .filter(|path| !scan.is_markdown(path))
+        .collect();
+    assets.sort();
+
+    let spinner = Spinner::new(show_progress);
+    let references = find_references_many(&assets, &root_path, spinner.as_reporter());
+    spinner.finish();
+    let references = references?;
+
+    let assets: Vec<(String, Vec<Reference>)> = assets
+        .iter()
+        .map(|asset| asset.display().to_string())
+        .zip(references)
+        .collect();
+
+    match format {
+        OutputFormat::Human => write_assets_human_output(&dir, &assets, writer),
+        OutputFormat::Json => {
+            let payload = FindAssetsOutput {
+                operation: "find",
+                target: &dir,
+                unused: assets
+                    .iter()
+                    .filter(|(_, references)| references.is_empty())
+                    .map(|(path, _)| path.clone())
+                    .collect(),
+                assets: assets
+                    .iter()
+                    .map(|(path, references)| JsonAsset {
+                        path: path.clone(),
+                        references: references.iter().map(JsonReference::from).collect(),
+                    })
+                    .collect(),
+            };
+            super::write_json_output(writer, &payload)
+        }
+    }
+}
+
+fn write_assets_human_output<W: Write>(
+    dir: &str,
+    assets: &[(String, Vec<Reference>)],
+    writer: &mut W,
+) -> Result<()> {
+    if assets.is_empty() {
+        writeln!(writer, "No assets found in {dir}")?;
+        return Ok(());
+    }
+
+    writeln!(writer, "-------------------------------")?;
+    writeln!(writer, "References to assets in {dir}:")?;
+    for (path, references) in assets.iter().filter(|(_, refs)| !refs.is_empty()) {
+        writeln!(writer, "{path}:")?;
+        for reference in references {
+            writeln!(writer, "  {}", reference)?;
+        }
+    }
+
+    writeln!(writer, "-------------------------------")?;
+    let unused: Vec<&str> = assets
+        .iter()
+        .filter(|(_, references)| references.is_empty())
+        .map(|(path, _)| path.as_str())
+        .collect();
+    if unused.is_empty() {
+        writeln!(writer, "No unused assets in {dir}")?;
+    } else {
+        writeln!(writer, "Unused assets in {dir}:")?;
+        for path in unused {
+            writeln!(writer, "  {path}")?;
+        }
+    }
+
+    writeln!(writer, "-------------------------------")?;
+
+    Ok(())
+}
+
 fn write_human_output<W: Write>(
     path: &str,
     references: &[Reference],
@@ -105,22 +211,17 @@ struct FindOutput<'a> {
 }
 
 #[derive(Serialize)]
-struct JsonReference {
-    path: String,
-    line: usize,
-    column: usize,
-    link_text: String,
+struct FindAssetsOutput<'a> {
+    operation: &'static str,
+    target: &'a str,
+    assets: Vec<JsonAsset>,
+    unused: Vec<String>,
 }
 
-impl From<&Reference> for JsonReference {
-    fn from(reference: &Reference) -> Self {
-        Self {
-            path: reference.path.display().to_string(),
-            line: reference.line,
-            column: reference.column,
-            link_text: reference.link_text.clone(),
-        }
-    }
+#[derive(Serialize)]
+struct JsonAsset {
+    path: String,
+    references: Vec<JsonReference>,
 }
 
 #[cfg(test)]
@@ -129,7 +230,10 @@ mod tests {
     use serde_json::Value;
     use tempfile::TempDir;
 
-    use crate::commands::{OutputFormat, find::run_with_writer};
+    use crate::commands::{
+        OutputFormat,
+        find::{run_assets_with_writer, run_with_writer},
+    };
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -233,4 +337,38 @@ mod tests {
         assert_eq!(links[0]["path"], target.to_str().unwrap());
         assert_eq!(links[0]["link_text"], "guide.md");
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_find_assets_command_lists_referrers_and_unused_assets() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let assets = root.join("assets");
+        write_file(root.join("index.md"), "![Logo](assets/logo.png)");
+        write_file(assets.join("logo.png"), "png");
+        write_file(assets.join("old.png"), "png");
+        write_file(assets.join("notes.md"), "# Notes");
+
+        let mut output = Vec::new();
+        run_assets_with_writer(
+            assets.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "find");
+        let listed = payload["assets"].as_array().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0]["path"], assets.join("logo.png").to_str().unwrap());
+        assert_eq!(listed[0]["references"][0]["link_text"], "assets/logo.png");
+        assert_eq!(listed[1]["references"].as_array().unwrap().len(), 0);
+        assert_eq!(
+            payload["unused"],
+            serde_json::json!([assets.join("old.png").to_str().unwrap()])
+        );
+    }
 }
@@ -0,0 +1,288 @@
+use std::io::Write;
+
+use mdref::{
+    FixMatch, FixPreview, LinkFix, NoopProgress, Result, fix, fix_from_git, preview_fix,
+    preview_fix_from_git,
+};
+use serde::Serialize;
+
+use crate::commands::{
+    JsonMove, JsonMoveChange, OutputFormat, progress::Spinner, write_json_output,
+    write_move_changes_human,
+};
+
+pub fn run(
+    root: Option<String>,
+    apply: bool,
+    from_git: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(root, apply, from_git, show_progress, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    apply: bool,
+    from_git: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root_path = root.unwrap_or_else(|| ".".to_string());
+
+    let spinner = Spinner::new(show_progress);
+    let preview = match &from_git {
+        Some(rev) => preview_fix_from_git(&root_path, rev, spinner.as_reporter()),
+        None => preview_fix(&root_path, spinner.as_reporter()),
+    };
+    spinner.finish();
+    let preview = preview?;
+
+    match format {
+        OutputFormat::Human => match &from_git {
+            Some(rev) => write_git_human_output(rev, &preview, apply, writer)?,
+            None => write_human_output(&root_path, &preview, apply, writer)?,
+        },
+        OutputFormat::Json => {
+            let payload = FixCommandOutput {
+                operation: "fix",
+                root: root_path.clone(),
+                from_git: from_git.clone(),
+                dry_run: !apply,
+                renames: preview
+                    .renames
+                    .iter()
+                    .map(|(source, destination)| JsonMove {
+                        source: source.display().to_string(),
+                        destination: destination.display().to_string(),
+                    })
+                    .collect(),
+                fixes: preview.fixes.iter().map(JsonLinkFix::from).collect(),
+                changes: preview.changes.iter().map(JsonMoveChange::from).collect(),
+            };
+            write_json_output(writer, &payload)?;
+        }
+    }
+
+    if apply {
+        match &from_git {
+            Some(rev) => fix_from_git(&root_path, rev, false, &NoopProgress)?,
+            None => fix(&root_path, false, &NoopProgress)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_git_human_output<W: Write>(
+    rev: &str,
+    preview: &FixPreview,
+    apply: bool,
+    writer: &mut W,
+) -> Result<()> {
+    if preview.renames.is_empty() {
+        writeln!(writer, "No renames found since {rev}")?;
+        return Ok(());
+    }
+
+    writeln!(writer, "Renames since {rev}:")?;
+    for (source, destination) in &preview.renames {
+        writeln!(
+            writer,
+            "  {} -> {}",
+            source.display(),
+            destination.display()
+        )?;
+    }
+
+    if apply {
+        writeln!(writer, "Update links in {} files", preview.changes.len())?;
+    } else {
+        write_move_changes_human(&preview.changes, writer)?;
+        if !preview.changes.is_empty() {
+            writeln!(writer, "Pass --apply to write these fixes.")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_human_output<W: Write>(
+    root: &str,
+    preview: &FixPreview,
+    apply: bool,
+    writer: &mut W,
+) -> Result<()> {
+    if preview.fixes.is_empty() {
+        writeln!(writer, "No broken links found in {root}")?;
+        return Ok(());
+    }
+
+    let (fixable, review): (Vec<&LinkFix>, Vec<&LinkFix>) =
+        preview.fixes.iter().partition(|fix| fix.target().is_some());
+
+    if !fixable.is_empty() {
+        writeln!(writer, "Fixable links in {root}:")?;
+        for fix in &fixable {
+            writeln!(
+                writer,
+                "  {} -> {} ({})",
+                fix.link,
+                fix.candidates[0].display(),
+                fix.matched_by.map_or("", FixMatch::description)
+            )?;
+        }
+    }
+
+    if !review.is_empty() {
+        writeln!(writer, "Needs manual review:")?;
+        for fix in &review {
+            writeln!(writer, "  {}", fix.link)?;
+            if fix.candidates.is_empty() {
+                writeln!(writer, "    no candidate found")?;
+            }
+            for candidate in &fix.candidates {
+                writeln!(writer, "    candidate: {}", candidate.display())?;
+            }
+        }
+    }
+
+    if apply {
+        writeln!(
+            writer,
+            "Fix {} of {} broken links",
+            fixable.len(),
+            preview.fixes.len()
+        )?;
+    } else if !fixable.is_empty() {
+        write_move_changes_human(&preview.changes, writer)?;
+        writeln!(writer, "Pass --apply to write these fixes.")?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct FixCommandOutput {
+    operation: &'static str,
+    root: String,
+    from_git: Option<String>,
+    dry_run: bool,
+    renames: Vec<JsonMove>,
+    fixes: Vec<JsonLinkFix>,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[derive(Serialize)]
+struct JsonLinkFix {
+    path: String,
+    line: usize,
+    column: usize,
+    link_text: String,
+    /// `null` when the link needs manual review.
+    target: Option<String>,
+    matched_by: Option<&'static str>,
+    candidates: Vec<String>,
+}
+
+impl From<&LinkFix> for JsonLinkFix {
+    fn from(fix: &LinkFix) -> Self {
+        Self {
+            path: fix.link.path.display().to_string(),
+            line: fix.link.line,
+            column: fix.link.column,
+            link_text: fix.link.link_text.clone(),
+            target: fix.target().map(|target| target.display().to_string()),
+            matched_by: fix.matched_by.map(|matched_by| match matched_by {
+                FixMatch::ContentHash => "content_hash",
+                FixMatch::UniqueFilename => "unique_filename",
+                FixMatch::PathSimilarity => "path_similarity",
+            }),
+            candidates: fix
+                .candidates
+                .iter()
+                .map(|candidate| candidate.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_fix_command_proposes_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("docs/guide.md"), "# Guide\n");
+        write_file(root.join("index.md"), "[Guide](guide.md)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            None,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Fixable links in"));
+        assert!(output.contains("(unique filename)"));
+        assert!(output.contains("Pass --apply to write these fixes."));
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](guide.md)\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_fix_command_apply_writes_json_and_fixes_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("docs/guide.md"), "# Guide\n");
+        write_file(root.join("a/setup.md"), "# A\n");
+        write_file(root.join("b/setup.md"), "# B\n");
+        write_file(
+            root.join("index.md"),
+            "[Guide](guide.md)\n[Setup](setup.md)\n",
+        );
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            None,
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "fix");
+        assert_eq!(payload["dry_run"], false);
+        assert_eq!(payload["fixes"][0]["matched_by"], "unique_filename");
+        assert_eq!(payload["fixes"][1]["target"], Value::Null);
+        assert_eq!(
+            payload["fixes"][1]["candidates"].as_array().unwrap().len(),
+            2
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](docs/guide.md)\n[Setup](setup.md)\n"
+        );
+    }
+}
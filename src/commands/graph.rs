@@ -0,0 +1,172 @@
+use std::io::Write;
+
+use mdref::{GraphEdge, LinkGraph, Result, build_link_graph};
+use serde::Serialize;
+
+use super::{GraphFormat, progress::Spinner, write_json_output};
+
+pub fn run(
+    root: Option<String>,
+    subtree: Option<String>,
+    around: Option<String>,
+    depth: usize,
+    show_progress: bool,
+    format: GraphFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(
+        root,
+        subtree,
+        around,
+        depth,
+        show_progress,
+        format,
+        &mut stdout,
+    )
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    subtree: Option<String>,
+    around: Option<String>,
+    depth: usize,
+    show_progress: bool,
+    format: GraphFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+
+    let spinner = Spinner::new(show_progress);
+    let graph = build_link_graph(&root, spinner.as_reporter());
+    spinner.finish();
+
+    let mut graph = graph?;
+    if let Some(dir) = &subtree {
+        graph = graph.subtree(dir)?;
+    }
+    if let Some(file) = &around {
+        graph = graph.neighborhood(file, depth)?;
+    }
+
+    match format {
+        GraphFormat::Dot => write!(writer, "{}", graph.to_dot())?,
+        GraphFormat::Mermaid => write!(writer, "{}", graph.to_mermaid())?,
+        GraphFormat::Graphml => write!(writer, "{}", graph.to_graphml())?,
+        GraphFormat::Json => write_json_output(writer, &GraphOutput::new(&root, &graph))?,
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct GraphOutput<'a> {
+    operation: &'static str,
+    root: &'a str,
+    nodes: Vec<String>,
+    edges: Vec<JsonGraphEdge<'a>>,
+}
+
+impl<'a> GraphOutput<'a> {
+    fn new(root: &'a str, graph: &'a LinkGraph) -> Self {
+        Self {
+            operation: "graph",
+            root,
+            nodes: graph
+                .nodes
+                .iter()
+                .map(|node| node.display().to_string())
+                .collect(),
+            edges: graph.edges.iter().map(JsonGraphEdge::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonGraphEdge<'a> {
+    source: String,
+    target: String,
+    line: usize,
+    column: usize,
+    link_text: &'a str,
+    link_type: &'static str,
+}
+
+impl<'a> From<&'a GraphEdge> for JsonGraphEdge<'a> {
+    fn from(edge: &'a GraphEdge) -> Self {
+        Self {
+            source: edge.source.display().to_string(),
+            target: edge.target.display().to_string(),
+            line: edge.line,
+            column: edge.column,
+            link_text: &edge.link_text,
+            link_type: edge.link_type.as_str(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_graph_command_writes_json_edges() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("index.md"), "[Guide](docs/guide.md)\n");
+        write_file(root.join("docs/guide.md"), "![Logo](logo.png) [[index]]\n");
+        write_file(root.join("docs/logo.png"), "png");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            None,
+            None,
+            1,
+            false,
+            GraphFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "graph");
+        assert_eq!(payload["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(payload["edges"][0]["source"], "docs/guide.md");
+        assert_eq!(payload["edges"][0]["target"], "docs/logo.png");
+        assert_eq!(payload["edges"][1]["link_type"], "wiki_link");
+        assert_eq!(payload["edges"][2]["line"], 1);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_graph_command_filters_subtree_as_dot() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("index.md"), "[Guide](docs/guide.md)\n");
+        write_file(root.join("docs/guide.md"), "[Setup](setup.md)\n");
+        write_file(root.join("docs/setup.md"), "# Setup\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            Some(root.join("docs").to_str().unwrap().to_string()),
+            None,
+            1,
+            false,
+            GraphFormat::Dot,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "digraph links {\n    \"docs/guide.md\";\n    \"docs/setup.md\";\n    \
+             \"docs/guide.md\" -> \"docs/setup.md\" [label=\"inline 1:1\"];\n}\n"
+        );
+    }
+}
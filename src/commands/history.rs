@@ -0,0 +1,203 @@
+use std::{
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use mdref::{HistoryEntry, Result, diff_paths, history};
+use serde::Serialize;
+
+use super::{JsonMove, OutputFormat, write_json_output};
+
+pub fn run(root: Option<String>, format: OutputFormat) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(root, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+    let entries = history(&root)?;
+
+    match format {
+        OutputFormat::Human => {
+            if entries.is_empty() {
+                writeln!(writer, "No operations recorded.")?;
+                return Ok(());
+            }
+            for entry in &entries {
+                write_entry_human(entry, Path::new(&root), writer)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Json => {
+            let payload = HistoryCommandOutput {
+                operation: "history",
+                root: &root,
+                entries: entries.iter().map(JsonHistoryEntry::from).collect(),
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+/// Write one journal entry as a summary line followed by its moves.
+pub fn write_entry_human<W: Write>(
+    entry: &HistoryEntry,
+    root: &Path,
+    writer: &mut W,
+) -> Result<()> {
+    writeln!(
+        writer,
+        "#{} {} ({}): moved {} path(s), rewrote {} file(s)",
+        entry.id,
+        entry.operation,
+        age(entry.timestamp),
+        entry.moves.len(),
+        entry.files.len()
+    )?;
+    for (source, destination) in &entry.moves {
+        writeln!(
+            writer,
+            "    {} -> {}",
+            relative_display(source, root),
+            relative_display(destination, root)
+        )?;
+    }
+    Ok(())
+}
+
+/// `path` relative to `root` when it lies below it, otherwise as recorded.
+fn relative_display(path: &Path, root: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    match diff_paths(path, &root) {
+        Some(relative) if !relative.starts_with("..") => relative.display().to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+/// How long ago `timestamp` (seconds since the Unix epoch) was, e.g. `5 minutes ago`.
+fn age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let seconds = now.saturating_sub(timestamp);
+    let (count, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        _ => (seconds / 86_400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{count} {unit}{plural} ago")
+}
+
+#[derive(Serialize)]
+struct HistoryCommandOutput<'a> {
+    operation: &'static str,
+    root: &'a str,
+    entries: Vec<JsonHistoryEntry>,
+}
+
+#[derive(Serialize)]
+pub struct JsonHistoryEntry {
+    pub id: u64,
+    pub operation: String,
+    pub timestamp: u64,
+    pub moves: Vec<JsonMove>,
+    pub files: Vec<String>,
+}
+
+impl From<&HistoryEntry> for JsonHistoryEntry {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: entry.id,
+            operation: entry.operation.clone(),
+            timestamp: entry.timestamp,
+            moves: entry
+                .moves
+                .iter()
+                .map(|(source, destination)| JsonMove {
+                    source: source.display().to_string(),
+                    destination: destination.display().to_string(),
+                })
+                .collect(),
+            files: entry
+                .files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::{NoopProgress, mv, test_utils::write_file};
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_history_command_lists_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        mv(
+            root.join("a.md"),
+            root.join("b.md"),
+            root,
+            false,
+            &NoopProgress,
+        )
+        .unwrap();
+        mv(
+            root.join("b.md"),
+            root.join("c.md"),
+            root,
+            false,
+            &NoopProgress,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "#2 mv (just now): moved 1 path(s), rewrote 1 file(s)\n    b.md -> c.md\n\
+             #1 mv (just now): moved 1 path(s), rewrote 1 file(s)\n    a.md -> b.md\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_history_command_json_empty_history() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(temp_dir.path().to_str().unwrap().to_string()),
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "history");
+        assert_eq!(payload["entries"].as_array().unwrap().len(), 0);
+    }
+}
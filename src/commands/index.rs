@@ -0,0 +1,243 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use mdref::{IndexStatus, Result, build_index, clear_index, index_status};
+use serde::Serialize;
+
+use super::{OutputFormat, progress::Spinner, write_json_output};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum IndexAction {
+    /// Build the index, or bring an existing one up to date
+    Build,
+    /// Compare the index with the files on disk
+    Status,
+    /// Delete the index
+    Clear,
+}
+
+impl IndexAction {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::Status => "status",
+            Self::Clear => "clear",
+        }
+    }
+}
+
+pub fn run(
+    action: IndexAction,
+    root: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(action, root, show_progress, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    action: IndexAction,
+    root: Option<String>,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+
+    let (status, cleared) = match action {
+        IndexAction::Build => {
+            let spinner = Spinner::new(show_progress);
+            let status = build_index(&root, spinner.as_reporter())?;
+            spinner.finish();
+            (status, None)
+        }
+        IndexAction::Status => (index_status(&root)?, None),
+        IndexAction::Clear => {
+            let cleared = clear_index(&root)?;
+            (index_status(&root)?, Some(cleared))
+        }
+    };
+
+    match format {
+        OutputFormat::Human => write_human_output(action, &status, cleared, writer),
+        OutputFormat::Json => {
+            let payload = IndexOutput {
+                operation: "index",
+                action: action.name(),
+                root: &root,
+                path: status.path.display().to_string(),
+                exists: status.exists,
+                fresh: status.is_fresh(),
+                indexed_files: status.indexed_files,
+                stale_files: status.stale_files,
+                new_files: status.new_files,
+                removed_files: status.removed_files,
+                links: status.links,
+                cleared,
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+fn write_human_output<W: Write>(
+    action: IndexAction,
+    status: &IndexStatus,
+    cleared: Option<bool>,
+    writer: &mut W,
+) -> Result<()> {
+    let path = status.path.display();
+
+    match (action, cleared) {
+        (IndexAction::Clear, Some(true)) => writeln!(writer, "Removed index {path}")?,
+        (IndexAction::Clear, _) => writeln!(writer, "No index at {path}")?,
+        (IndexAction::Build, _) => {
+            writeln!(writer, "Built index {path}")?;
+            writeln!(
+                writer,
+                "  {} files indexed, {} links",
+                status.indexed_files, status.links
+            )?;
+        }
+        _ if !status.exists => writeln!(
+            writer,
+            "No index at {path} ({} Markdown files not indexed)",
+            status.new_files
+        )?,
+        _ => {
+            let state = if status.is_fresh() {
+                "up to date"
+            } else {
+                "stale"
+            };
+            writeln!(writer, "Index {path} is {state}")?;
+            writeln!(
+                writer,
+                "  {} files, {} links",
+                status.indexed_files, status.links
+            )?;
+            if !status.is_fresh() {
+                writeln!(
+                    writer,
+                    "  {} changed, {} new, {} removed",
+                    status.stale_files, status.new_files, status.removed_files
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct IndexOutput<'a> {
+    operation: &'static str,
+    action: &'static str,
+    root: &'a str,
+    path: String,
+    exists: bool,
+    fresh: bool,
+    indexed_files: usize,
+    stale_files: usize,
+    new_files: usize,
+    removed_files: usize,
+    links: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cleared: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_index_command_build_reports_built_then_status_reports_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "[B](b.md)");
+        write_file(root.join("b.md"), "# B");
+        let root_arg = Some(root.to_str().unwrap().to_string());
+
+        let mut output = Vec::new();
+        run_with_writer(
+            IndexAction::Build,
+            root_arg.clone(),
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let built = String::from_utf8(output).unwrap();
+        assert!(built.starts_with("Built index "));
+        assert!(built.contains("2 files indexed, 1 links"));
+        assert!(!built.contains("up to date"));
+
+        let mut output = Vec::new();
+        run_with_writer(
+            IndexAction::Status,
+            root_arg,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let status = String::from_utf8(output).unwrap();
+        assert!(status.contains("is up to date"));
+        assert!(status.contains("2 files, 1 links"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_index_command_status_without_index_reports_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file(temp_dir.path().join("a.md"), "# A");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            IndexAction::Status,
+            Some(temp_dir.path().to_str().unwrap().to_string()),
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("No index at"));
+        assert!(output.contains("1 Markdown files not indexed"));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_index_command_clear_writes_json_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        build_index(root, &mdref::NoopProgress).unwrap();
+
+        let mut output = Vec::new();
+        run_with_writer(
+            IndexAction::Clear,
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "index");
+        assert_eq!(payload["action"], "clear");
+        assert_eq!(payload["cleared"], true);
+        assert_eq!(payload["exists"], false);
+        assert!(!root.join(".mdref").exists());
+    }
+}
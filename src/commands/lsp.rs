@@ -0,0 +1,758 @@
+//! `mdref lsp`: a language server speaking LSP over stdio.
+//!
+//! Requests are answered from the open documents' unsaved contents, tracked
+//! as an [`Overlay`], and from disk for every other file.
+
+use std::path::{Path, PathBuf};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, FileOperationFilter, FileOperationPattern,
+    FileOperationRegistrationOptions, GotoDefinitionParams, GotoDefinitionResponse,
+    InitializeParams, Location, OneOf, Position, PublishDiagnosticsParams, Range, ReferenceParams,
+    RenameFilesParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, Url, WorkspaceEdit, WorkspaceFileOperationsServerCapabilities,
+    WorkspaceServerCapabilities,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidRenameFiles,
+        DidSaveTextDocument, Notification as _, PublishDiagnostics,
+    },
+    request::{GotoDefinition, References, Request as _, WillRenameFiles},
+};
+use mdref::{
+    MdrefError, NoopProgress, Reference, Result,
+    core::{
+        config::is_markdown,
+        editor::{
+            Overlay, check_document, find_references_in_overlay, link_at, link_span,
+            preview_move_in_overlay, resolve_link_target,
+        },
+        model::LinkReplacement,
+        util::pre_move_path,
+    },
+};
+
+pub fn run(root: Option<String>, anchors: bool) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection, root, anchors)?;
+    // The writer thread exits once every sender is gone.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn serve(connection: &Connection, root: Option<String>, anchors: bool) -> Result<()> {
+    let (id, params) = connection.initialize_start().map_err(protocol_error)?;
+    let params: InitializeParams =
+        serde_json::from_value(params).map_err(|error| MdrefError::LanguageServer {
+            details: format!("invalid initialize params: {error}"),
+        })?;
+    let root = match root {
+        Some(root) => PathBuf::from(root),
+        None => workspace_root(&params).unwrap_or_else(|| PathBuf::from(".")),
+    };
+    let root = root.canonicalize().map_err(|e| MdrefError::IoRead {
+        path: root.clone(),
+        source: e,
+    })?;
+
+    let initialize_result = serde_json::json!({
+        "capabilities": capabilities(),
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    });
+    connection
+        .initialize_finish(id, initialize_result)
+        .map_err(protocol_error)?;
+
+    let mut server = Server {
+        root,
+        anchors,
+        overlay: Overlay::new(),
+    };
+    for message in &connection.receiver {
+        let replies = match message {
+            Message::Request(request) => {
+                if connection
+                    .handle_shutdown(&request)
+                    .map_err(protocol_error)?
+                {
+                    return Ok(());
+                }
+                vec![Message::Response(server.handle_request(request))]
+            }
+            Message::Notification(notification) => server
+                .handle_notification(notification)
+                .into_iter()
+                .map(Message::Notification)
+                .collect(),
+            Message::Response(_) => Vec::new(),
+        };
+        for reply in replies {
+            connection
+                .sender
+                .send(reply)
+                .map_err(|error| MdrefError::LanguageServer {
+                    details: error.to_string(),
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    let markdown_files = FileOperationRegistrationOptions {
+        filters: vec![FileOperationFilter {
+            scheme: Some("file".to_string()),
+            pattern: FileOperationPattern {
+                glob: "**/*".to_string(),
+                matches: None,
+                options: None,
+            },
+        }],
+    };
+
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        references_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        workspace: Some(WorkspaceServerCapabilities {
+            workspace_folders: None,
+            file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                will_rename: Some(markdown_files.clone()),
+                did_rename: Some(markdown_files),
+                ..Default::default()
+            }),
+        }),
+        ..Default::default()
+    }
+}
+
+#[allow(deprecated)]
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    params
+        .workspace_folders
+        .iter()
+        .flatten()
+        .map(|folder| &folder.uri)
+        .chain(params.root_uri.as_ref())
+        .find_map(|uri| uri.to_file_path().ok())
+}
+
+struct Server {
+    root: PathBuf,
+    anchors: bool,
+    overlay: Overlay,
+}
+
+impl Server {
+    fn handle_request(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            References::METHOD => respond::<References>(request, |params| self.references(params)),
+            GotoDefinition::METHOD => {
+                respond::<GotoDefinition>(request, |params| self.definition(params))
+            }
+            WillRenameFiles::METHOD => {
+                respond::<WillRenameFiles>(request, |params| self.will_rename_files(params))
+            }
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {method}"),
+            ),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Vec<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(params) = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                else {
+                    return Vec::new();
+                };
+                self.update(&params.text_document.uri, Some(params.text_document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(mut params) = notification
+                    .extract::<lsp_types::DidChangeTextDocumentParams>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                else {
+                    return Vec::new();
+                };
+                // With full sync, the last change holds the whole document.
+                let text = params.content_changes.pop().map(|change| change.text);
+                if text.is_none() {
+                    return Vec::new();
+                }
+                self.update(&params.text_document.uri, text)
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(params) = notification
+                    .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                else {
+                    return Vec::new();
+                };
+                self.update(&params.text_document.uri, None)
+            }
+            // Saving or renaming a file can fix or break links in any open document.
+            DidSaveTextDocument::METHOD | DidRenameFiles::METHOD => self
+                .overlay
+                .keys()
+                .filter_map(|path| self.diagnostics(path))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replace the overlaid contents of `uri`, or drop them when `text` is
+    /// `None`, and return the document's new diagnostics.
+    fn update(&mut self, uri: &Url, text: Option<String>) -> Vec<Notification> {
+        let Ok(path) = uri.to_file_path() else {
+            return Vec::new();
+        };
+        let key = path.canonicalize().unwrap_or(path);
+        match text {
+            Some(text) => {
+                self.overlay.insert(key.clone(), text);
+                self.diagnostics(&key).into_iter().collect()
+            }
+            None => {
+                self.overlay.remove(&key);
+                vec![publish_diagnostics(uri.clone(), Vec::new())]
+            }
+        }
+    }
+
+    fn diagnostics(&self, path: &Path) -> Option<Notification> {
+        if !is_markdown(path) {
+            return None;
+        }
+        let uri = Url::from_file_path(path).ok()?;
+        let content = self.overlay.get(path)?;
+        let broken_links = check_document(path, &self.root, self.anchors, &self.overlay).ok()?;
+
+        let diagnostics = broken_links
+            .into_iter()
+            .map(|broken| {
+                let reference = Reference::with_link_type(
+                    broken.path,
+                    broken.line,
+                    broken.column,
+                    broken.link_text,
+                    broken.link_type,
+                );
+                Diagnostic {
+                    range: reference_range(content, &reference),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    source: Some("mdref".to_string()),
+                    message: format!("{}: {}", broken.kind.description(), reference.link_text),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Some(publish_diagnostics(uri, diagnostics))
+    }
+
+    /// References to the target of the link under the cursor, or to the
+    /// document itself when the cursor is not on a link.
+    fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let position = params.text_document_position;
+        let path = file_path(&position.text_document.uri)?;
+        let target = match self.link_under(&path, position.position)? {
+            Some(link) => match resolve_link_target(&link, &self.root, &self.overlay) {
+                Some((target, _)) => target,
+                None => return Ok(None),
+            },
+            None => path,
+        };
+
+        let references =
+            find_references_in_overlay(&target, &self.root, &self.overlay, &NoopProgress)?;
+        let mut locations = Vec::with_capacity(references.len());
+        for reference in &references {
+            let content = self.read(&reference.path)?;
+            locations.push(Location {
+                uri: file_uri(&reference.path)?,
+                range: reference_range(&content, reference),
+            });
+        }
+
+        Ok(Some(locations))
+    }
+
+    /// The file the link under the cursor points at, on the heading its
+    /// `#fragment` names.
+    fn definition(&self, params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let path = file_path(&position.text_document.uri)?;
+        let Some(link) = self.link_under(&path, position.position)? else {
+            return Ok(None);
+        };
+        let Some((target, heading)) = resolve_link_target(&link, &self.root, &self.overlay) else {
+            return Ok(None);
+        };
+
+        let start = heading
+            .map(|heading| Position::new(heading.line.saturating_sub(1) as u32, 0))
+            .unwrap_or_default();
+        Ok(Some(GotoDefinitionResponse::Scalar(Location {
+            uri: file_uri(&target)?,
+            range: Range::new(start, start),
+        })))
+    }
+
+    /// The link rewrites that keep every link working once the files move.
+    /// Edits are relative to the files before the move, which is when the
+    /// client applies them.
+    fn will_rename_files(&self, params: RenameFilesParams) -> Result<Option<WorkspaceEdit>> {
+        let moves = params
+            .files
+            .iter()
+            .map(|rename| {
+                Ok((
+                    parse_file_uri(&rename.old_uri)?,
+                    parse_file_uri(&rename.new_uri)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if moves.is_empty() {
+            return Ok(None);
+        }
+
+        let preview = preview_move_in_overlay(&moves, &self.root, &self.overlay, &NoopProgress)?;
+        let mut changes = std::collections::HashMap::new();
+        for change in &preview.changes {
+            let path = pre_move_path(&preview.moves, &change.path);
+            let content = self.read(&path)?;
+            let edits: Vec<TextEdit> = change
+                .replacements
+                .iter()
+                .filter_map(|replacement| text_edit(&content, replacement))
+                .collect();
+            changes.insert(file_uri(&path)?, edits);
+        }
+
+        Ok((!changes.is_empty()).then(|| WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
+    fn link_under(&self, path: &Path, position: Position) -> Result<Option<Reference>> {
+        let content = self.read(path)?;
+        let Some(line) = content.lines().nth(position.line as usize) else {
+            return Ok(None);
+        };
+        let column = byte_offset(line, position.character) + 1;
+        Ok(link_at(path, &content, position.line as usize + 1, column))
+    }
+
+    /// The contents of `path`: its overlaid buffer if it is open, else the file on disk.
+    fn read(&self, path: &Path) -> Result<String> {
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        match self.overlay.get(&key) {
+            Some(content) => Ok(content.clone()),
+            None => std::fs::read_to_string(path).map_err(|e| MdrefError::IoRead {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+        }
+    }
+}
+
+/// Extract the params of `request`, run `handler` on them, and wrap its
+/// outcome in a response.
+fn respond<R: lsp_types::request::Request>(
+    request: Request,
+    handler: impl FnOnce(R::Params) -> Result<R::Result>,
+) -> Response {
+    let id = request.id.clone();
+    match request.extract::<R::Params>(R::METHOD) {
+        Ok((_, params)) => match handler(params) {
+            Ok(result) => Response::new_ok(id, result),
+            Err(error) => Response::new_err(id, ErrorCode::RequestFailed as i32, error.to_string()),
+        },
+        Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+    }
+}
+
+fn publish_diagnostics(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        },
+    )
+}
+
+/// The range of the URL of `reference` in `content`, or an empty range at
+/// its column when the URL cannot be located.
+fn reference_range(content: &str, reference: &Reference) -> Range {
+    let line = content
+        .lines()
+        .nth(reference.line.saturating_sub(1))
+        .unwrap_or("");
+    let (start, end) = link_span(content, reference).unwrap_or_else(|| {
+        let column = reference.column.saturating_sub(1);
+        (column, column)
+    });
+    line_range(reference.line, line, start, end)
+}
+
+fn text_edit(content: &str, replacement: &LinkReplacement) -> Option<TextEdit> {
+    let line = content.lines().nth(replacement.line.checked_sub(1)?)?;
+    let from = replacement.column.saturating_sub(1);
+    let start = from + line.get(from..)?.find(&replacement.old_pattern)?;
+    let end = start + replacement.old_pattern.len();
+
+    Some(TextEdit {
+        range: line_range(replacement.line, line, start, end),
+        new_text: replacement.new_pattern.clone(),
+    })
+}
+
+/// The LSP range of bytes `start..end` on the 1-based `line_number`, whose text is `line`.
+fn line_range(line_number: usize, line: &str, start: usize, end: usize) -> Range {
+    let line_index = line_number.saturating_sub(1) as u32;
+    Range::new(
+        Position::new(line_index, utf16_offset(line, start)),
+        Position::new(line_index, utf16_offset(line, end)),
+    )
+}
+
+/// Convert a byte offset in `line` to the UTF-16 offset LSP positions use.
+fn utf16_offset(line: &str, byte: usize) -> u32 {
+    line.get(..byte).unwrap_or(line).encode_utf16().count() as u32
+}
+
+/// Convert a UTF-16 offset in `line` to a byte offset.
+fn byte_offset(line: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (byte, c) in line.char_indices() {
+        if units >= utf16 {
+            return byte;
+        }
+        units += c.len_utf16() as u32;
+    }
+    line.len()
+}
+
+fn file_path(uri: &Url) -> Result<PathBuf> {
+    uri.to_file_path().map_err(|_| MdrefError::PathValidation {
+        path: PathBuf::from(uri.as_str()),
+        details: "not a file URI".to_string(),
+    })
+}
+
+fn parse_file_uri(uri: &str) -> Result<PathBuf> {
+    let uri = Url::parse(uri).map_err(|error| MdrefError::PathValidation {
+        path: PathBuf::from(uri),
+        details: format!("invalid URI: {error}"),
+    })?;
+    file_path(&uri)
+}
+
+fn file_uri(path: &Path) -> Result<Url> {
+    Url::from_file_path(path).map_err(|_| MdrefError::PathValidation {
+        path: path.to_path_buf(),
+        details: "cannot be expressed as a file URI".to_string(),
+    })
+}
+
+fn protocol_error(error: lsp_server::ProtocolError) -> MdrefError {
+    MdrefError::LanguageServer {
+        details: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::{self, JoinHandle};
+
+    use lsp_server::RequestId;
+    use mdref::test_utils::write_file;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// The client end of a server running on an in-memory connection.
+    struct Client {
+        connection: Connection,
+        server: JoinHandle<Result<()>>,
+        next_id: i32,
+    }
+
+    impl Client {
+        #[allow(clippy::unwrap_used)]
+        fn start(root: &Path) -> Self {
+            let (server_side, connection) = Connection::memory();
+            let root = root.to_str().unwrap().to_string();
+            let server = thread::spawn(move || serve(&server_side, Some(root), true));
+            let mut client = Self {
+                connection,
+                server,
+                next_id: 0,
+            };
+            client.request("initialize", json!({ "capabilities": {} }));
+            client.notify("initialized", json!({}));
+            client
+        }
+
+        #[allow(clippy::unwrap_used)]
+        fn request(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            let request = Request::new(id.clone(), method.to_string(), params);
+            self.connection.sender.send(request.into()).unwrap();
+            loop {
+                if let Message::Response(response) = self.connection.receiver.recv().unwrap() {
+                    assert_eq!(response.id, id);
+                    assert!(response.error.is_none(), "{:?}", response.error);
+                    return response.result.unwrap();
+                }
+            }
+        }
+
+        #[allow(clippy::unwrap_used)]
+        fn notify(&self, method: &str, params: Value) {
+            let notification = Notification::new(method.to_string(), params);
+            self.connection.sender.send(notification.into()).unwrap();
+        }
+
+        #[allow(clippy::unwrap_used)]
+        fn diagnostics(&self) -> Value {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification)
+                    if notification.method == PublishDiagnostics::METHOD =>
+                {
+                    notification.params["diagnostics"].clone()
+                }
+                other => panic!("expected diagnostics, got {other:?}"),
+            }
+        }
+
+        #[allow(clippy::unwrap_used)]
+        fn shutdown(mut self) {
+            self.request("shutdown", Value::Null);
+            self.notify("exit", Value::Null);
+            self.server.join().unwrap().unwrap();
+        }
+    }
+
+    fn uri(path: &Path) -> String {
+        file_uri(path).map(String::from).unwrap_or_default()
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_lsp_publishes_diagnostics_for_unsaved_buffers() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+        write_file(&index, "# Index\n");
+        let client = Client::start(&root);
+
+        client.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri(&index), "languageId": "markdown", "version": 1,
+                "text": "Café 😀 [Gone](gone.md)\n",
+            }}),
+        );
+        let diagnostics = client.diagnostics();
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(diagnostics[0]["message"], "missing target: gone.md");
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({ "start": { "line": 0, "character": 15 }, "end": { "line": 0, "character": 22 } })
+        );
+
+        client.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri(&index), "version": 2 },
+                "contentChanges": [{ "text": "# Index\n" }],
+            }),
+        );
+        assert_eq!(client.diagnostics(), json!([]));
+
+        client.shutdown();
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_lsp_answers_references_and_definition() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+        let guide = root.join("guide.md");
+        write_file(&index, "# Index\n");
+        write_file(&guide, "# Guide\n\n## Setup\n");
+        let mut client = Client::start(&root);
+        client.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri(&index), "languageId": "markdown", "version": 1,
+                "text": "# Index\n[Setup](guide.md#setup)\n",
+            }}),
+        );
+        assert_eq!(client.diagnostics(), json!([]));
+
+        let references = client.request(
+            "textDocument/references",
+            json!({
+                "textDocument": { "uri": uri(&guide) },
+                "position": { "line": 0, "character": 0 },
+                "context": { "includeDeclaration": true },
+            }),
+        );
+        assert_eq!(
+            references,
+            json!([{
+                "uri": uri(&index),
+                "range": { "start": { "line": 1, "character": 8 }, "end": { "line": 1, "character": 22 } },
+            }])
+        );
+
+        let definition = client.request(
+            "textDocument/definition",
+            json!({
+                "textDocument": { "uri": uri(&index) },
+                "position": { "line": 1, "character": 2 },
+            }),
+        );
+        assert_eq!(definition["uri"], uri(&guide));
+        assert_eq!(definition["range"]["start"]["line"], 2);
+
+        client.shutdown();
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_lsp_will_rename_files_edits_unsaved_buffers() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+        let guide = root.join("guide.md");
+        write_file(&index, "[Guide](guide.md)\n");
+        write_file(&guide, "# Guide\n");
+        let mut client = Client::start(&root);
+        client.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri(&index), "languageId": "markdown", "version": 1,
+                "text": "# Home\n\n[Guide](guide.md)\n",
+            }}),
+        );
+        assert_eq!(client.diagnostics(), json!([]));
+
+        let edit = client.request(
+            "workspace/willRenameFiles",
+            json!({ "files": [{
+                "oldUri": uri(&guide),
+                "newUri": uri(&root.join("docs/guide.md")),
+            }]}),
+        );
+
+        assert_eq!(
+            edit,
+            json!({ "changes": { uri(&index): [{
+                "range": { "start": { "line": 2, "character": 6 }, "end": { "line": 2, "character": 17 } },
+                "newText": "](docs/guide.md)",
+            }]}})
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_lsp_will_rename_files_edits_links_only_in_referencing_buffer() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+        let guide = root.join("guide.md");
+        write_file(&index, "# Home\n");
+        write_file(&guide, "# Guide\n");
+        let mut client = Client::start(&root);
+        client.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri(&index), "languageId": "markdown", "version": 1,
+                "text": "# Home\n\n[Guide](guide.md) and [[guide]]\n",
+            }}),
+        );
+        assert_eq!(client.diagnostics(), json!([]));
+
+        let edit = client.request(
+            "workspace/willRenameFiles",
+            json!({ "files": [{
+                "oldUri": uri(&guide),
+                "newUri": uri(&root.join("docs/manual.md")),
+            }]}),
+        );
+
+        assert_eq!(
+            edit,
+            json!({ "changes": { uri(&index): [
+                {
+                    "range": { "start": { "line": 2, "character": 6 }, "end": { "line": 2, "character": 17 } },
+                    "newText": "](docs/manual.md)",
+                },
+                {
+                    "range": { "start": { "line": 2, "character": 24 }, "end": { "line": 2, "character": 29 } },
+                    "newText": "manual",
+                },
+            ]}})
+        );
+
+        client.shutdown();
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_lsp_will_rename_files_edits_links_only_in_moved_buffer() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+        let guide = root.join("guide.md");
+        write_file(&index, "# Home\n");
+        write_file(&guide, "# Guide\n");
+        let mut client = Client::start(&root);
+        client.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri(&guide), "languageId": "markdown", "version": 1,
+                "text": "# Guide\n[Home](index.md)\n",
+            }}),
+        );
+        assert_eq!(client.diagnostics(), json!([]));
+
+        let edit = client.request(
+            "workspace/willRenameFiles",
+            json!({ "files": [{
+                "oldUri": uri(&guide),
+                "newUri": uri(&root.join("docs/guide.md")),
+            }]}),
+        );
+
+        assert_eq!(
+            edit,
+            json!({ "changes": { uri(&guide): [{
+                "range": { "start": { "line": 1, "character": 5 }, "end": { "line": 1, "character": 16 } },
+                "newText": "](../index.md)",
+            }]}})
+        );
+
+        client.shutdown();
+    }
+}
@@ -0,0 +1,207 @@
+//! Parse `mdref mv --from` manifests into `(source, destination)` pairs.
+//!
+//! Supported formats, picked by file extension:
+//!
+//! - `.json`: an array of `{"source": ..., "destination": ...}` objects or of
+//!   `[source, destination]` pairs
+//! - `.csv` / `.tsv`: two columns per line, with an optional
+//!   `source,destination` header; blank lines and `#` comments are skipped
+
+use std::{fs, path::Path};
+
+use mdref::{MdrefError, Result};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Pair(String, String),
+    Object {
+        source: String,
+        #[serde(alias = "dest")]
+        destination: String,
+    },
+}
+
+pub fn read_manifest(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = fs::read_to_string(path).map_err(|e| MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("json") => parse_json(path, &content),
+        Some("csv") => parse_delimited(path, &content, ','),
+        Some("tsv") => parse_delimited(path, &content, '\t'),
+        _ => Err(MdrefError::PathValidation {
+            path: path.to_path_buf(),
+            details: "unsupported manifest format; expected .json, .csv or .tsv".to_string(),
+        }),
+    }
+}
+
+fn parse_json(path: &Path, content: &str) -> Result<Vec<(String, String)>> {
+    let entries: Vec<JsonEntry> =
+        serde_json::from_str(content).map_err(|e| MdrefError::PathValidation {
+            path: path.to_path_buf(),
+            details: format!("invalid JSON manifest: {e}"),
+        })?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            JsonEntry::Pair(source, destination)
+            | JsonEntry::Object {
+                source,
+                destination,
+            } => (source, destination),
+        })
+        .collect())
+}
+
+fn parse_delimited(path: &Path, content: &str, delimiter: char) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let fields = split_fields(line, delimiter);
+        let [source, destination] = fields.as_slice() else {
+            return Err(MdrefError::PathValidation {
+                path: path.to_path_buf(),
+                details: format!(
+                    "line {}: expected 2 columns, found {}",
+                    index + 1,
+                    fields.len()
+                ),
+            });
+        };
+
+        let is_header = pairs.is_empty()
+            && source.eq_ignore_ascii_case("source")
+            && (destination.eq_ignore_ascii_case("destination")
+                || destination.eq_ignore_ascii_case("dest"));
+        if !is_header {
+            pairs.push((source.clone(), destination.clone()));
+        }
+    }
+
+    Ok(pairs)
+}
+
+/// Split one line into trimmed fields. Fields wrapped in double quotes may
+/// contain the delimiter, and `""` inside them stands for a literal quote.
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes || field.trim().is_empty() => {
+                in_quotes = !in_quotes;
+                if in_quotes {
+                    field.clear();
+                }
+            }
+            ch if ch == delimiter && !in_quotes => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            ch => field.push(ch),
+        }
+    }
+    fields.push(field.trim().to_string());
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::test_utils::write_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_read_manifest_json_accepts_objects_and_pairs() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("moves.json");
+        write_file(
+            &manifest,
+            r#"[{"source": "a.md", "destination": "b.md"}, ["c.md", "d/c.md"]]"#,
+        );
+
+        let pairs = read_manifest(&manifest).unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("a.md".to_string(), "b.md".to_string()),
+                ("c.md".to_string(), "d/c.md".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_read_manifest_csv_skips_header_and_unquotes_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("moves.csv");
+        write_file(
+            &manifest,
+            "source,destination\n# comment\n\"my, notes.md\", archive/notes.md\r\n\nx.md,y.md\n",
+        );
+
+        let pairs = read_manifest(&manifest).unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("my, notes.md".to_string(), "archive/notes.md".to_string()),
+                ("x.md".to_string(), "y.md".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_read_manifest_tsv_wrong_column_count_reports_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("moves.tsv");
+        write_file(&manifest, "a.md\tb.md\nc.md\n");
+
+        let error = read_manifest(&manifest).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("line 2: expected 2 columns, found 1")
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_read_manifest_unknown_extension_returns_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("moves.txt");
+        write_file(&manifest, "a.md b.md");
+
+        let error = read_manifest(&manifest).unwrap_err();
+
+        assert!(error.to_string().contains("unsupported manifest format"));
+    }
+}
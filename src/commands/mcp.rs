@@ -0,0 +1,579 @@
+//! `mdref mcp`: a Model Context Protocol server speaking JSON-RPC over stdio.
+//!
+//! Every tool answers with the payload the matching command prints with
+//! `--format json`, as `structuredContent` and as a text block, and declares
+//! its schema. Tools that change files only preview unless they are called
+//! with `dry_run: false` and `confirm: true`.
+
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+};
+
+use mdref::{
+    MdrefError, NoopProgress, Result, find_links, find_references, mv, preview_move, rename,
+};
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use super::{JsonReference, json_move_changes, mv::MoveCommandOutput, rename::RenameCommandOutput};
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+const INSTRUCTIONS: &str = "Paths are resolved from the directory the server runs in. \
+`mv` and `rename` preview by default; pass `dry_run: false` and `confirm: true` to move files.";
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+pub fn run(root: Option<String>) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    serve(root, stdin.lock(), &mut stdout)
+}
+
+/// Answer the newline-delimited JSON-RPC messages read from `reader` until it
+/// is closed.
+fn serve<R: BufRead, W: Write>(root: Option<String>, reader: R, writer: &mut W) -> Result<()> {
+    let server = Server {
+        root: root.unwrap_or_else(|| ".".to_string()),
+    };
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => server.handle_message(message),
+            Err(error) => Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                format!("invalid JSON: {error}"),
+            )),
+        };
+        if let Some(reply) = reply {
+            serde_json::to_writer(&mut *writer, &reply).map_err(|error| {
+                MdrefError::SerializationFailed {
+                    details: format!("failed to write MCP message: {error}"),
+                }
+            })?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+struct Server {
+    root: String,
+}
+
+impl Server {
+    /// The reply to `message`, or `None` for notifications and responses.
+    fn handle_message(&self, message: Value) -> Option<Value> {
+        let Value::Object(mut message) = message else {
+            return Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                "expected a JSON-RPC message object".to_string(),
+            ));
+        };
+        let id = message.remove("id")?;
+        let params = message.remove("params").unwrap_or(Value::Null);
+        let method = message.get("method").and_then(Value::as_str)?;
+
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(params),
+            method => Err((METHOD_NOT_FOUND, format!("unsupported method: {method}"))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn call_tool(&self, params: Value) -> RpcResult {
+        let CallToolParams { name, arguments } =
+            serde_json::from_value(params).map_err(invalid_params)?;
+
+        match name.as_str() {
+            "find_references" => call(arguments, |params| self.find_references(params)),
+            "find_links" => call(arguments, find_links_output),
+            "preview_move" => call(arguments, |params| self.preview_move(params)),
+            "mv" => call(arguments, |params| self.mv(params)),
+            "rename" => call(arguments, |params| self.rename(params)),
+            name => Err((INVALID_PARAMS, format!("unknown tool: {name}"))),
+        }
+    }
+
+    fn find_references(&self, params: FindReferencesParams) -> Result<FindReferencesOutput> {
+        let root = params.root.unwrap_or_else(|| self.root.clone());
+        let references = find_references(&params.path, &root, &NoopProgress)?;
+
+        Ok(FindReferencesOutput {
+            operation: "find",
+            target: params.path,
+            references: references.iter().map(JsonReference::from).collect(),
+        })
+    }
+
+    fn preview_move(&self, params: PreviewMoveParams) -> Result<MoveCommandOutput> {
+        self.mv(MoveParams {
+            source: params.source,
+            dest: params.dest,
+            root: params.root,
+            dry_run: true,
+            confirm: false,
+        })
+    }
+
+    fn mv(&self, params: MoveParams) -> Result<MoveCommandOutput> {
+        let dry_run = confirm_write("mv", params.dry_run, params.confirm)?;
+        let root = params.root.unwrap_or_else(|| self.root.clone());
+
+        let preview = preview_move(&params.source, &params.dest, &root, &NoopProgress)?;
+        if !dry_run {
+            mv(&params.source, &params.dest, &root, false, &NoopProgress)?;
+        }
+
+        Ok(MoveCommandOutput {
+            operation: "mv",
+            source: params.source,
+            destination: preview.destination.display().to_string(),
+            root,
+            dry_run,
+            changes: json_move_changes(&preview),
+        })
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<RenameCommandOutput> {
+        let dry_run = confirm_write("rename", params.dry_run, params.confirm)?;
+        let root = params.root.unwrap_or_else(|| self.root.clone());
+        let destination = Path::new(&params.old).with_file_name(&params.new);
+
+        let preview = preview_move(&params.old, &destination, &root, &NoopProgress)?;
+        if !dry_run {
+            rename(&params.old, &params.new, &root, false, &NoopProgress)?;
+        }
+
+        Ok(RenameCommandOutput {
+            operation: "rename",
+            source: params.old,
+            new_name: params.new,
+            destination: preview.destination.display().to_string(),
+            root,
+            dry_run,
+            changes: json_move_changes(&preview),
+        })
+    }
+}
+
+fn find_links_output(params: FindLinksParams) -> Result<FindLinksOutput> {
+    let links = find_links(&params.path)?;
+
+    Ok(FindLinksOutput {
+        operation: "find",
+        target: params.path,
+        links: links.iter().map(JsonReference::from).collect(),
+    })
+}
+
+/// Whether a mutating tool call stays a dry run; writing needs `confirm`.
+fn confirm_write(operation: &str, dry_run: bool, confirm: bool) -> Result<bool> {
+    if !dry_run && !confirm {
+        return Err(MdrefError::ConfirmationRequired {
+            operation: operation.to_string(),
+        });
+    }
+    Ok(dry_run)
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| Some(*version) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": INSTRUCTIONS,
+    })
+}
+
+fn tools() -> Vec<Value> {
+    vec![
+        tool::<FindReferencesParams, FindReferencesOutput>(
+            "find_references",
+            "Find references to a file",
+            "List every local link under the root that points at a file, like `mdref find`.",
+            true,
+        ),
+        tool::<FindLinksParams, FindLinksOutput>(
+            "find_links",
+            "Find links in a file",
+            "List the local links inside a Markdown file.",
+            true,
+        ),
+        tool::<PreviewMoveParams, MoveCommandOutput>(
+            "preview_move",
+            "Preview a move",
+            "List the link rewrites moving a file or directory would make, without changing anything.",
+            true,
+        ),
+        tool::<MoveParams, MoveCommandOutput>(
+            "mv",
+            "Move a file and update references",
+            "Move a file or directory and rewrite the links to and inside it, like `mdref mv`. \
+             Previews unless called with `dry_run: false` and `confirm: true`.",
+            false,
+        ),
+        tool::<RenameParams, RenameCommandOutput>(
+            "rename",
+            "Rename a file and update references",
+            "Rename a file in place and rewrite the links to and inside it, like `mdref rename`. \
+             Previews unless called with `dry_run: false` and `confirm: true`.",
+            false,
+        ),
+    ]
+}
+
+fn tool<P: JsonSchema, O: JsonSchema>(
+    name: &str,
+    title: &str,
+    description: &str,
+    read_only: bool,
+) -> Value {
+    json!({
+        "name": name,
+        "title": title,
+        "description": description,
+        "inputSchema": schema_for!(P),
+        "outputSchema": schema_for!(O),
+        "annotations": {
+            "readOnlyHint": read_only,
+            "destructiveHint": !read_only,
+            "idempotentHint": read_only,
+            "openWorldHint": false,
+        },
+    })
+}
+
+/// Run `tool` on the parsed `arguments` and wrap its outcome in a tool result.
+/// Failures of the tool itself are reported in the result, not as protocol
+/// errors, so the caller can read them.
+fn call<P, O>(arguments: Option<Value>, tool: impl FnOnce(P) -> Result<O>) -> RpcResult
+where
+    P: DeserializeOwned,
+    O: Serialize,
+{
+    let params =
+        serde_json::from_value(arguments.unwrap_or_else(|| json!({}))).map_err(invalid_params)?;
+
+    match tool(params) {
+        Ok(output) => {
+            let output = serde_json::to_value(output)
+                .map_err(|error| (INTERNAL_ERROR, error.to_string()))?;
+            Ok(json!({
+                "content": [{ "type": "text", "text": output.to_string() }],
+                "structuredContent": output,
+                "isError": false,
+            }))
+        }
+        Err(error) => Ok(json!({
+            "content": [{ "type": "text", "text": error.to_string() }],
+            "isError": true,
+        })),
+    }
+}
+
+fn invalid_params(error: serde_json::Error) -> (i64, String) {
+    (INVALID_PARAMS, format!("invalid arguments: {error}"))
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[derive(Deserialize)]
+struct CallToolParams {
+    name: String,
+    arguments: Option<Value>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct FindReferencesParams {
+    /// File whose referrers to find
+    path: String,
+    /// Root directory to search in (default: the server's root)
+    root: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct FindLinksParams {
+    /// Markdown file whose links to list
+    path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct PreviewMoveParams {
+    /// Source path
+    source: String,
+    /// Destination path
+    dest: String,
+    /// Root directory (default: the server's root)
+    root: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct MoveParams {
+    /// Source path
+    source: String,
+    /// Destination path
+    dest: String,
+    /// Root directory (default: the server's root)
+    root: Option<String>,
+    /// Preview changes without modifying any files
+    #[serde(default = "dry_run_default")]
+    dry_run: bool,
+    /// Confirm that files should be moved and rewritten when `dry_run` is false
+    #[serde(default)]
+    confirm: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct RenameParams {
+    /// Old filename
+    old: String,
+    /// New filename
+    new: String,
+    /// Root directory (default: the server's root)
+    root: Option<String>,
+    /// Preview changes without modifying any files
+    #[serde(default = "dry_run_default")]
+    dry_run: bool,
+    /// Confirm that files should be renamed and rewritten when `dry_run` is false
+    #[serde(default)]
+    confirm: bool,
+}
+
+fn dry_run_default() -> bool {
+    true
+}
+
+#[derive(Serialize, JsonSchema)]
+struct FindReferencesOutput {
+    operation: &'static str,
+    target: String,
+    references: Vec<JsonReference>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct FindLinksOutput {
+    operation: &'static str,
+    target: String,
+    links: Vec<JsonReference>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use mdref::test_utils::write_file;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn exchange(root: &Path, messages: &[Value]) -> Vec<Value> {
+        let input: String = messages
+            .iter()
+            .map(|message| format!("{message}\n"))
+            .collect();
+        let mut output = Vec::new();
+        serve(
+            Some(root.to_str().unwrap().to_string()),
+            Cursor::new(input),
+            &mut output,
+        )
+        .unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn call_tool(id: u64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mcp_initialize_and_list_tools_with_schemas() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let replies = exchange(
+            temp_dir.path(),
+            &[
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "initialize",
+                    "params": { "protocolVersion": "2025-03-26", "capabilities": {} },
+                }),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }),
+            ],
+        );
+
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+        let tools = replies[1]["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "find_references",
+                "find_links",
+                "preview_move",
+                "mv",
+                "rename"
+            ]
+        );
+        let mv = &tools[3];
+        assert_eq!(mv["inputSchema"]["properties"]["dry_run"]["default"], true);
+        assert_eq!(mv["inputSchema"]["required"], json!(["source", "dest"]));
+        assert_eq!(mv["annotations"]["readOnlyHint"], false);
+        assert!(
+            mv["outputSchema"]["$defs"]["JsonMoveChange"]["properties"]["replacements"].is_object()
+        );
+        assert_eq!(replies[2]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mcp_mv_previews_until_confirmed() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("guide.md");
+        let dest = root.join("docs/guide.md");
+        write_file(&source, "# Guide\n");
+        write_file(root.join("index.md"), "[Guide](guide.md)\n");
+        let arguments = |extra: Value| {
+            let mut arguments = json!({
+                "source": source.to_str().unwrap(),
+                "dest": dest.to_str().unwrap(),
+            });
+            arguments
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            arguments
+        };
+
+        let replies = exchange(
+            root,
+            &[
+                call_tool(1, "mv", arguments(json!({}))),
+                call_tool(2, "mv", arguments(json!({ "dry_run": false }))),
+            ],
+        );
+
+        let preview = &replies[0]["result"];
+        assert_eq!(preview["isError"], false);
+        assert_eq!(preview["structuredContent"]["dry_run"], true);
+        let change = &preview["structuredContent"]["changes"][0];
+        assert_eq!(change["kind"], "reference_update");
+        assert_eq!(change["replacements"][0]["new_pattern"], "](docs/guide.md)");
+        assert_eq!(
+            preview["content"][0]["text"].as_str().unwrap(),
+            preview["structuredContent"].to_string()
+        );
+        assert_eq!(replies[1]["result"]["isError"], true);
+        assert!(source.exists());
+
+        let replies = exchange(
+            root,
+            &[call_tool(
+                3,
+                "mv",
+                arguments(json!({ "dry_run": false, "confirm": true })),
+            )],
+        );
+
+        assert_eq!(replies[0]["result"]["structuredContent"]["dry_run"], false);
+        assert!(dest.exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](docs/guide.md)\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mcp_reports_tool_and_argument_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let missing = root.join("missing.md");
+
+        let replies = exchange(
+            root,
+            &[
+                call_tool(
+                    1,
+                    "find_links",
+                    json!({ "path": missing.to_str().unwrap() }),
+                ),
+                call_tool(2, "find_links", json!({ "file": "a.md" })),
+                call_tool(3, "delete", json!({})),
+            ],
+        );
+
+        assert_eq!(replies[0]["result"]["isError"], true);
+        assert!(
+            replies[0]["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("missing.md")
+        );
+        assert_eq!(replies[1]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(replies[2]["error"]["code"], INVALID_PARAMS);
+    }
+}
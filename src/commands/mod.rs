@@ -2,15 +2,36 @@ use std::{io::Write, path::Path};
 
 use clap::{Subcommand, ValueEnum};
 use mdref::{
-    MdrefError, Result,
+    GitMode, MdrefError, Reference, RemoveMode, Result,
     core::model::{LinkReplacement, MoveChange, MoveChangeKind, MovePreview},
 };
+use schemars::JsonSchema;
 use serde::Serialize;
 
+use self::{config::ConfigAction, index::IndexAction, plan::PlanInput, recover::RecoverAction};
+
+mod apply;
+mod check;
+pub(crate) mod config;
+mod cp;
 mod find;
+mod fix;
+mod graph;
+mod history;
+mod index;
+mod lsp;
+mod manifest;
+mod mcp;
 mod mv;
+mod orphans;
+mod plan;
 pub(crate) mod progress;
+mod recover;
 mod rename;
+mod rename_heading;
+mod rm;
+mod undo;
+mod watch;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
 pub enum OutputFormat {
@@ -18,12 +39,72 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Output formats for commands that move files.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum MoveOutputFormat {
+    Human,
+    Json,
+    /// Unified diff with rename headers, applicable with `git apply`
+    Diff,
+}
+
+/// Output formats for `graph`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    Graphml,
+    Json,
+}
+
+impl From<GraphFormat> for OutputFormat {
+    fn from(format: GraphFormat) -> Self {
+        match format {
+            GraphFormat::Json => Self::Json,
+            GraphFormat::Dot | GraphFormat::Mermaid | GraphFormat::Graphml => Self::Human,
+        }
+    }
+}
+
+impl From<MoveOutputFormat> for OutputFormat {
+    fn from(format: MoveOutputFormat) -> Self {
+        match format {
+            MoveOutputFormat::Json => Self::Json,
+            MoveOutputFormat::Human | MoveOutputFormat::Diff => Self::Human,
+        }
+    }
+}
+
+/// When `mv --git` moves through git.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum GitWhen {
+    Always,
+    /// Only when the source is inside a git work tree
+    Auto,
+}
+
+impl From<GitWhen> for GitMode {
+    fn from(when: GitWhen) -> Self {
+        match when {
+            GitWhen::Always => Self::Always,
+            GitWhen::Auto => Self::Auto,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Find references to a file
     Find {
         /// The path to find references
-        path: String,
+        #[arg(required_unless_present = "all_assets")]
+        path: Option<String>,
+        /// Instead of one file, report the referrers of every non-Markdown file
+        /// under DIR and list the ones nothing links to
+        #[arg(long, value_name = "DIR", conflicts_with = "path")]
+        all_assets: Option<String>,
         /// Root directory to search in (default: current directory)
         #[arg(short, long)]
         root: Option<String>,
@@ -31,6 +112,96 @@ pub enum Commands {
         #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
         format: OutputFormat,
     },
+    /// Report local links whose target does not exist
+    Check {
+        /// Root directory to check (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Also verify that `#fragment`s match a heading or anchor in the target file
+        #[arg(long)]
+        anchors: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Export the link graph of every file under the root
+    Graph {
+        /// Root directory to scan (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Only keep files inside this directory
+        #[arg(long, value_name = "DIR")]
+        subtree: Option<String>,
+        /// Only keep files at most --depth links away from this file
+        #[arg(long, value_name = "FILE")]
+        around: Option<String>,
+        /// Number of links to follow from --around, in either direction
+        #[arg(long, default_value_t = 1, requires = "around")]
+        depth: usize,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+    /// Report pages nothing links to, unreachable pages, unused assets and dead ends
+    Orphans {
+        /// Root directory to scan (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Page reachability starts from: a file name, or a path relative to the root
+        /// (repeatable; default: README.md and index.md)
+        #[arg(long, value_name = "PAGE")]
+        entrypoint: Vec<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Run a language server over stdio for references, definitions, rename edits
+    /// and broken-link diagnostics
+    Lsp {
+        /// Root directory links resolve against (default: the client's workspace folder)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Also report `#fragment`s that match no heading or anchor in the target file
+        #[arg(long)]
+        anchors: bool,
+    },
+    /// Run a Model Context Protocol server over stdio exposing find, preview, mv and
+    /// rename as tools
+    Mcp {
+        /// Root directory tools use when a call names none (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+    },
+    /// Repair links broken by moves made outside mdref
+    Fix {
+        /// Root directory to repair (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Write the proposed fixes instead of only listing them
+        #[arg(long)]
+        apply: bool,
+        /// Replay the renames git detected since this revision instead of guessing
+        /// (renames must be staged or committed)
+        #[arg(long, value_name = "REV")]
+        from_git: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Watch a directory and fix links when files or directories in it are moved
+    Watch {
+        /// Directory to watch (default: current directory)
+        root: Option<String>,
+        /// Milliseconds without new events before a batch of moves is fixed
+        #[arg(long, value_name = "MS", default_value_t = 300)]
+        debounce: u64,
+        /// Only report the fixes instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Output format; JSON writes one line per batch
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
     /// Rename a file and update references
     Rename {
         /// Old filename
@@ -43,19 +214,83 @@ pub enum Commands {
         /// Preview changes without modifying any files
         #[arg(long)]
         dry_run: bool,
+        /// Re-plan and try again if a file changes while the rename runs
+        #[arg(long)]
+        retry: bool,
         /// Output format
-        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
-        format: OutputFormat,
+        #[arg(long, value_enum, default_value_t = MoveOutputFormat::Human)]
+        format: MoveOutputFormat,
     },
     /// Move a file and update references
     Mv {
+        /// Source path
+        #[arg(required_unless_present = "from")]
+        source: Option<String>,
+        /// Destination path
+        #[arg(required_unless_present = "from")]
+        dest: Option<String>,
+        /// Move every pair listed in a manifest (.json, .csv or .tsv) at once
+        #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["source", "dest"])]
+        from: Option<String>,
+        /// Root directory
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Preview changes without modifying any files
+        #[arg(long)]
+        dry_run: bool,
+        /// Re-plan and try again if a file changes while the move runs
+        #[arg(long)]
+        retry: bool,
+        /// Move with `git mv` and stage the rewritten files; `--git=auto` only inside a git work tree
+        #[arg(
+            long,
+            value_enum,
+            value_name = "WHEN",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "always",
+            conflicts_with = "from"
+        )]
+        git: Option<GitWhen>,
+        /// With --git, move even if the source has uncommitted changes
+        #[arg(long, requires = "git")]
+        force: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = MoveOutputFormat::Human)]
+        format: MoveOutputFormat,
+    },
+    /// Copy a file or directory and update the links inside the copy
+    Cp {
         /// Source path
         source: String,
         /// Destination path
         dest: String,
+        /// Point links between copied files at the copies instead of the originals
+        #[arg(long)]
+        to_copy: bool,
+        /// Preview changes without modifying any files
+        #[arg(long)]
+        dry_run: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Remove a file or directory, refusing while links still point at it
+    Rm {
+        /// Path to remove
+        path: String,
         /// Root directory
         #[arg(short, long)]
         root: Option<String>,
+        /// Replace links to the removed path with their text
+        #[arg(long, conflicts_with_all = ["redirect_to", "force"])]
+        unlink: bool,
+        /// Point links to the removed path at this page instead
+        #[arg(long, value_name = "PAGE", conflicts_with = "force")]
+        redirect_to: Option<String>,
+        /// Remove the path even if links still point at it
+        #[arg(long)]
+        force: bool,
         /// Preview changes without modifying any files
         #[arg(long)]
         dry_run: bool,
@@ -63,6 +298,104 @@ pub enum Commands {
         #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
         format: OutputFormat,
     },
+    /// Plan a move and save it as JSON for review and `mdref apply`
+    Plan {
+        /// Source path
+        #[arg(required_unless_present = "from")]
+        source: Option<String>,
+        /// Destination path
+        #[arg(required_unless_present = "from")]
+        dest: Option<String>,
+        /// Plan every pair listed in a manifest (.json, .csv or .tsv)
+        #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["source", "dest"])]
+        from: Option<String>,
+        /// Write the plan to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Root directory
+        #[arg(short, long)]
+        root: Option<String>,
+    },
+    /// Apply a plan saved by `mdref plan`, refusing if any planned file changed
+    Apply {
+        /// Plan file written by `mdref plan`
+        plan: String,
+        /// Root directory whose undo journal records the change (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Manage the persistent link index under `.mdref/index`
+    Index {
+        /// What to do with the index
+        #[arg(value_enum)]
+        action: IndexAction,
+        /// Root directory to index (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Rename a heading and update links to its anchor
+    RenameHeading {
+        /// File containing the heading
+        file: String,
+        /// Current heading text or anchor slug
+        old: String,
+        /// New heading text
+        new: String,
+        /// Root directory
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Preview changes without modifying any files
+        #[arg(long)]
+        dry_run: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Revert the last recorded move, rename or applied plan
+    Undo {
+        /// Root directory whose undo journal to use (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// List the operations `mdref undo` can revert, newest first
+    History {
+        /// Root directory whose undo journal to list (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Roll an operation interrupted by a crash back or forward
+    Recover {
+        /// What to do with the interrupted operation
+        #[arg(value_enum, default_value_t = RecoverAction::Status)]
+        action: RecoverAction,
+        /// Root directory the interrupted operation ran in (default: current directory)
+        #[arg(short, long)]
+        root: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+    /// Inspect the `.mdref.toml` configuration
+    Config {
+        /// What to do with the configuration
+        #[arg(value_enum)]
+        action: ConfigAction,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
 }
 
 pub struct CommandOutputContext {
@@ -76,7 +409,15 @@ pub struct CommandOutputContext {
     pub dry_run: Option<bool>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+pub struct JsonReference {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub link_text: String,
+}
+
+#[derive(Serialize, JsonSchema)]
 pub struct JsonReplacement {
     pub line: usize,
     pub column: usize,
@@ -84,9 +425,16 @@ pub struct JsonReplacement {
     pub new_pattern: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+pub struct JsonMove {
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Serialize, JsonSchema)]
 pub struct JsonMoveChange {
     pub path: String,
+    /// One of `reference_update`, `moved_file_update`, `heading_update` or `copied_file_update`
     pub kind: &'static str,
     pub replacements: Vec<JsonReplacement>,
 }
@@ -94,25 +442,116 @@ pub struct JsonMoveChange {
 impl Commands {
     pub fn output_context(&self) -> CommandOutputContext {
         match self {
-            Self::Find { path, root, format } => CommandOutputContext {
+            Self::Find {
+                path,
+                all_assets,
+                root,
+                format,
+            } => CommandOutputContext {
                 operation: "find",
                 format: *format,
-                target: Some(path.clone()),
+                target: path.clone().or_else(|| all_assets.clone()),
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Check { root, format, .. } => CommandOutputContext {
+                operation: "check",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Graph {
+                root,
+                around,
+                format,
+                ..
+            } => CommandOutputContext {
+                operation: "graph",
+                format: (*format).into(),
+                target: around.clone(),
                 source: None,
                 destination: None,
                 new_name: None,
                 root: root.clone(),
                 dry_run: None,
             },
+            Self::Orphans { root, format, .. } => CommandOutputContext {
+                operation: "orphans",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Lsp { root, .. } => CommandOutputContext {
+                operation: "lsp",
+                format: OutputFormat::Human,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Mcp { root } => CommandOutputContext {
+                operation: "mcp",
+                format: OutputFormat::Human,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Fix {
+                root,
+                apply,
+                from_git,
+                format,
+            } => CommandOutputContext {
+                operation: "fix",
+                format: *format,
+                target: from_git.clone(),
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: Some(!*apply),
+            },
+            Self::Watch {
+                root,
+                dry_run,
+                format,
+                ..
+            } => CommandOutputContext {
+                operation: "watch",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: Some(*dry_run),
+            },
             Self::Rename {
                 old,
                 new,
                 root,
                 dry_run,
                 format,
+                ..
             } => CommandOutputContext {
                 operation: "rename",
-                format: *format,
+                format: (*format).into(),
                 target: None,
                 source: Some(old.clone()),
                 destination: Some(Path::new(old).with_file_name(new).display().to_string()),
@@ -123,44 +562,321 @@ impl Commands {
             Self::Mv {
                 source,
                 dest,
+                from,
                 root,
                 dry_run,
                 format,
+                ..
             } => CommandOutputContext {
                 operation: "mv",
+                format: (*format).into(),
+                target: from.clone(),
+                source: source.clone(),
+                destination: dest.clone(),
+                new_name: None,
+                root: root.clone(),
+                dry_run: Some(*dry_run),
+            },
+            Self::Cp {
+                source,
+                dest,
+                dry_run,
+                format,
+                ..
+            } => CommandOutputContext {
+                operation: "cp",
                 format: *format,
                 target: None,
                 source: Some(source.clone()),
                 destination: Some(dest.clone()),
                 new_name: None,
+                root: None,
+                dry_run: Some(*dry_run),
+            },
+            Self::Rm {
+                path,
+                root,
+                dry_run,
+                format,
+                ..
+            } => CommandOutputContext {
+                operation: "rm",
+                format: *format,
+                target: Some(path.clone()),
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: Some(*dry_run),
+            },
+            Self::Plan {
+                source,
+                dest,
+                from,
+                root,
+                ..
+            } => CommandOutputContext {
+                operation: "plan",
+                format: OutputFormat::Human,
+                target: from.clone(),
+                source: source.clone(),
+                destination: dest.clone(),
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Apply { plan, root, format } => CommandOutputContext {
+                operation: "apply",
+                format: *format,
+                target: Some(plan.clone()),
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Index { root, format, .. } => CommandOutputContext {
+                operation: "index",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::RenameHeading {
+                file,
+                new,
+                root,
+                dry_run,
+                format,
+                ..
+            } => CommandOutputContext {
+                operation: "rename-heading",
+                format: *format,
+                target: Some(file.clone()),
+                source: None,
+                destination: None,
+                new_name: Some(new.clone()),
                 root: root.clone(),
                 dry_run: Some(*dry_run),
             },
+            Self::Undo { root, format } => CommandOutputContext {
+                operation: "undo",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::History { root, format } => CommandOutputContext {
+                operation: "history",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Recover { root, format, .. } => CommandOutputContext {
+                operation: "recover",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: root.clone(),
+                dry_run: None,
+            },
+            Self::Config { format, .. } => CommandOutputContext {
+                operation: "config",
+                format: *format,
+                target: None,
+                source: None,
+                destination: None,
+                new_name: None,
+                root: None,
+                dry_run: None,
+            },
         }
     }
 }
 
 pub fn handle_command(command: Commands, progress: bool) -> Result<()> {
+    if !matches!(command, Commands::Recover { .. }) {
+        let root = command.output_context().root;
+        recover::warn_if_interrupted(root.as_deref().unwrap_or("."));
+    }
+
     match command {
         Commands::Find {
-            path: filepath,
+            path,
+            all_assets,
+            root,
+            format,
+        } => match (path, all_assets) {
+            (_, Some(dir)) => find::run_assets(dir, root, progress, format),
+            (Some(path), None) => find::run(path, root, progress, format),
+            (None, None) => unreachable!("clap requires a path or --all-assets"),
+        },
+        Commands::Check {
+            root,
+            anchors,
+            format,
+        } => check::run(root, anchors, progress, format),
+        Commands::Graph {
+            root,
+            subtree,
+            around,
+            depth,
+            format,
+        } => graph::run(root, subtree, around, depth, progress, format),
+        Commands::Orphans {
+            root,
+            entrypoint,
+            format,
+        } => orphans::run(root, entrypoint, progress, format),
+        Commands::Lsp { root, anchors } => lsp::run(root, anchors),
+        Commands::Mcp { root } => mcp::run(root),
+        Commands::Fix {
+            root,
+            apply,
+            from_git,
+            format,
+        } => fix::run(root, apply, from_git, progress, format),
+        Commands::Watch {
             root,
+            debounce,
+            dry_run,
             format,
-        } => find::run(filepath, root, progress, format),
+        } => watch::run(root, debounce, dry_run, format),
         Commands::Rename {
             old,
             new,
             root,
             dry_run,
+            retry,
             format,
-        } => rename::run(old, new, root, dry_run, progress, format),
+        } => rename::run(old, new, root, dry_run, retry, progress, format),
         Commands::Mv {
             source,
             dest,
+            from,
+            root,
+            dry_run,
+            retry,
+            git,
+            force,
+            format,
+        } => match (from, source, dest) {
+            (Some(manifest), _, _) => {
+                mv::run_manifest(manifest, root, dry_run, retry, progress, format)
+            }
+            (None, Some(source), Some(dest)) => mv::run(
+                source,
+                dest,
+                root,
+                dry_run,
+                retry,
+                git.map_or(GitMode::Off, GitMode::from),
+                force,
+                progress,
+                format,
+            ),
+            _ => Err(MdrefError::PathValidation {
+                path: Default::default(),
+                details: "mv needs SOURCE and DEST, or --from MANIFEST".to_string(),
+            }),
+        },
+        Commands::Cp {
+            source,
+            dest,
+            to_copy,
+            dry_run,
+            format,
+        } => cp::run(source, dest, to_copy, dry_run, format),
+        Commands::Rm {
+            path,
+            root,
+            unlink,
+            redirect_to,
+            force,
+            dry_run,
+            format,
+        } => {
+            let mode = match (unlink, redirect_to, force) {
+                (true, _, _) => RemoveMode::Unlink,
+                (_, Some(page), _) => RemoveMode::RedirectTo(page.into()),
+                (_, _, true) => RemoveMode::Force,
+                _ => RemoveMode::Refuse,
+            };
+            rm::run(path, root, mode, dry_run, progress, format)
+        }
+        Commands::Plan {
+            source,
+            dest,
+            from,
+            output,
+            root,
+        } => {
+            let input = match (from, source, dest) {
+                (Some(manifest), _, _) => PlanInput::Manifest(manifest),
+                (None, Some(source), Some(dest)) => PlanInput::Single { source, dest },
+                _ => {
+                    return Err(MdrefError::PathValidation {
+                        path: Default::default(),
+                        details: "plan needs SOURCE and DEST, or --from MANIFEST".to_string(),
+                    });
+                }
+            };
+            plan::run(input, output, root, progress)
+        }
+        Commands::Apply { plan, root, format } => apply::run(plan, root, format),
+        Commands::Index {
+            action,
+            root,
+            format,
+        } => index::run(action, root, progress, format),
+        Commands::RenameHeading {
+            file,
+            old,
+            new,
             root,
             dry_run,
             format,
-        } => mv::run(source, dest, root, dry_run, progress, format),
+        } => rename_heading::run(file, old, new, root, dry_run, progress, format),
+        Commands::Undo { root, format } => undo::run(root, format),
+        Commands::History { root, format } => history::run(root, format),
+        Commands::Recover {
+            action,
+            root,
+            format,
+        } => recover::run(action, root, format),
+        Commands::Config { action, format } => config::run(action, format),
+    }
+}
+
+/// Attempts `--retry` makes before a concurrent edit is reported.
+const RETRY_ATTEMPTS: usize = 3;
+
+/// Run `operation`; with `retry`, re-run it from scratch while it fails
+/// because a file changed between scanning and writing.
+pub fn with_retry<T>(retry: bool, mut operation: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match operation() {
+            Err(MdrefError::ConcurrentModification { .. }) if retry && attempt < RETRY_ATTEMPTS => {
+                attempt += 1;
+                eprintln!(
+                    "Files changed during the scan; re-planning (attempt {attempt} of {RETRY_ATTEMPTS})"
+                );
+            }
+            result => return result,
+        }
     }
 }
 
@@ -175,22 +891,30 @@ pub fn write_json_output<W: Write, T: Serialize>(writer: &mut W, payload: &T) ->
 }
 
 pub fn write_move_preview_human<W: Write>(preview: &MovePreview, writer: &mut W) -> Result<()> {
-    writeln!(
-        writer,
-        "[dry-run] Would move: {} -> {}",
-        preview.source.display(),
-        preview.destination.display()
-    )?;
-
-    if preview.changes.is_empty() {
+    for (source, destination) in &preview.moves {
+        writeln!(
+            writer,
+            "[dry-run] Would move: {} -> {}",
+            source.display(),
+            destination.display()
+        )?;
+    }
+
+    write_move_changes_human(&preview.changes, writer)
+}
+
+pub fn write_move_changes_human<W: Write>(changes: &[MoveChange], writer: &mut W) -> Result<()> {
+    if changes.is_empty() {
         writeln!(writer, "[dry-run] No references to update.")?;
         return Ok(());
     }
 
-    for change in &preview.changes {
+    for change in changes {
         let label = match change.kind {
             MoveChangeKind::MovedFileUpdate => "Would update links in moved file",
             MoveChangeKind::ReferenceUpdate => "Would update reference in",
+            MoveChangeKind::HeadingUpdate => "Would update heading in",
+            MoveChangeKind::CopiedFileUpdate => "Would update links in copy",
         };
         writeln!(writer, "[dry-run] {} {}:", label, change.path.display())?;
         for replacement in &change.replacements {
@@ -209,6 +933,17 @@ pub fn json_move_changes(preview: &MovePreview) -> Vec<JsonMoveChange> {
     preview.changes.iter().map(JsonMoveChange::from).collect()
 }
 
+pub fn json_moves(preview: &MovePreview) -> Vec<JsonMove> {
+    preview
+        .moves
+        .iter()
+        .map(|(source, destination)| JsonMove {
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+        })
+        .collect()
+}
+
 impl From<&MoveChange> for JsonMoveChange {
     fn from(change: &MoveChange) -> Self {
         Self {
@@ -216,6 +951,8 @@ impl From<&MoveChange> for JsonMoveChange {
             kind: match change.kind {
                 MoveChangeKind::ReferenceUpdate => "reference_update",
                 MoveChangeKind::MovedFileUpdate => "moved_file_update",
+                MoveChangeKind::HeadingUpdate => "heading_update",
+                MoveChangeKind::CopiedFileUpdate => "copied_file_update",
             },
             replacements: change
                 .replacements
@@ -226,6 +963,17 @@ impl From<&MoveChange> for JsonMoveChange {
     }
 }
 
+impl From<&Reference> for JsonReference {
+    fn from(reference: &Reference) -> Self {
+        Self {
+            path: reference.path.display().to_string(),
+            line: reference.line,
+            column: reference.column,
+            link_text: reference.link_text.clone(),
+        }
+    }
+}
+
 impl From<&LinkReplacement> for JsonReplacement {
     fn from(replacement: &LinkReplacement) -> Self {
         Self {
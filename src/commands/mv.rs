@@ -1,19 +1,25 @@
-use std::io::Write;
+use std::{io::Write, path::Path};
 
-use mdref::{NoopProgress, Result, mv, preview_move};
+use mdref::{GitMode, NoopProgress, Result, mv_many, mv_with_git, preview_move, preview_move_many};
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::commands::{
-    OutputFormat, json_move_changes, progress::Spinner, write_json_output, write_move_preview_human,
+    MoveOutputFormat, json_move_changes, json_moves, manifest::read_manifest, progress::Spinner,
+    with_retry, write_json_output, write_move_preview_human,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     source: String,
     dest: String,
     root: Option<String>,
     dry_run: bool,
+    retry: bool,
+    git: GitMode,
+    force: bool,
     show_progress: bool,
-    format: OutputFormat,
+    format: MoveOutputFormat,
 ) -> Result<()> {
     let mut stdout = std::io::stdout();
     run_with_writer(
@@ -21,45 +27,65 @@ pub fn run(
         dest,
         root,
         dry_run,
+        retry,
+        git,
+        force,
         show_progress,
         format,
         &mut stdout,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_with_writer<W: Write>(
     source: String,
     dest: String,
     root: Option<String>,
     dry_run: bool,
+    retry: bool,
+    git: GitMode,
+    force: bool,
     show_progress: bool,
-    format: OutputFormat,
+    format: MoveOutputFormat,
     writer: &mut W,
 ) -> Result<()> {
     let root = root.unwrap_or_else(|| ".".to_string());
 
     let spinner = Spinner::new(show_progress && !dry_run);
+    let mv = || {
+        mv_with_git(
+            &source,
+            &dest,
+            &root,
+            git,
+            force,
+            false,
+            spinner.as_reporter(),
+        )
+    };
 
     match format {
-        OutputFormat::Human => {
+        MoveOutputFormat::Human => {
             if dry_run {
                 let preview = preview_move(&source, &dest, &root, &NoopProgress)?;
                 return write_move_preview_human(&preview, writer);
             }
 
             writeln!(writer, "Move {source} -> {dest} in {root}")?;
-            let result = mv(&source, &dest, &root, false, spinner.as_reporter());
+            let result = with_retry(retry, mv);
 
             spinner.finish();
 
             result
         }
-        OutputFormat::Json => {
-            let preview = preview_move(&source, &dest, &root, &NoopProgress)?;
-
-            if !dry_run {
-                mv(&source, &dest, &root, false, spinner.as_reporter())?;
-            }
+        MoveOutputFormat::Json => {
+            let preview = with_retry(retry, || {
+                let preview = preview_move(&source, &dest, &root, &NoopProgress)?;
+                if !dry_run {
+                    mv()?;
+                }
+                Ok(preview)
+            })?;
 
             spinner.finish();
 
@@ -74,19 +100,138 @@ fn run_with_writer<W: Write>(
 
             write_json_output(writer, &payload)
         }
+        MoveOutputFormat::Diff => {
+            let diff = with_retry(retry, || {
+                let preview = preview_move(&source, &dest, &root, &NoopProgress)?;
+                let diff = preview.to_unified_diff(&root)?;
+                if !dry_run {
+                    mv()?;
+                }
+                Ok(diff)
+            })?;
+
+            spinner.finish();
+
+            write!(writer, "{diff}")?;
+            Ok(())
+        }
+    }
+}
+
+pub fn run_manifest(
+    manifest: String,
+    root: Option<String>,
+    dry_run: bool,
+    retry: bool,
+    show_progress: bool,
+    format: MoveOutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_manifest_with_writer(
+        manifest,
+        root,
+        dry_run,
+        retry,
+        show_progress,
+        format,
+        &mut stdout,
+    )
+}
+
+fn run_manifest_with_writer<W: Write>(
+    manifest: String,
+    root: Option<String>,
+    dry_run: bool,
+    retry: bool,
+    show_progress: bool,
+    format: MoveOutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+    let moves = read_manifest(Path::new(&manifest))?;
+
+    let spinner = Spinner::new(show_progress && !dry_run);
+
+    match format {
+        MoveOutputFormat::Human => {
+            if dry_run {
+                let preview = preview_move_many(&moves, &root, &NoopProgress)?;
+                return write_move_preview_human(&preview, writer);
+            }
+
+            writeln!(
+                writer,
+                "Move {} paths from {manifest} in {root}",
+                moves.len()
+            )?;
+            let result = with_retry(retry, || {
+                mv_many(&moves, &root, false, spinner.as_reporter())
+            });
+
+            spinner.finish();
+
+            result
+        }
+        MoveOutputFormat::Json => {
+            let preview = with_retry(retry, || {
+                let preview = preview_move_many(&moves, &root, &NoopProgress)?;
+                if !dry_run {
+                    mv_many(&moves, &root, false, spinner.as_reporter())?;
+                }
+                Ok(preview)
+            })?;
+
+            spinner.finish();
+
+            let payload = ManifestMoveCommandOutput {
+                operation: "mv",
+                manifest,
+                root,
+                dry_run,
+                moves: json_moves(&preview),
+                changes: json_move_changes(&preview),
+            };
+
+            write_json_output(writer, &payload)
+        }
+        MoveOutputFormat::Diff => {
+            let diff = with_retry(retry, || {
+                let preview = preview_move_many(&moves, &root, &NoopProgress)?;
+                let diff = preview.to_unified_diff(&root)?;
+                if !dry_run {
+                    mv_many(&moves, &root, false, spinner.as_reporter())?;
+                }
+                Ok(diff)
+            })?;
+
+            spinner.finish();
+
+            write!(writer, "{diff}")?;
+            Ok(())
+        }
     }
 }
 
 #[derive(Serialize)]
-struct MoveCommandOutput {
+struct ManifestMoveCommandOutput {
     operation: &'static str,
-    source: String,
-    destination: String,
+    manifest: String,
     root: String,
     dry_run: bool,
+    moves: Vec<crate::commands::JsonMove>,
     changes: Vec<crate::commands::JsonMoveChange>,
 }
 
+#[derive(Serialize, JsonSchema)]
+pub(super) struct MoveCommandOutput {
+    pub(super) operation: &'static str,
+    pub(super) source: String,
+    pub(super) destination: String,
+    pub(super) root: String,
+    pub(super) dry_run: bool,
+    pub(super) changes: Vec<crate::commands::JsonMoveChange>,
+}
+
 #[cfg(test)]
 mod tests {
     use mdref::test_utils::write_file;
@@ -94,7 +239,7 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
-    use crate::commands::OutputFormat;
+    use crate::commands::MoveOutputFormat;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -112,7 +257,10 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             false,
             false,
-            OutputFormat::Human,
+            GitMode::Off,
+            false,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap();
@@ -141,7 +289,10 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             true,
             false,
-            OutputFormat::Human,
+            GitMode::Off,
+            false,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap();
@@ -165,7 +316,10 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             false,
             false,
-            OutputFormat::Human,
+            GitMode::Off,
+            false,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap_err();
@@ -191,7 +345,10 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             true,
             false,
-            OutputFormat::Json,
+            GitMode::Off,
+            false,
+            false,
+            MoveOutputFormat::Json,
             &mut output,
         )
         .unwrap();
@@ -207,4 +364,168 @@ mod tests {
         assert_eq!(changes[0]["kind"], "reference_update");
         assert_eq!(changes[0]["replacements"].as_array().unwrap().len(), 1);
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mv_manifest_command_moves_all_pairs_and_prints_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("b.md"), "# B");
+        write_file(root.join("index.md"), "[A](a.md) [B](b.md)");
+        let manifest = root.join("moves.tsv");
+        write_file(
+            &manifest,
+            &format!(
+                "{}\t{}\n{}\t{}\n",
+                root.join("a.md").display(),
+                root.join("notes/a.md").display(),
+                root.join("b.md").display(),
+                root.join("notes/b.md").display()
+            ),
+        );
+
+        let mut output = Vec::new();
+        run_manifest_with_writer(
+            manifest.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            false,
+            false,
+            MoveOutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Move 2 paths from"));
+        assert!(root.join("notes/a.md").exists());
+        assert!(root.join("notes/b.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("index.md")).unwrap(),
+            "[A](notes/a.md) [B](notes/b.md)"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mv_manifest_command_writes_json_payload_for_dry_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("a.md");
+        let target = root.join("b.md");
+        write_file(&source, "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        let manifest = root.join("moves.json");
+        write_file(
+            &manifest,
+            &serde_json::json!([[source.to_str().unwrap(), target.to_str().unwrap()]]).to_string(),
+        );
+
+        let mut output = Vec::new();
+        run_manifest_with_writer(
+            manifest.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            false,
+            false,
+            MoveOutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "mv");
+        assert_eq!(payload["dry_run"], true);
+        assert_eq!(payload["moves"][0]["source"], source.to_str().unwrap());
+        assert_eq!(payload["moves"][0]["destination"], target.to_str().unwrap());
+        assert_eq!(payload["changes"].as_array().unwrap().len(), 1);
+        assert!(source.exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_mv_command_diff_format_dry_run_prints_patch_without_moving() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("source.md");
+        write_file(&source, "# Source");
+        write_file(root.join("index.md"), "See [Source](source.md)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            source.to_str().unwrap().to_string(),
+            root.join("docs/source.md").to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            false,
+            GitMode::Off,
+            false,
+            false,
+            MoveOutputFormat::Diff,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "diff --git a/source.md b/docs/source.md\n\
+             rename from source.md\n\
+             rename to docs/source.md\n\
+             diff --git a/index.md b/index.md\n\
+             --- a/index.md\n\
+             +++ b/index.md\n\
+             @@ -1,1 +1,1 @@\n\
+             -See [Source](source.md)\n\
+             +See [Source](docs/source.md)\n"
+        );
+        assert!(source.exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_with_retry_reruns_after_concurrent_modification() {
+        let mut attempts = 0;
+        let result = with_retry(true, || {
+            attempts += 1;
+            if attempts < 2 {
+                return Err(mdref::MdrefError::ConcurrentModification {
+                    paths: vec!["index.md".into()],
+                });
+            }
+            Ok(attempts)
+        });
+
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_with_retry_gives_up_after_last_attempt() {
+        let mut attempts = 0;
+        let result: Result<()> = with_retry(true, || {
+            attempts += 1;
+            Err(mdref::MdrefError::ConcurrentModification {
+                paths: vec!["index.md".into()],
+            })
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_with_retry_disabled_runs_once() {
+        let mut attempts = 0;
+        let result: Result<()> = with_retry(false, || {
+            attempts += 1;
+            Err(mdref::MdrefError::ConcurrentModification {
+                paths: vec!["index.md".into()],
+            })
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
 }
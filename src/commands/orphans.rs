@@ -0,0 +1,178 @@
+use std::{io::Write, path::PathBuf};
+
+use mdref::{DEFAULT_ENTRYPOINTS, OrphanReport, Result, find_orphans};
+use serde::Serialize;
+
+use super::{OutputFormat, progress::Spinner, write_json_output};
+
+pub fn run(
+    root: Option<String>,
+    entrypoints: Vec<String>,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(root, entrypoints, show_progress, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    mut entrypoints: Vec<String>,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+    if entrypoints.is_empty() {
+        entrypoints = DEFAULT_ENTRYPOINTS.iter().map(|s| s.to_string()).collect();
+    }
+
+    let spinner = Spinner::new(show_progress);
+    let report = find_orphans(&root, &entrypoints, spinner.as_reporter());
+    spinner.finish();
+    let report = report?;
+
+    match format {
+        OutputFormat::Human => write_human_output(&root, &entrypoints, &report, writer),
+        OutputFormat::Json => {
+            let payload = OrphansOutput {
+                operation: "orphans",
+                root: &root,
+                entrypoints: display_all(&report.entrypoints),
+                orphans: display_all(&report.orphans),
+                unreachable: report
+                    .unreachable
+                    .iter()
+                    .map(|group| display_all(group))
+                    .collect(),
+                unused_assets: display_all(&report.unused_assets),
+                dead_ends: display_all(&report.dead_ends),
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+fn write_human_output<W: Write>(
+    root: &str,
+    entrypoints: &[String],
+    report: &OrphanReport,
+    writer: &mut W,
+) -> Result<()> {
+    if report.entrypoints.is_empty() {
+        writeln!(
+            writer,
+            "No entrypoint found in {root} (looked for {})",
+            entrypoints.join(", ")
+        )?;
+    }
+    if report.is_empty() {
+        writeln!(writer, "No orphans found in {root}")?;
+        return Ok(());
+    }
+
+    let sections = [
+        ("Orphan pages (nothing links to them):", &report.orphans),
+        ("Unused assets:", &report.unused_assets),
+        ("Dead-end pages (link to no other page):", &report.dead_ends),
+    ];
+    for (title, paths) in sections {
+        if paths.is_empty() {
+            continue;
+        }
+        writeln!(writer, "{title}")?;
+        for path in paths {
+            writeln!(writer, "  {}", path.display())?;
+        }
+    }
+
+    if !report.unreachable.is_empty() {
+        writeln!(writer, "Unreachable from the entrypoints:")?;
+        for group in &report.unreachable {
+            writeln!(writer, "  {}", display_all(group).join(", "))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn display_all(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect()
+}
+
+#[derive(Serialize)]
+struct OrphansOutput<'a> {
+    operation: &'static str,
+    root: &'a str,
+    entrypoints: Vec<String>,
+    orphans: Vec<String>,
+    unreachable: Vec<Vec<String>>,
+    unused_assets: Vec<String>,
+    dead_ends: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_orphans_command_prints_each_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("README.md"), "[Guide](guide.md)\n");
+        write_file(root.join("guide.md"), "[Home](README.md)\n");
+        write_file(root.join("old.md"), "# Old\n");
+        write_file(root.join("unused.png"), "png");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            Vec::new(),
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Orphan pages (nothing links to them):\n  old.md\n\
+             Unused assets:\n  unused.png\n\
+             Dead-end pages (link to no other page):\n  old.md\n\
+             Unreachable from the entrypoints:\n  old.md\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_orphans_command_writes_json_with_custom_entrypoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("docs/start.md"), "[Next](next.md)\n");
+        write_file(root.join("docs/next.md"), "[Start](start.md)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            vec!["docs/start.md".to_string()],
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "orphans");
+        assert_eq!(payload["entrypoints"][0], "docs/start.md");
+        assert!(payload["orphans"].as_array().unwrap().is_empty());
+        assert!(payload["unreachable"].as_array().unwrap().is_empty());
+    }
+}
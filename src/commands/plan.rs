@@ -0,0 +1,139 @@
+use std::{fs, io::Write, path::Path};
+
+use mdref::{MdrefError, MovePreview, Result, preview_move, preview_move_many};
+
+use super::{manifest::read_manifest, progress::Spinner, write_json_output};
+
+/// Where to find the moves to plan: one `source -> dest` pair or a manifest.
+pub enum PlanInput {
+    Single { source: String, dest: String },
+    Manifest(String),
+}
+
+pub fn run(
+    input: PlanInput,
+    output: Option<String>,
+    root: Option<String>,
+    show_progress: bool,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(input, output, root, show_progress, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    input: PlanInput,
+    output: Option<String>,
+    root: Option<String>,
+    show_progress: bool,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+
+    let spinner = Spinner::new(show_progress);
+    let preview = match input {
+        PlanInput::Single { source, dest } => {
+            preview_move(&source, &dest, &root, spinner.as_reporter())
+        }
+        PlanInput::Manifest(manifest) => {
+            let moves = read_manifest(Path::new(&manifest))?;
+            preview_move_many(&moves, &root, spinner.as_reporter())
+        }
+    };
+    spinner.finish();
+    let preview = preview?.into_absolute();
+
+    let Some(output) = output else {
+        return write_json_output(writer, &preview);
+    };
+
+    let mut file = Vec::new();
+    write_json_output(&mut file, &preview)?;
+    fs::write(&output, file).map_err(|e| MdrefError::IoWrite {
+        path: output.clone().into(),
+        source: e,
+    })?;
+
+    write_summary(&preview, &output, writer)
+}
+
+fn write_summary<W: Write>(preview: &MovePreview, output: &str, writer: &mut W) -> Result<()> {
+    let replacements: usize = preview
+        .changes
+        .iter()
+        .map(|change| change.replacements.len())
+        .sum();
+    writeln!(
+        writer,
+        "Planned {} move(s) and {} replacement(s) in {} file(s); wrote {output}",
+        preview.moves.len(),
+        replacements,
+        preview.changes.len()
+    )?;
+    writeln!(writer, "Review it, then run: mdref apply {output}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_plan_command_writes_plan_file_and_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("a.md");
+        write_file(&source, "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        let plan_path = root.join("plan.json");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            PlanInput::Single {
+                source: source.to_str().unwrap().to_string(),
+                dest: root.join("b.md").to_str().unwrap().to_string(),
+            },
+            Some(plan_path.to_str().unwrap().to_string()),
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Planned 1 move(s) and 1 replacement(s) in 1 file(s)"));
+        let plan: Value = serde_json::from_str(&fs::read_to_string(&plan_path).unwrap()).unwrap();
+        assert_eq!(plan["changes"][0]["kind"], "reference_update");
+        assert_eq!(plan["file_hashes"].as_object().unwrap().len(), 2);
+        assert!(source.exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_plan_command_without_output_prints_plan_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let source = root.join("a.md");
+        write_file(&source, "# A");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            PlanInput::Single {
+                source: source.to_str().unwrap().to_string(),
+                dest: root.join("b.md").to_str().unwrap().to_string(),
+            },
+            None,
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            &mut output,
+        )
+        .unwrap();
+
+        let plan: MovePreview = serde_json::from_slice(&output).unwrap();
+        assert_eq!(plan.moves, vec![(source, root.join("b.md"))]);
+    }
+}
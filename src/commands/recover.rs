@@ -0,0 +1,205 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use mdref::{InterruptedOperation, RecoveryAction, Result, interrupted_operation, recover};
+use serde::Serialize;
+
+use super::{JsonMove, OutputFormat, write_json_output};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum RecoverAction {
+    /// Describe the interrupted operation, if any
+    Status,
+    /// Restore every file to its state before the operation
+    Back,
+    /// Finish the operation as planned
+    Forward,
+}
+
+impl RecoverAction {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Back => "back",
+            Self::Forward => "forward",
+        }
+    }
+}
+
+pub fn run(action: RecoverAction, root: Option<String>, format: OutputFormat) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(action, root, format, &mut stdout)
+}
+
+/// Print a warning to stderr when an interrupted operation is waiting under
+/// `root`. Errors are ignored: the command that follows reports them.
+pub fn warn_if_interrupted(root: &str) {
+    if let Ok(Some(interrupted)) = interrupted_operation(root) {
+        eprintln!(
+            "Warning: an interrupted {} was found in '{}'; run `mdref recover` to roll it forward or back",
+            interrupted.operation, root
+        );
+    }
+}
+
+fn run_with_writer<W: Write>(
+    action: RecoverAction,
+    root: Option<String>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+
+    let interrupted = match action {
+        RecoverAction::Status => interrupted_operation(&root)?,
+        RecoverAction::Back => Some(recover(&root, RecoveryAction::RollBack)?),
+        RecoverAction::Forward => Some(recover(&root, RecoveryAction::RollForward)?),
+    };
+
+    match format {
+        OutputFormat::Human => write_human_output(action, &root, interrupted.as_ref(), writer),
+        OutputFormat::Json => {
+            let payload = RecoverCommandOutput {
+                operation: "recover",
+                action: action.name(),
+                root: &root,
+                interrupted: interrupted.as_ref().map(JsonInterruptedOperation::from),
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+fn write_human_output<W: Write>(
+    action: RecoverAction,
+    root: &str,
+    interrupted: Option<&InterruptedOperation>,
+    writer: &mut W,
+) -> Result<()> {
+    let Some(interrupted) = interrupted else {
+        writeln!(writer, "No interrupted operation in {root}")?;
+        return Ok(());
+    };
+
+    match action {
+        RecoverAction::Status => {
+            writeln!(
+                writer,
+                "Interrupted {}: {} of {} move(s) started, {} file(s) to rewrite",
+                interrupted.operation,
+                interrupted.started_moves,
+                interrupted.moves.len(),
+                interrupted.files.len()
+            )?;
+            for (source, destination) in &interrupted.moves {
+                writeln!(
+                    writer,
+                    "    {} -> {}",
+                    source.display(),
+                    destination.display()
+                )?;
+            }
+            writeln!(
+                writer,
+                "Run `mdref recover back` to undo it or `mdref recover forward` to finish it."
+            )?;
+        }
+        RecoverAction::Back => {
+            writeln!(writer, "Rolled back interrupted {}", interrupted.operation)?;
+        }
+        RecoverAction::Forward => {
+            writeln!(
+                writer,
+                "Rolled forward interrupted {}",
+                interrupted.operation
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct RecoverCommandOutput<'a> {
+    operation: &'static str,
+    action: &'static str,
+    root: &'a str,
+    interrupted: Option<JsonInterruptedOperation>,
+}
+
+#[derive(Serialize)]
+struct JsonInterruptedOperation {
+    operation: String,
+    moves: Vec<JsonMove>,
+    started_moves: usize,
+    files: Vec<String>,
+}
+
+impl From<&InterruptedOperation> for JsonInterruptedOperation {
+    fn from(interrupted: &InterruptedOperation) -> Self {
+        Self {
+            operation: interrupted.operation.clone(),
+            moves: interrupted
+                .moves
+                .iter()
+                .map(|(source, destination)| JsonMove {
+                    source: source.display().to_string(),
+                    destination: destination.display().to_string(),
+                })
+                .collect(),
+            started_moves: interrupted.started_moves,
+            files: interrupted
+                .files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_recover_command_status_without_log_reports_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_str().unwrap().to_string();
+
+        let mut output = Vec::new();
+        run_with_writer(
+            RecoverAction::Status,
+            Some(root.clone()),
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("No interrupted operation in {root}\n")
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_recover_command_back_without_log_returns_error() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut output = Vec::new();
+        let error = run_with_writer(
+            RecoverAction::Back,
+            Some(temp_dir.path().to_str().unwrap().to_string()),
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("no interrupted operation"));
+        assert!(serde_json::from_slice::<Value>(&output).is_err());
+    }
+}
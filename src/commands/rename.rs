@@ -1,10 +1,12 @@
 use std::io::Write;
 
 use mdref::{NoopProgress, Result, preview_move, rename};
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::commands::{
-    OutputFormat, json_move_changes, progress::Spinner, write_json_output, write_move_preview_human,
+    MoveOutputFormat, json_move_changes, progress::Spinner, with_retry, write_json_output,
+    write_move_preview_human,
 };
 
 pub fn run(
@@ -12,20 +14,32 @@ pub fn run(
     new: String,
     root: Option<String>,
     dry_run: bool,
+    retry: bool,
     show_progress: bool,
-    format: OutputFormat,
+    format: MoveOutputFormat,
 ) -> Result<()> {
     let mut stdout = std::io::stdout();
-    run_with_writer(old, new, root, dry_run, show_progress, format, &mut stdout)
+    run_with_writer(
+        old,
+        new,
+        root,
+        dry_run,
+        retry,
+        show_progress,
+        format,
+        &mut stdout,
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_with_writer<W: Write>(
     old: String,
     new: String,
     root: Option<String>,
     dry_run: bool,
+    retry: bool,
     show_progress: bool,
-    format: OutputFormat,
+    format: MoveOutputFormat,
     writer: &mut W,
 ) -> Result<()> {
     let root_path = root.unwrap_or_else(|| ".".to_string());
@@ -34,25 +48,29 @@ fn run_with_writer<W: Write>(
     let spinner = Spinner::new(show_progress && !dry_run);
 
     match format {
-        OutputFormat::Human => {
+        MoveOutputFormat::Human => {
             if dry_run {
                 let preview = preview_move(&old, &destination, &root_path, &NoopProgress)?;
                 return write_move_preview_human(&preview, writer);
             }
 
             writeln!(writer, "Rename {old} -> {new} in {root_path}")?;
-            let result = rename(&old, &new, &root_path, false, spinner.as_reporter());
+            let result = with_retry(retry, || {
+                rename(&old, &new, &root_path, false, spinner.as_reporter())
+            });
 
             spinner.finish();
 
             result
         }
-        OutputFormat::Json => {
-            let preview = preview_move(&old, &destination, &root_path, &NoopProgress)?;
-
-            if !dry_run {
-                rename(&old, &new, &root_path, false, spinner.as_reporter())?;
-            }
+        MoveOutputFormat::Json => {
+            let preview = with_retry(retry, || {
+                let preview = preview_move(&old, &destination, &root_path, &NoopProgress)?;
+                if !dry_run {
+                    rename(&old, &new, &root_path, false, spinner.as_reporter())?;
+                }
+                Ok(preview)
+            })?;
 
             spinner.finish();
 
@@ -68,18 +86,33 @@ fn run_with_writer<W: Write>(
 
             write_json_output(writer, &payload)
         }
+        MoveOutputFormat::Diff => {
+            let diff = with_retry(retry, || {
+                let preview = preview_move(&old, &destination, &root_path, &NoopProgress)?;
+                let diff = preview.to_unified_diff(&root_path)?;
+                if !dry_run {
+                    rename(&old, &new, &root_path, false, spinner.as_reporter())?;
+                }
+                Ok(diff)
+            })?;
+
+            spinner.finish();
+
+            write!(writer, "{diff}")?;
+            Ok(())
+        }
     }
 }
 
-#[derive(Serialize)]
-struct RenameCommandOutput {
-    operation: &'static str,
-    source: String,
-    new_name: String,
-    destination: String,
-    root: String,
-    dry_run: bool,
-    changes: Vec<crate::commands::JsonMoveChange>,
+#[derive(Serialize, JsonSchema)]
+pub(super) struct RenameCommandOutput {
+    pub(super) operation: &'static str,
+    pub(super) source: String,
+    pub(super) new_name: String,
+    pub(super) destination: String,
+    pub(super) root: String,
+    pub(super) dry_run: bool,
+    pub(super) changes: Vec<crate::commands::JsonMoveChange>,
 }
 
 #[cfg(test)]
@@ -89,7 +122,7 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
-    use crate::commands::OutputFormat;
+    use crate::commands::MoveOutputFormat;
 
     #[test]
     #[allow(clippy::unwrap_used)]
@@ -106,7 +139,8 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             false,
             false,
-            OutputFormat::Human,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap();
@@ -134,7 +168,8 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             true,
             false,
-            OutputFormat::Human,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap();
@@ -158,7 +193,8 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             false,
             false,
-            OutputFormat::Human,
+            false,
+            MoveOutputFormat::Human,
             &mut output,
         )
         .unwrap_err();
@@ -183,7 +219,8 @@ mod tests {
             Some(root.to_str().unwrap().to_string()),
             true,
             false,
-            OutputFormat::Json,
+            false,
+            MoveOutputFormat::Json,
             &mut output,
         )
         .unwrap();
@@ -0,0 +1,216 @@
+use std::io::Write;
+
+use mdref::{NoopProgress, Result, preview_rename_heading, rename_heading};
+use serde::Serialize;
+
+use crate::commands::{
+    JsonMoveChange, OutputFormat, progress::Spinner, write_json_output, write_move_changes_human,
+};
+
+pub fn run(
+    file: String,
+    old: String,
+    new: String,
+    root: Option<String>,
+    dry_run: bool,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(
+        file,
+        old,
+        new,
+        root,
+        dry_run,
+        show_progress,
+        format,
+        &mut stdout,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_with_writer<W: Write>(
+    file: String,
+    old: String,
+    new: String,
+    root: Option<String>,
+    dry_run: bool,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root_path = root.unwrap_or_else(|| ".".to_string());
+
+    let spinner = Spinner::new(show_progress && !dry_run);
+
+    match format {
+        OutputFormat::Human => {
+            if dry_run {
+                let preview = preview_rename_heading(&file, &old, &new, &root_path, &NoopProgress)?;
+                writeln!(
+                    writer,
+                    "[dry-run] Would rename heading in {}: {} (#{}) -> {} (#{})",
+                    file,
+                    preview.old_heading,
+                    preview.old_anchor,
+                    preview.new_heading,
+                    preview.new_anchor
+                )?;
+                return write_move_changes_human(&preview.changes, writer);
+            }
+
+            writeln!(writer, "Rename heading {old} -> {new} in {file}")?;
+            let result =
+                rename_heading(&file, &old, &new, &root_path, false, spinner.as_reporter());
+
+            spinner.finish();
+
+            result
+        }
+        OutputFormat::Json => {
+            let preview = preview_rename_heading(&file, &old, &new, &root_path, &NoopProgress)?;
+
+            if !dry_run {
+                rename_heading(&file, &old, &new, &root_path, false, spinner.as_reporter())?;
+            }
+
+            spinner.finish();
+
+            let payload = RenameHeadingCommandOutput {
+                operation: "rename-heading",
+                path: file,
+                old_heading: preview.old_heading,
+                new_heading: preview.new_heading,
+                old_anchor: preview.old_anchor,
+                new_anchor: preview.new_anchor,
+                root: root_path,
+                dry_run,
+                changes: preview.changes.iter().map(JsonMoveChange::from).collect(),
+            };
+
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RenameHeadingCommandOutput {
+    operation: &'static str,
+    path: String,
+    old_heading: String,
+    new_heading: String,
+    old_anchor: String,
+    new_anchor: String,
+    root: String,
+    dry_run: bool,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rename_heading_command_prints_summary_and_rewrites_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let guide = root.join("guide.md");
+        let index = root.join("index.md");
+        write_file(&guide, "# Guide\n\n## Install\n");
+        write_file(&index, "[Install](guide.md#install)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            guide.to_str().unwrap().to_string(),
+            "Install".to_string(),
+            "Installation".to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            false,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Rename heading Install -> Installation"));
+        assert_eq!(
+            fs::read_to_string(&guide).unwrap(),
+            "# Guide\n\n## Installation\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&index).unwrap(),
+            "[Install](guide.md#installation)\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rename_heading_command_dry_run_preserves_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let guide = root.join("guide.md");
+        write_file(&guide, "## Install\n");
+        write_file(root.join("index.md"), "[Install](guide.md#install)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            guide.to_str().unwrap().to_string(),
+            "#install".to_string(),
+            "Setup".to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Install (#install) -> Setup (#setup)"));
+        assert!(output.contains("Would update heading in"));
+        assert!(output.contains("Would update reference in"));
+        assert!(output.contains("](guide.md#install) -> ](guide.md#setup)"));
+        assert_eq!(fs::read_to_string(&guide).unwrap(), "## Install\n");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rename_heading_command_writes_json_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let guide = root.join("guide.md");
+        write_file(&guide, "## Install\n\n[Self](#install)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            guide.to_str().unwrap().to_string(),
+            "Install".to_string(),
+            "Setup".to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            true,
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "rename-heading");
+        assert_eq!(payload["old_anchor"], "install");
+        assert_eq!(payload["new_anchor"], "setup");
+        assert_eq!(payload["dry_run"], true);
+        let changes = payload["changes"].as_array().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["kind"], "heading_update");
+        assert_eq!(changes[0]["replacements"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{io::Write, path::PathBuf};
+
+use mdref::{MdrefError, NoopProgress, RemoveMode, Result, preview_remove, rm};
+use serde::Serialize;
+
+use crate::commands::{
+    JsonMoveChange, JsonReference, OutputFormat, progress::Spinner, write_json_output,
+    write_move_changes_human,
+};
+
+pub fn run(
+    path: String,
+    root: Option<String>,
+    mode: RemoveMode,
+    dry_run: bool,
+    show_progress: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(
+        path,
+        root,
+        mode,
+        dry_run,
+        show_progress,
+        format,
+        &mut stdout,
+    )
+}
+
+fn run_with_writer<W: Write>(
+    path: String,
+    root: Option<String>,
+    mode: RemoveMode,
+    dry_run: bool,
+    show_progress: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root_path = root.unwrap_or_else(|| ".".to_string());
+
+    let preview = preview_remove(&path, &root_path, &mode, &NoopProgress)?;
+    let refused = mode == RemoveMode::Refuse && !preview.references.is_empty();
+
+    match format {
+        OutputFormat::Human => {
+            if refused {
+                writeln!(writer, "{path} is still referenced by:")?;
+                for reference in &preview.references {
+                    writeln!(writer, "  {reference}")?;
+                }
+                writeln!(
+                    writer,
+                    "Pass --unlink, --redirect-to <PAGE> or --force to remove it anyway."
+                )?;
+            } else if dry_run {
+                writeln!(writer, "[dry-run] Would remove: {path}")?;
+                if mode == RemoveMode::Force {
+                    for reference in &preview.references {
+                        writeln!(writer, "[dry-run] Would leave dangling: {reference}")?;
+                    }
+                }
+                write_move_changes_human(&preview.changes, writer)?;
+            } else {
+                writeln!(writer, "Remove {path}")?;
+            }
+        }
+        OutputFormat::Json => {
+            let payload = RemoveCommandOutput {
+                operation: "rm",
+                path: path.clone(),
+                root: root_path.clone(),
+                mode: match &mode {
+                    RemoveMode::Refuse => "refuse",
+                    RemoveMode::Unlink => "unlink",
+                    RemoveMode::RedirectTo(_) => "redirect",
+                    RemoveMode::Force => "force",
+                },
+                redirect_to: match &mode {
+                    RemoveMode::RedirectTo(page) => Some(page.display().to_string()),
+                    _ => None,
+                },
+                dry_run,
+                references: preview.references.iter().map(JsonReference::from).collect(),
+                changes: preview.changes.iter().map(JsonMoveChange::from).collect(),
+            };
+            write_json_output(writer, &payload)?;
+        }
+    }
+
+    if refused {
+        return Err(MdrefError::StillReferenced {
+            path: PathBuf::from(path),
+            count: preview.references.len(),
+        });
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let spinner = Spinner::new(show_progress);
+    let result = rm(&path, &root_path, &mode, false, spinner.as_reporter());
+    spinner.finish();
+    result
+}
+
+#[derive(Serialize)]
+struct RemoveCommandOutput {
+    operation: &'static str,
+    path: String,
+    root: String,
+    mode: &'static str,
+    redirect_to: Option<String>,
+    dry_run: bool,
+    references: Vec<JsonReference>,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rm_command_refuses_and_lists_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let gone = root.join("gone.md");
+        write_file(&gone, "# Gone\n");
+        write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+        let mut output = Vec::new();
+        let error = run_with_writer(
+            gone.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            RemoveMode::Refuse,
+            false,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            MdrefError::StillReferenced { count: 1, .. }
+        ));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("is still referenced by:"));
+        assert!(output.contains("index.md:1:1 - gone.md"));
+        assert!(gone.exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rm_command_unlink_writes_json_and_removes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let gone = root.join("gone.md");
+        write_file(&gone, "# Gone\n");
+        write_file(root.join("index.md"), "See [Gone](gone.md).\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            gone.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            RemoveMode::Unlink,
+            false,
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "rm");
+        assert_eq!(payload["mode"], "unlink");
+        assert_eq!(payload["references"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            payload["changes"][0]["replacements"][0]["new_pattern"],
+            "Gone"
+        );
+        assert!(!gone.exists());
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "See Gone.\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_rm_command_dry_run_force_lists_dangling_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let gone = root.join("gone.md");
+        write_file(&gone, "# Gone\n");
+        write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+        let mut output = Vec::new();
+        run_with_writer(
+            gone.to_str().unwrap().to_string(),
+            Some(root.to_str().unwrap().to_string()),
+            RemoveMode::Force,
+            true,
+            false,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("[dry-run] Would remove:"));
+        assert!(output.contains("[dry-run] Would leave dangling:"));
+        assert!(gone.exists());
+    }
+}
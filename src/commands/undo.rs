@@ -0,0 +1,108 @@
+use std::io::Write;
+
+use mdref::{Result, undo};
+use serde::Serialize;
+
+use super::{
+    OutputFormat,
+    history::{JsonHistoryEntry, write_entry_human},
+    write_json_output,
+};
+
+pub fn run(root: Option<String>, format: OutputFormat) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    run_with_writer(root, format, &mut stdout)
+}
+
+fn run_with_writer<W: Write>(
+    root: Option<String>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let root = root.unwrap_or_else(|| ".".to_string());
+    let entry = undo(&root)?;
+
+    match format {
+        OutputFormat::Human => {
+            write!(writer, "Undid ")?;
+            write_entry_human(&entry, std::path::Path::new(&root), writer)
+        }
+        OutputFormat::Json => {
+            let payload = UndoCommandOutput {
+                operation: "undo",
+                root: &root,
+                undone: JsonHistoryEntry::from(&entry),
+            };
+            write_json_output(writer, &payload)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UndoCommandOutput<'a> {
+    operation: &'static str,
+    root: &'a str,
+    undone: JsonHistoryEntry,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::{NoopProgress, mv, test_utils::write_file};
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_undo_command_reverts_last_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("index.md"), "[A](a.md)");
+        mv(
+            root.join("a.md"),
+            root.join("docs/a.md"),
+            root,
+            false,
+            &NoopProgress,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        run_with_writer(
+            Some(root.to_str().unwrap().to_string()),
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let payload: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(payload["operation"], "undo");
+        assert_eq!(payload["undone"]["operation"], "mv");
+        assert!(root.join("a.md").exists());
+        assert!(!root.join("docs/a.md").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[A](a.md)"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_undo_command_without_history_returns_error() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut output = Vec::new();
+        let error = run_with_writer(
+            Some(temp_dir.path().to_str().unwrap().to_string()),
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("no operation to undo"));
+    }
+}
@@ -0,0 +1,393 @@
+//! `mdref watch`: follow moves made by other programs and fix links as they
+//! happen.
+//!
+//! Rename events are collected until the filesystem has been quiet for the
+//! debounce window, then the whole batch is replayed with [`fix_moved`], as if
+//! `mv` had made the moves. Each batch is logged as it is fixed.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use mdref::{
+    FixPreview, MdrefError, NoopProgress, Result, core::util::post_move_path, diff_paths,
+    fix_moved, preview_fix_moved,
+};
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+use serde::Serialize;
+
+use super::{JsonMove, JsonMoveChange, OutputFormat};
+
+pub fn run(
+    root: Option<String>,
+    debounce_ms: u64,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let root_dir = root.unwrap_or_else(|| ".".to_string());
+    let root = Path::new(&root_dir)
+        .canonicalize()
+        .map_err(|e| MdrefError::IoRead {
+            path: PathBuf::from(&root_dir),
+            source: e,
+        })?;
+    let watch_error = |error: notify::Error| MdrefError::Watch {
+        path: root.clone(),
+        details: error.to_string(),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(watch_error)?;
+
+    let mut stdout = std::io::stdout();
+    if format == OutputFormat::Human {
+        let mode = if dry_run { " (dry run)" } else { "" };
+        writeln!(
+            stdout,
+            "Watching {root_dir} for moves{mode}; press Ctrl-C to stop"
+        )?;
+        stdout.flush()?;
+    }
+
+    watch_with_writer(
+        &root,
+        &receiver,
+        Duration::from_millis(debounce_ms),
+        dry_run,
+        format,
+        &mut stdout,
+    )
+}
+
+/// Fix the moves reported on `events` in batches until every sender is gone.
+fn watch_with_writer<W: Write>(
+    root: &Path,
+    events: &Receiver<notify::Result<Event>>,
+    debounce: Duration,
+    dry_run: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let mut moves = Vec::new();
+    loop {
+        let event = if moves.is_empty() {
+            events.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            events.recv_timeout(debounce)
+        };
+
+        match event {
+            Ok(Ok(event)) => record_move(root, &event, &mut moves),
+            Ok(Err(error)) => eprintln!("Warning: {error}"),
+            Err(timeout_or_disconnected) => {
+                if !moves.is_empty() {
+                    let batch = std::mem::take(&mut moves);
+                    // One failed batch must not stop the watcher.
+                    if let Err(error) = fix_batch(root, &batch, dry_run, format, writer) {
+                        eprintln!("Error: {error}");
+                    }
+                }
+                if timeout_or_disconnected == RecvTimeoutError::Disconnected {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Add the rename reported by `event` to `moves`. A path moved again before
+/// its batch is fixed keeps its original source, so the batch maps each path
+/// from where the links last saw it to where it ended up.
+///
+/// Moves into, out of, or inside hidden files and directories are ignored;
+/// they include the temporary files editors and mdref itself write through.
+fn record_move(root: &Path, event: &Event, moves: &mut Vec<(PathBuf, PathBuf)>) {
+    let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind else {
+        return;
+    };
+    let [source, destination] = event.paths.as_slice() else {
+        return;
+    };
+    if is_hidden(root, source) || is_hidden(root, destination) {
+        return;
+    }
+
+    let renamed = [(source.clone(), destination.clone())];
+    let mut followed = false;
+    for (_, to) in moves.iter_mut() {
+        followed |= to == source;
+        *to = post_move_path(&renamed, to);
+    }
+    if !followed {
+        moves.push((source.clone(), destination.clone()));
+    }
+}
+
+fn is_hidden(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).map_or(true, |relative| {
+        relative
+            .components()
+            .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+    })
+}
+
+fn fix_batch<W: Write>(
+    root: &Path,
+    moves: &[(PathBuf, PathBuf)],
+    dry_run: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<()> {
+    let preview = preview_fix_moved(root, moves, &NoopProgress)?;
+    if preview.renames.is_empty() {
+        return Ok(());
+    }
+    if !dry_run {
+        fix_moved(root, moves, false, &NoopProgress)?;
+    }
+
+    match format {
+        OutputFormat::Human => write_batch_human(root, &preview, dry_run, writer)?,
+        OutputFormat::Json => {
+            let payload = WatchBatchOutput {
+                operation: "watch",
+                root: root.display().to_string(),
+                dry_run,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default(),
+                moves: preview
+                    .renames
+                    .iter()
+                    .map(|(source, destination)| JsonMove {
+                        source: source.display().to_string(),
+                        destination: destination.display().to_string(),
+                    })
+                    .collect(),
+                changes: preview.changes.iter().map(JsonMoveChange::from).collect(),
+            };
+            // One line per batch, so the log can be read as it is written.
+            serde_json::to_writer(&mut *writer, &payload).map_err(|error| {
+                MdrefError::SerializationFailed {
+                    details: format!("failed to write JSON output: {error}"),
+                }
+            })?;
+            writeln!(writer)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_batch_human<W: Write>(
+    root: &Path,
+    preview: &FixPreview,
+    dry_run: bool,
+    writer: &mut W,
+) -> Result<()> {
+    let relative = |path: &Path| {
+        diff_paths(path, root)
+            .unwrap_or_else(|| path.to_path_buf())
+            .display()
+            .to_string()
+    };
+
+    for (source, destination) in &preview.renames {
+        writeln!(
+            writer,
+            "Moved {} -> {}",
+            relative(source),
+            relative(destination)
+        )?;
+    }
+
+    let (prefix, verb) = if dry_run {
+        ("[dry-run] ", "Would update")
+    } else {
+        ("", "Updated")
+    };
+    if preview.changes.is_empty() {
+        writeln!(writer, "{prefix}No links to update")?;
+    }
+    for change in &preview.changes {
+        writeln!(
+            writer,
+            "{prefix}{verb} links in {}:",
+            relative(&change.path)
+        )?;
+        for replacement in &change.replacements {
+            writeln!(
+                writer,
+                "  Line {}: {} -> {}",
+                replacement.line, replacement.old_pattern, replacement.new_pattern
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct WatchBatchOutput {
+    operation: &'static str,
+    root: String,
+    dry_run: bool,
+    timestamp: u64,
+    moves: Vec<JsonMove>,
+    changes: Vec<JsonMoveChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mdref::test_utils::write_file;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn rename_event(source: &Path, destination: &Path) -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(source.to_path_buf())
+            .add_path(destination.to_path_buf())
+    }
+
+    #[test]
+    fn test_record_move_follows_paths_moved_again() {
+        let root = Path::new("/notes");
+        let mut moves = Vec::new();
+
+        for (source, destination) in [
+            ("/notes/a.md", "/notes/b.md"),
+            ("/notes/b.md", "/notes/docs/b.md"),
+            ("/notes/docs", "/notes/guide"),
+            ("/notes/.a.md.mdref-write", "/notes/a.md"),
+            ("/notes/c.md", "/elsewhere/c.md"),
+        ] {
+            record_move(
+                root,
+                &rename_event(Path::new(source), Path::new(destination)),
+                &mut moves,
+            );
+        }
+
+        assert_eq!(
+            moves,
+            [
+                (
+                    PathBuf::from("/notes/a.md"),
+                    PathBuf::from("/notes/guide/b.md")
+                ),
+                (PathBuf::from("/notes/docs"), PathBuf::from("/notes/guide")),
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_watch_fixes_batched_moves_and_logs_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        write_file(
+            root.join("index.md"),
+            "[Guide](guide.md) ![Logo](logo.png)\n",
+        );
+        write_file(root.join("guide.md"), "[Home](index.md)\n");
+        write_file(root.join("logo.png"), "png");
+        fs::create_dir(root.join("docs")).unwrap();
+        fs::rename(root.join("guide.md"), root.join("docs/guide.md")).unwrap();
+        fs::rename(root.join("logo.png"), root.join("docs/logo.png")).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        sender
+            .send(Ok(rename_event(
+                &root.join("guide.md"),
+                &root.join("docs/guide.md"),
+            )))
+            .unwrap();
+        sender
+            .send(Ok(rename_event(
+                &root.join("logo.png"),
+                &root.join("docs/logo.png"),
+            )))
+            .unwrap();
+        drop(sender);
+
+        let mut output = Vec::new();
+        watch_with_writer(
+            &root,
+            &receiver,
+            Duration::from_millis(10),
+            false,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        let payload: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(payload["operation"], "watch");
+        assert_eq!(payload["moves"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](docs/guide.md) ![Logo](docs/logo.png)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("docs/guide.md")).unwrap(),
+            "[Home](../index.md)\n"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_watch_dry_run_only_reports_fixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        write_file(root.join("index.md"), "[Guide](guide.md)\n");
+        write_file(root.join("guide.md"), "# Guide\n");
+        fs::rename(root.join("guide.md"), root.join("manual.md")).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        sender
+            .send(Ok(rename_event(
+                &root.join("guide.md"),
+                &root.join("manual.md"),
+            )))
+            .unwrap();
+        drop(sender);
+
+        let mut output = Vec::new();
+        watch_with_writer(
+            &root,
+            &receiver,
+            Duration::from_millis(10),
+            true,
+            OutputFormat::Human,
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Moved guide.md -> manual.md\n\
+             [dry-run] Would update links in index.md:\n  \
+             Line 1: ](guide.md) -> ](manual.md)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[Guide](guide.md)\n"
+        );
+    }
+}
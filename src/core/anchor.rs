@@ -0,0 +1,256 @@
+//! Fragment anchors defined by a Markdown document.
+//!
+//! A document defines anchors in three ways:
+//!
+//! - headings, using GitHub-style slugs with `-1`, `-2`, … suffixes for duplicates
+//! - explicit `{#id}` attributes at the end of a heading, which replace the slug
+//! - raw HTML elements carrying an `id` (or `<a name>`) attribute
+
+use std::collections::{HashMap, HashSet};
+
+use comrak::{
+    Arena,
+    nodes::{AstNode, NodeValue},
+    parse_document,
+};
+
+use super::{html::scan_tags, model::Heading};
+
+/// Convert heading text into a GitHub-style slug.
+///
+/// The text is lowercased, whitespace becomes `-`, and every character other
+/// than letters, digits, `-` and `_` is dropped.
+///
+/// # Examples
+/// - `"Getting Started"` -> `"getting-started"`
+/// - `"What's new in v2.0?"` -> `"whats-new-in-v20"`
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_whitespace() {
+                Some('-')
+            } else if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Collect every heading of a Markdown document with its resolved anchor.
+///
+/// Duplicate slugs get GitHub's numeric suffixes in document order, so the
+/// second `## Usage` resolves to `usage-1`.
+pub fn collect_headings(content: &str) -> Vec<Heading> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &comrak::Options::default());
+
+    let mut headings = Vec::new();
+    let mut slugs = SlugCounter::default();
+    collect_heading_nodes(root, &mut slugs, &mut headings);
+    headings
+}
+
+/// Collect every fragment anchor a Markdown document defines.
+pub fn collect_anchors(content: &str) -> HashSet<String> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &comrak::Options::default());
+
+    let mut headings = Vec::new();
+    let mut slugs = SlugCounter::default();
+    collect_heading_nodes(root, &mut slugs, &mut headings);
+
+    let mut anchors: HashSet<String> = headings.into_iter().map(|h| h.anchor).collect();
+    collect_html_anchors(root, &mut anchors);
+    anchors
+}
+
+/// Split a trailing `{#id}` attribute off heading text.
+///
+/// Returns the remaining text and the explicit id, if any.
+pub fn split_explicit_anchor(text: &str) -> (&str, Option<&str>) {
+    let trimmed = text.trim_end();
+    if let Some(without_brace) = trimmed.strip_suffix('}')
+        && let Some(open) = without_brace.rfind("{#")
+    {
+        let id = &without_brace[open + 2..];
+        if !id.is_empty() && !id.contains(char::is_whitespace) {
+            return (without_brace[..open].trim_end(), Some(id));
+        }
+    }
+    (trimmed, None)
+}
+
+/// Tracks slug occurrences to apply GitHub's duplicate-suffix rule.
+#[derive(Default)]
+pub(crate) struct SlugCounter {
+    occurrences: HashMap<String, usize>,
+}
+
+impl SlugCounter {
+    /// Return `slug` if unused, otherwise the first free `slug-N`, and record it.
+    pub(crate) fn unique(&mut self, slug: String) -> String {
+        let mut candidate = slug.clone();
+        while self.occurrences.contains_key(&candidate) {
+            let count = self.occurrences.entry(slug.clone()).or_default();
+            *count += 1;
+            candidate = format!("{slug}-{count}");
+        }
+        self.occurrences.insert(candidate.clone(), 0);
+        candidate
+    }
+}
+
+fn collect_heading_nodes<'a>(
+    node: &'a AstNode<'a>,
+    slugs: &mut SlugCounter,
+    headings: &mut Vec<Heading>,
+) {
+    let data = node.data.borrow();
+    if let NodeValue::Heading(heading) = &data.value {
+        let line = data.sourcepos.start.line;
+        let column = data.sourcepos.start.column;
+        let level = heading.level;
+        drop(data);
+
+        let mut raw_text = String::new();
+        collect_plain_text(node, &mut raw_text);
+        let (text, explicit) = split_explicit_anchor(&raw_text);
+        let anchor = match explicit {
+            Some(id) => slugs.unique(id.to_string()),
+            None => slugs.unique(heading_slug(text)),
+        };
+
+        headings.push(Heading {
+            line,
+            column,
+            level,
+            text: text.to_string(),
+            anchor,
+            explicit_anchor: explicit.is_some(),
+        });
+        return;
+    }
+    drop(data);
+
+    for child in node.children() {
+        collect_heading_nodes(child, slugs, headings);
+    }
+}
+
+fn collect_plain_text<'a>(node: &'a AstNode<'a>, text: &mut String) {
+    for child in node.children() {
+        match &child.data.borrow().value {
+            NodeValue::Text(literal) => text.push_str(literal),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            NodeValue::SoftBreak | NodeValue::LineBreak => text.push(' '),
+            NodeValue::HtmlInline(_) => {}
+            _ => collect_plain_text(child, text),
+        }
+    }
+}
+
+fn collect_html_anchors<'a>(node: &'a AstNode<'a>, anchors: &mut HashSet<String>) {
+    match &node.data.borrow().value {
+        NodeValue::HtmlBlock(block) => insert_html_anchors(&block.literal, anchors),
+        NodeValue::HtmlInline(literal) => insert_html_anchors(literal, anchors),
+        _ => {}
+    }
+
+    for child in node.children() {
+        collect_html_anchors(child, anchors);
+    }
+}
+
+fn insert_html_anchors(html: &str, anchors: &mut HashSet<String>) {
+    for tag in scan_tags(html) {
+        if let Some(id) = tag.attribute("id") {
+            anchors.insert(id.value.to_string());
+        }
+        if tag.name.eq_ignore_ascii_case("a")
+            && let Some(name) = tag.attribute("name")
+        {
+            anchors.insert(name.value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::simple("Getting Started", "getting-started")]
+    #[case::punctuation("What's new in v2.0?", "whats-new-in-v20")]
+    #[case::keeps_hyphen_and_underscore("snake_case and kebab-case", "snake_case-and-kebab-case")]
+    #[case::code_span_text("The `mv` command", "the-mv-command")]
+    #[case::unicode("Über Größe", "über-größe")]
+    #[case::cjk("安装 指南", "安装-指南")]
+    #[case::double_space("A  B", "a--b")]
+    fn test_heading_slug_follows_github_rules(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(heading_slug(input), expected);
+    }
+
+    #[test]
+    fn test_collect_headings_applies_duplicate_suffixes() {
+        let content = "# Usage\n\n## Usage\n\n## Usage\n\n## Usage 1\n";
+        let anchors: Vec<String> = collect_headings(content)
+            .into_iter()
+            .map(|heading| heading.anchor)
+            .collect();
+
+        assert_eq!(anchors, vec!["usage", "usage-1", "usage-2", "usage-1-1"]);
+    }
+
+    #[test]
+    fn test_collect_headings_reads_explicit_id_and_setext() {
+        let content = "Intro\n=====\n\n## Install {#setup}\n";
+        let headings = collect_headings(content);
+
+        assert_eq!(headings.len(), 2);
+        assert_eq!(headings[0].anchor, "intro");
+        assert_eq!(headings[0].level, 1);
+        assert_eq!(headings[1].text, "Install");
+        assert_eq!(headings[1].anchor, "setup");
+        assert!(headings[1].explicit_anchor);
+        assert_eq!(headings[1].line, 4);
+    }
+
+    #[test]
+    fn test_collect_headings_uses_rendered_text_of_inline_markup() {
+        let headings = collect_headings("## **Bold** [link](x.md) `code`\n");
+
+        assert_eq!(headings[0].text, "Bold link code");
+        assert_eq!(headings[0].anchor, "bold-link-code");
+    }
+
+    #[test]
+    fn test_collect_anchors_includes_html_ids_and_named_anchors() {
+        let content = "# Title\n\n<div id=\"block\"></div>\n\nText <a name=\"legacy\"></a> and <a id=inline></a>.\n";
+        let anchors = collect_anchors(content);
+
+        assert!(anchors.contains("title"));
+        assert!(anchors.contains("block"));
+        assert!(anchors.contains("legacy"));
+        assert!(anchors.contains("inline"));
+    }
+
+    #[test]
+    fn test_collect_anchors_ignores_headings_in_code_blocks() {
+        let anchors = collect_anchors("```md\n# Not a heading\n```\n");
+
+        assert!(anchors.is_empty());
+    }
+
+    #[test]
+    fn test_split_explicit_anchor_ignores_non_id_braces() {
+        assert_eq!(split_explicit_anchor("Title {#id}"), ("Title", Some("id")));
+        assert_eq!(split_explicit_anchor("Set {a, b}"), ("Set {a, b}", None));
+        assert_eq!(split_explicit_anchor("Empty {#}"), ("Empty {#}", None));
+    }
+}
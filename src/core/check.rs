@@ -0,0 +1,307 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use super::{
+    anchor::collect_anchors,
+    config::{ScanConfig, is_markdown},
+    find::{collect_local_links, process_md_file, resolve_link},
+    model::{BrokenLink, BrokenLinkKind},
+    progress::ProgressReporter,
+    util::{collect_files, url_decode_link},
+    wikilink::WikiVault,
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+pub(super) type AnchorIndex = HashMap<PathBuf, HashSet<String>>;
+
+/// Check every local link in the Markdown files under `root_dir` and report the
+/// ones whose target does not exist.
+///
+/// Inline links, images, link reference definitions, and wikilinks are all
+/// checked; wikilink targets resolve with vault semantics (see
+/// [`crate::core::wikilink::WikiVault`]). External URLs are skipped. When `check_anchors` is `true`, `#fragment`s
+/// pointing into Markdown files (including pure `#section` links into the same
+/// document) must also match a heading slug, an explicit `{#id}`, or an HTML
+/// `id` in the target; see [`crate::core::anchor`]. Wikilink `#Heading`
+/// fragments name headings by text and are not validated. The returned
+/// [`BrokenLink`]s are sorted by path, line, and column.
+///
+/// # Progress
+///
+/// Callers report progress through a [`ProgressReporter`] trait object. Pass
+/// [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+/// The reporter is called with [`ProgressReporter::set_total`] once before scanning,
+/// and with [`ProgressReporter::inc`] once per Markdown file as it is processed.
+pub fn check_links<P: AsRef<Path>>(
+    root_dir: P,
+    check_anchors: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<BrokenLink>> {
+    let files = collect_files(root_dir.as_ref());
+    let vault = WikiVault::from_files(root_dir.as_ref(), &files);
+    let scan = ScanConfig::for_path(root_dir.as_ref());
+    let markdown_files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|path| scan.is_markdown(path))
+        .collect();
+
+    progress.set_total(markdown_files.len() as u64);
+
+    let contents = markdown_files
+        .par_iter()
+        .map(|path| {
+            fs::read_to_string(path)
+                .map(|content| (path.clone(), content))
+                .map_err(|e| MdrefError::IoRead {
+                    path: path.clone(),
+                    source: e,
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let anchor_index: AnchorIndex = if check_anchors {
+        contents
+            .par_iter()
+            .filter_map(|(path, content)| {
+                let canonical = path.canonicalize().ok()?;
+                Some((canonical, collect_anchors(content)))
+            })
+            .collect()
+    } else {
+        AnchorIndex::new()
+    };
+
+    let mut broken_links: Vec<BrokenLink> = contents
+        .par_iter()
+        .flat_map_iter(|(path, content)| {
+            let broken = check_file(path, content, check_anchors, &anchor_index, &vault);
+            progress.inc(1);
+            broken
+        })
+        .collect();
+
+    broken_links.sort_by(|left, right| {
+        left.path
+            .cmp(&right.path)
+            .then(left.line.cmp(&right.line))
+            .then(left.column.cmp(&right.column))
+    });
+
+    Ok(broken_links)
+}
+
+pub(super) fn check_file(
+    path: &Path,
+    content: &str,
+    check_anchors: bool,
+    anchor_index: &AnchorIndex,
+    vault: &WikiVault,
+) -> Vec<BrokenLink> {
+    let links = if check_anchors {
+        collect_local_links(content, path)
+    } else {
+        process_md_file(content, path, None)
+    };
+
+    links
+        .into_iter()
+        .filter_map(|link| {
+            let kind = check_link(path, &link, check_anchors, anchor_index, vault)?;
+            Some(BrokenLink::new(link, kind))
+        })
+        .collect()
+}
+
+/// Check a single link found in `base_file`, returning why it is broken, if it is.
+fn check_link(
+    base_file: &Path,
+    link: &Reference,
+    check_anchors: bool,
+    anchor_index: &AnchorIndex,
+    vault: &WikiVault,
+) -> Option<BrokenLinkKind> {
+    let (link_path, anchor) = match link.link_text.split_once('#') {
+        Some((link_path, anchor)) => (link_path, Some(anchor)),
+        None => (link.link_text.as_str(), None),
+    };
+
+    if link.link_type == LinkType::WikiLink {
+        let missing = !link_path.is_empty() && vault.resolve(base_file, link_path).is_none();
+        return missing.then_some(BrokenLinkKind::MissingTarget);
+    }
+
+    let target = if link_path.is_empty() {
+        base_file.to_path_buf()
+    } else {
+        match resolve_link(base_file, Path::new(link_path)) {
+            Some(resolved) if resolved.exists() => resolved,
+            _ => return Some(BrokenLinkKind::MissingTarget),
+        }
+    };
+
+    let anchor = anchor.filter(|anchor| check_anchors && !anchor.is_empty())?;
+    let anchor = url_decode_link(anchor);
+    let found = match target
+        .canonicalize()
+        .ok()
+        .and_then(|canonical| anchor_index.get(&canonical))
+    {
+        Some(anchors) => anchors.contains(&anchor),
+        // Targets outside the scanned root are parsed on demand. Fragments of
+        // other files are not checked.
+        None if is_markdown_file(&target) => fs::read_to_string(&target)
+            .map(|content| collect_anchors(&content).contains(&anchor))
+            .unwrap_or(true),
+        None => return None,
+    };
+
+    (!found).then_some(BrokenLinkKind::MissingAnchor)
+}
+
+fn is_markdown_file(path: &Path) -> bool {
+    path.is_file() && is_markdown(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{LinkType, core::progress::NoopProgress, test_utils::write_file};
+
+    fn check(base_file: &Path, link_text: &str, check_anchors: bool) -> Option<BrokenLinkKind> {
+        let link = Reference::new(base_file.to_path_buf(), 1, 1, link_text.to_string());
+        let vault = WikiVault::new(base_file.parent().unwrap_or(Path::new(".")));
+        check_link(base_file, &link, check_anchors, &AnchorIndex::new(), &vault)
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_link_existing_file_with_anchor() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.md");
+        write_file(&base, "");
+        write_file(temp_dir.path().join("guide.md"), "# Install");
+
+        assert_eq!(check(&base, "guide.md#install", true), None);
+        assert_eq!(
+            check(&base, "guide.md#instalation", true),
+            Some(BrokenLinkKind::MissingAnchor)
+        );
+        assert_eq!(check(&base, "guide.md#instalation", false), None);
+        assert_eq!(
+            check(&base, "missing.md#install", true),
+            Some(BrokenLinkKind::MissingTarget)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_link_anchor_into_non_markdown_target_is_not_checked() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.md");
+        write_file(&base, "");
+        write_file(temp_dir.path().join("page.html"), "<h1>Title</h1>");
+
+        assert_eq!(check(&base, "page.html#anything", true), None);
+    }
+
+    #[test]
+    fn test_check_link_empty_link_and_empty_fragment() {
+        assert_eq!(check(Path::new("base.md"), "", true), None);
+        assert_eq!(check(Path::new("base.md"), "#", true), None);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_links_reports_inline_image_and_definition() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("ok.md"), "# Ok");
+        write_file(
+            root.join("index.md"),
+            "[Ok](ok.md)\n[Gone](gone.md)\n![Img](missing.png)\n\n[ref]: ./nowhere.md\n",
+        );
+
+        let broken = check_links(root, false, &NoopProgress).unwrap();
+
+        let link_texts: Vec<&str> = broken.iter().map(|b| b.link_text.as_str()).collect();
+        assert_eq!(link_texts, vec!["gone.md", "missing.png", "./nowhere.md"]);
+        assert_eq!(broken[2].link_type, LinkType::ReferenceDefinition);
+        assert_eq!(broken[2].line, 5);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_links_ignores_external_and_anchor_links() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file(
+            temp_dir.path().join("index.md"),
+            "[Site](https://example.com)\n[Mail](mailto:a@b.c)\n[Top](#top)\n",
+        );
+
+        let broken = check_links(temp_dir.path(), false, &NoopProgress).unwrap();
+
+        assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_links_reports_missing_html_attribute_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("logo.png"), "");
+        write_file(
+            root.join("index.md"),
+            "<p>\n  <img src=\"logo.png\" srcset=\"logo-dark.png 2x\">\n</p>\n",
+        );
+
+        let broken = check_links(root, false, &NoopProgress).unwrap();
+
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].link_text, "logo-dark.png");
+        assert_eq!(broken[0].link_type, LinkType::HtmlAttribute);
+        assert_eq!((broken[0].line, broken[0].column), (2, 31));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_links_resolves_wikilinks_through_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("notes/Topic.md"), "# Topic");
+        write_file(root.join("assets/diagram.png"), "");
+        write_file(
+            root.join("index.md"),
+            "[[Topic]] [[Topic#Any Heading|alias]] ![[diagram.png]] [[Missing Note]]\n",
+        );
+
+        let broken = check_links(root, true, &NoopProgress).unwrap();
+
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].link_text, "Missing Note");
+        assert_eq!(broken[0].link_type, LinkType::WikiLink);
+        assert_eq!(broken[0].kind, BrokenLinkKind::MissingTarget);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_check_links_with_anchors_reports_same_document_fragments() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file(
+            temp_dir.path().join("index.md"),
+            "# Intro\n\n[Ok](#intro)\n[Typo](#intor)\n",
+        );
+
+        let broken = check_links(temp_dir.path(), true, &NoopProgress).unwrap();
+
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].link_text, "#intor");
+        assert_eq!(broken[0].kind, BrokenLinkKind::MissingAnchor);
+    }
+}
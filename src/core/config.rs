@@ -0,0 +1,310 @@
+//! Project-wide settings read from a `.mdref.toml` file.
+//!
+//! The file applies to the directory holding it and everything below, so the
+//! nearest one at or above a path wins. File discovery reads the `[scan]`
+//! settings of the file governing the root it walks, and link rewrites read
+//! the `[links]` settings of the file governing the document they change. The
+//! top-level `root` and `format` only provide defaults for the command line.
+//!
+//! ```toml
+//! root = "docs"
+//! format = "json"
+//!
+//! [scan]
+//! include = ["guide/", "*.md"]
+//! exclude = ["drafts/", "node_modules/"]
+//! markdown_extensions = ["md", "mdx"]
+//! ignore_files = true
+//!
+//! [links]
+//! spaces = "percent"
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::wikilink::absolute;
+use crate::{MdrefError, Result};
+
+/// The file name [`Config::discover`] looks for.
+pub const CONFIG_FILE_NAME: &str = ".mdref.toml";
+
+/// The extensions treated as Markdown when `scan.markdown_extensions` is not
+/// set.
+pub const DEFAULT_MARKDOWN_EXTENSIONS: &[&str] =
+    &["md", "markdown", "mdown", "mkdn", "mkd", "mdwn", "mdx"];
+
+/// The contents of a `.mdref.toml` file. Missing settings take their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Default root directory, relative to the directory holding the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// Default output format, for commands that offer it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    pub scan: ScanConfig,
+    pub links: LinkConfig,
+}
+
+/// Which files are scanned under a root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Gitignore-style patterns, relative to the directory holding the file.
+    /// When any is given, only files matching one (or inside a matching
+    /// directory) are scanned.
+    pub include: Vec<String>,
+    /// Gitignore-style patterns for files and directories never scanned.
+    pub exclude: Vec<String>,
+    /// Extensions of the files treated as Markdown, matched without regard to
+    /// case. A leading dot is optional.
+    pub markdown_extensions: Vec<String>,
+    /// Honour `.gitignore`, `.ignore`, and git's global and repository
+    /// excludes. Hidden files are skipped either way.
+    pub ignore_files: bool,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            markdown_extensions: DEFAULT_MARKDOWN_EXTENSIONS
+                .iter()
+                .map(|extension| extension.to_string())
+                .collect(),
+            ignore_files: true,
+        }
+    }
+}
+
+impl ScanConfig {
+    /// The scan settings of the nearest configuration file at or above
+    /// `path`, or the defaults when there is none or it cannot be read.
+    pub fn for_path(path: &Path) -> Self {
+        Config::for_path(path).1.scan
+    }
+
+    /// Whether `path` has one of the Markdown extensions.
+    pub fn is_markdown(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                self.markdown_extensions
+                    .iter()
+                    .any(|known| known.trim_start_matches('.').eq_ignore_ascii_case(ext))
+            })
+    }
+}
+
+/// How rewritten links are written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkConfig {
+    /// How an inline link or reference definition writes a new path that
+    /// contains spaces.
+    pub spaces: SpaceStyle,
+}
+
+/// How a path with spaces is written in a Markdown link destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpaceStyle {
+    /// `my%20file.md`
+    #[default]
+    Percent,
+    /// `<my file.md>`
+    Angle,
+}
+
+impl Config {
+    /// Parse the configuration file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| MdrefError::IoRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        toml::from_str(&content).map_err(|error| MdrefError::Config {
+            path: path.to_path_buf(),
+            details: error.message().to_string(),
+        })
+    }
+
+    /// Find the nearest [`CONFIG_FILE_NAME`] in `start` (a file or directory)
+    /// or any directory above it, and parse it.
+    ///
+    /// Returns the absolute path of the file with its contents, or `None`
+    /// when there is no such file.
+    pub fn discover<P: AsRef<Path>>(start: P) -> Result<Option<(PathBuf, Self)>> {
+        let start = absolute(start.as_ref());
+        for dir in start.ancestors() {
+            let path = dir.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                let config = Self::load(&path)?;
+                return Ok(Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The settings governing `path`, or the defaults when there is no
+    /// configuration file or it cannot be read.
+    ///
+    /// Commands report unreadable files up front; library scans fall back to
+    /// the defaults rather than fail.
+    pub(crate) fn for_path(path: &Path) -> (Option<PathBuf>, Self) {
+        match Self::discover(path) {
+            Ok(Some((file, config))) => (file.parent().map(Path::to_path_buf), config),
+            _ => (None, Self::default()),
+        }
+    }
+}
+
+/// Whether the configuration governing `path` treats it as Markdown.
+///
+/// This looks the configuration up on every call; scans of a whole root ask
+/// the [`ScanConfig`] of the root instead.
+pub fn is_markdown(path: &Path) -> bool {
+    ScanConfig::for_path(path).is_markdown(path)
+}
+
+/// The [`ScanConfig`] governing a root, ready to match paths against.
+#[derive(Clone)]
+pub(crate) struct ScanFilter {
+    base: Option<PathBuf>,
+    include: Gitignore,
+    exclude: Gitignore,
+    pub(crate) scan: ScanConfig,
+}
+
+impl ScanFilter {
+    pub(crate) fn for_root(root: &Path) -> Self {
+        let (base, config) = Config::for_path(root);
+        let matcher = |patterns: &[String]| {
+            let Some(base) = &base else {
+                return Gitignore::empty();
+            };
+            let mut builder = GitignoreBuilder::new(base);
+            for pattern in patterns {
+                // Invalid patterns are skipped, like in an ignore file.
+                let _ = builder.add_line(None, pattern);
+            }
+            builder.build().unwrap_or_else(|_| Gitignore::empty())
+        };
+
+        Self {
+            include: matcher(&config.scan.include),
+            exclude: matcher(&config.scan.exclude),
+            base,
+            scan: config.scan,
+        }
+    }
+
+    /// Whether the walk should skip `path` and, for a directory, everything
+    /// below it.
+    pub(crate) fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        self.relative(path).is_some_and(|relative| {
+            self.exclude
+                .matched_path_or_any_parents(relative, is_dir)
+                .is_ignore()
+        })
+    }
+
+    /// Whether the file at `path` passes the include patterns.
+    pub(crate) fn is_included(&self, path: &Path) -> bool {
+        if self.include.is_empty() {
+            return true;
+        }
+        self.relative(path).is_none_or(|relative| {
+            self.include
+                .matched_path_or_any_parents(relative, false)
+                .is_ignore()
+        })
+    }
+
+    pub(crate) fn is_markdown(&self, path: &Path) -> bool {
+        self.scan.is_markdown(path)
+    }
+
+    /// `path` relative to the directory of the configuration file, or `None`
+    /// when it lies outside it.
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        let base = self.base.as_ref()?;
+        Some(absolute(path).strip_prefix(base).ok()?.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::test_utils::write_file;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_discover_finds_nearest_file_above_start() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        write_file(
+            root.join(CONFIG_FILE_NAME),
+            "root = \"docs\"\n[scan]\nexclude = [\"drafts/\"]\n",
+        );
+        write_file(root.join("docs/guide/page.md"), "# Page\n");
+
+        let (path, config) = Config::discover(root.join("docs/guide")).unwrap().unwrap();
+
+        assert_eq!(path, root.join(CONFIG_FILE_NAME));
+        assert_eq!(config.root, Some(PathBuf::from("docs")));
+        assert_eq!(config.scan.exclude, ["drafts/"]);
+        assert_eq!(config.scan.markdown_extensions, DEFAULT_MARKDOWN_EXTENSIONS);
+        assert!(config.scan.ignore_files);
+        assert_eq!(config.links.spaces, SpaceStyle::Percent);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_load_rejects_unknown_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(CONFIG_FILE_NAME);
+        write_file(&path, "[scan]\nextensions = [\"md\"]\n");
+
+        let result = Config::load(&path);
+
+        assert!(matches!(result, Err(MdrefError::Config { .. })));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_scan_filter_matches_relative_to_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        write_file(
+            root.join(CONFIG_FILE_NAME),
+            "[scan]\ninclude = [\"docs/\"]\nexclude = [\"docs/drafts/\"]\nmarkdown_extensions = [\".md\", \"markdown\"]\n",
+        );
+
+        let filter = ScanFilter::for_root(&root.join("docs"));
+
+        assert!(filter.is_included(&root.join("docs/a.md")));
+        assert!(!filter.is_included(&root.join("other/a.md")));
+        assert!(filter.is_excluded(&root.join("docs/drafts"), true));
+        assert!(filter.is_excluded(&root.join("docs/drafts/a.md"), false));
+        assert!(!filter.is_excluded(&root.join("docs/a.md"), false));
+        assert!(filter.is_markdown(Path::new("notes.markdown")));
+        assert!(filter.is_markdown(Path::new("NOTES.MD")));
+        assert!(!filter.is_markdown(Path::new("notes.mdx")));
+        assert!(!filter.is_markdown(Path::new("notes.txt")));
+    }
+}
@@ -0,0 +1,205 @@
+//! Copy a file or directory and rewrite the relative links inside the copy.
+//!
+//! A copy that lands at a different depth than its original would otherwise
+//! carry broken relative links. Planning reuses the `mv` planner as if the
+//! copied tree had moved, but only the copies are rewritten: the originals and
+//! every inbound reference to them stay untouched. Wikilinks in the copy are
+//! retargeted when the copied files would otherwise capture them. A copy that
+//! fails halfway is removed again. Copies are not recorded in the undo
+//! journal; delete the copy to revert one.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use super::{
+    model::{CopyPreview, MoveChangeKind},
+    mv::{
+        apply::rewrite_content,
+        plan::{ReplacementPlan, plan_copy_replacements},
+        preview::{build_move_changes, print_move_changes},
+        validate::validate_copy_paths,
+    },
+    util::{pre_move_path, remove_path, write_atomic},
+    wikilink::absolute,
+};
+use crate::{MdrefError, Result};
+
+/// Where links between two copied files point after a copy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntraCopyLinks {
+    /// Keep pointing at the original files.
+    #[default]
+    ToOriginal,
+    /// Point at the corresponding copies instead.
+    ToCopy,
+}
+
+/// Copy `source` (a file or directory) to `dest` and rewrite the relative
+/// links inside the copied Markdown files so they still resolve from the new
+/// location.
+///
+/// As with [`crate::mv`], a `dest` that is an existing directory receives the
+/// copy under the source's name, and an existing destination path is an
+/// error. Links between copied files follow `intra_links`. Nothing outside the
+/// copy is modified.
+///
+/// When `dry_run` is `true`, no files are written. Instead, the function
+/// prints all changes that *would* be made.
+///
+/// # Example
+///
+/// ```ignore
+/// use mdref::{IntraCopyLinks, cp};
+///
+/// // Start docs/v2 from docs/v1, with v2's pages linking to each other.
+/// cp("docs/v1", "docs/v2", IntraCopyLinks::ToCopy, false)?;
+/// ```
+pub fn cp<P, D>(source: P, dest: D, intra_links: IntraCopyLinks, dry_run: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    let CopyPlan {
+        preview,
+        source_canonical,
+        dest_canonical,
+        replacements_by_file,
+    } = plan_copy(source.as_ref(), dest.as_ref(), intra_links)?;
+
+    if dry_run {
+        println!(
+            "[dry-run] Would copy: {} -> {}",
+            preview.source.display(),
+            preview.destination.display()
+        );
+        print_move_changes(&preview.changes);
+        return Ok(());
+    }
+
+    let copies = [(source_canonical.clone(), dest_canonical.clone())];
+
+    // Render every rewritten copy before writing anything.
+    let mut rewritten = HashMap::new();
+    for (copy_path, replacements) in &replacements_by_file {
+        let original_path = pre_move_path(&copies, copy_path);
+        let content = fs::read_to_string(&original_path).map_err(|e| MdrefError::IoRead {
+            path: original_path.clone(),
+            source: e,
+        })?;
+        rewritten.insert(
+            copy_path.clone(),
+            rewrite_content(&original_path, &content, replacements)?,
+        );
+    }
+
+    if let Some(parent) = dest_canonical.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if let Err(error) = copy_tree(&source_canonical, &dest_canonical, &rewritten) {
+        let _ = remove_path(&dest_canonical);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Preview a copy without touching the filesystem.
+pub fn preview_copy<P, D>(source: P, dest: D, intra_links: IntraCopyLinks) -> Result<CopyPreview>
+where
+    P: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    Ok(plan_copy(source.as_ref(), dest.as_ref(), intra_links)?.preview)
+}
+
+/// A validated copy and the rewrites of its Markdown files, keyed by the
+/// copies' paths under `dest_canonical`.
+struct CopyPlan {
+    preview: CopyPreview,
+    source_canonical: PathBuf,
+    dest_canonical: PathBuf,
+    replacements_by_file: ReplacementPlan,
+}
+
+fn plan_copy(source: &Path, dest: &Path, intra_links: IntraCopyLinks) -> Result<CopyPlan> {
+    let (resolved_dest, source_canonical, dest_canonical) = validate_copy_paths(source, dest)?;
+    // The destination's parent may not exist yet; the plan needs an absolute path.
+    let dest_canonical = absolute(&dest_canonical);
+
+    let replacements_by_file = plan_copy_replacements(
+        source,
+        &source_canonical,
+        &dest_canonical,
+        &vault_root(&source_canonical, &dest_canonical),
+        intra_links == IntraCopyLinks::ToCopy,
+    )?;
+    let changes = build_move_changes(replacements_by_file.clone(), |_| {
+        MoveChangeKind::CopiedFileUpdate
+    });
+
+    Ok(CopyPlan {
+        preview: CopyPreview {
+            source: source.to_path_buf(),
+            destination: resolved_dest,
+            changes,
+        },
+        source_canonical,
+        dest_canonical,
+        replacements_by_file,
+    })
+}
+
+/// The directory whose files wikilinks in the copy are resolved against: the
+/// deepest one containing both the original and the copy.
+fn vault_root(source: &Path, dest: &Path) -> PathBuf {
+    let source_parent = source.parent().unwrap_or(source);
+    dest.ancestors()
+        .find(|ancestor| source_parent.starts_with(ancestor))
+        .unwrap_or(source_parent)
+        .to_path_buf()
+}
+
+/// Copy `source` to `dest`, writing the `rewritten` content (keyed by copy
+/// path) instead of the original bytes where there is one.
+fn copy_tree(source: &Path, dest: &Path, rewritten: &HashMap<PathBuf, String>) -> Result<()> {
+    for entry in WalkDir::new(source).sort_by_file_name() {
+        let entry = entry.map_err(|e| MdrefError::IoRead {
+            path: source.to_path_buf(),
+            source: e.into(),
+        })?;
+        let relative =
+            entry
+                .path()
+                .strip_prefix(source)
+                .map_err(|e| MdrefError::PathValidation {
+                    path: entry.path().to_path_buf(),
+                    details: format!("cannot compute relative path: {e}"),
+                })?;
+        let target = if relative.as_os_str().is_empty() {
+            dest.to_path_buf()
+        } else {
+            dest.join(relative)
+        };
+        let write_error = |e| MdrefError::IoWrite {
+            path: target.clone(),
+            source: e,
+        };
+
+        if entry.file_type().is_dir() {
+            fs::create_dir(&target).map_err(write_error)?;
+        } else if let Some(content) = rewritten.get(&target) {
+            write_atomic(&target, content.as_bytes()).map_err(write_error)?;
+            let permissions = fs::metadata(entry.path())?.permissions();
+            fs::set_permissions(&target, permissions).map_err(write_error)?;
+        } else {
+            fs::copy(entry.path(), &target).map_err(write_error)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Render a [`MovePreview`] as a unified diff that `git apply` understands.
+//!
+//! Each moved file gets `rename from` / `rename to` headers (a moved directory
+//! contributes one rename per file inside it), and every planned rewrite
+//! becomes a hunk with [`CONTEXT_LINES`] lines of context. Replacements never
+//! add or remove lines, so the original and rewritten files are compared line
+//! by line.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use super::{
+    model::{MoveChange, MovePreview},
+    mv::apply::rewrite_content,
+    pathdiff::diff_paths,
+    util::resolve_path,
+    wikilink::{absolute, path_to_slash},
+};
+use crate::{MdrefError, Result};
+
+/// Unchanged lines shown before and after each changed line.
+pub const CONTEXT_LINES: usize = 3;
+
+/// Render `preview` as a unified diff with paths relative to `root`.
+///
+/// Files are read from their pre-move locations, so this must run before the
+/// move is applied.
+pub(crate) fn render_move_preview(preview: &MovePreview, root: &Path) -> Result<String> {
+    let root = resolve(root)?;
+    let mut changes: HashMap<PathBuf, &MoveChange> = HashMap::new();
+    for change in &preview.changes {
+        changes.insert(resolve(&change.path)?, change);
+    }
+
+    let mut renames = Vec::new();
+    for (source, destination) in &preview.moves {
+        let source = resolve(source)?;
+        let destination = resolve(destination)?;
+        if source == destination {
+            continue;
+        }
+        if source.is_dir() {
+            for entry in WalkDir::new(&source).sort_by_file_name() {
+                let entry = entry.map_err(|e| MdrefError::PathValidation {
+                    path: source.clone(),
+                    details: format!("cannot walk directory: {e}"),
+                })?;
+                if entry.file_type().is_file()
+                    && let Ok(relative) = entry.path().strip_prefix(&source)
+                {
+                    renames.push((entry.path().to_path_buf(), destination.join(relative)));
+                }
+            }
+        } else {
+            renames.push((source, destination));
+        }
+    }
+
+    let mut output = String::new();
+
+    for (source, destination) in &renames {
+        let old_name = display_path(source, &root);
+        let new_name = display_path(destination, &root);
+        let _ = writeln!(output, "diff --git a/{old_name} b/{new_name}");
+        let _ = writeln!(output, "rename from {old_name}");
+        let _ = writeln!(output, "rename to {new_name}");
+
+        if let Some(change) = changes.remove(destination) {
+            write_file_diff(&mut output, source, &old_name, &new_name, change, false)?;
+        }
+    }
+
+    let mut remaining: Vec<_> = changes.into_iter().collect();
+    remaining.sort_by(|left, right| left.0.cmp(&right.0));
+    for (path, change) in remaining {
+        let name = display_path(&path, &root);
+        write_file_diff(&mut output, &path, &name, &name, change, true)?;
+    }
+
+    Ok(output)
+}
+
+/// Append the `---` / `+++` headers and hunks for one file, reading its
+/// original content from `original_path`. `with_git_header` adds the
+/// `diff --git` line for files that were not already introduced by a rename.
+fn write_file_diff(
+    output: &mut String,
+    original_path: &Path,
+    old_name: &str,
+    new_name: &str,
+    change: &MoveChange,
+    with_git_header: bool,
+) -> Result<()> {
+    let original = fs::read_to_string(original_path).map_err(|e| MdrefError::IoRead {
+        path: original_path.to_path_buf(),
+        source: e,
+    })?;
+    let rewritten = rewrite_content(original_path, &original, &change.replacements)?;
+    if original == rewritten {
+        return Ok(());
+    }
+
+    if with_git_header {
+        let _ = writeln!(output, "diff --git a/{old_name} b/{new_name}");
+    }
+    let _ = writeln!(output, "--- a/{old_name}");
+    let _ = writeln!(output, "+++ b/{new_name}");
+    write_hunks(output, &original, &rewritten);
+    Ok(())
+}
+
+/// Append unified-diff hunks turning `old` into `new`, which must have the
+/// same number of lines.
+pub(crate) fn write_hunks(output: &mut String, old: &str, new: &str) {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let changed: Vec<usize> = (0..old_lines.len())
+        .filter(|&index| new_lines.get(index) != Some(&old_lines[index]))
+        .collect();
+
+    let mut index = 0;
+    while index < changed.len() {
+        let start = changed[index].saturating_sub(CONTEXT_LINES);
+        let mut last = changed[index];
+        // Merge changes whose context windows touch or overlap.
+        while index + 1 < changed.len() && changed[index + 1] <= last + 2 * CONTEXT_LINES + 1 {
+            index += 1;
+            last = changed[index];
+        }
+        let end = (last + CONTEXT_LINES + 1).min(old_lines.len());
+        let count = end - start;
+
+        let _ = writeln!(
+            output,
+            "@@ -{},{count} +{},{count} @@",
+            start + 1,
+            start + 1
+        );
+        for (line, old_line) in old_lines.iter().enumerate().take(end).skip(start) {
+            match new_lines.get(line) {
+                Some(new_line) if new_line == old_line => push_line(output, ' ', old_line),
+                new_line => {
+                    push_line(output, '-', old_line);
+                    push_line(output, '+', new_line.copied().unwrap_or_default());
+                }
+            }
+        }
+        index += 1;
+    }
+}
+
+fn push_line(output: &mut String, prefix: char, line: &str) {
+    output.push(prefix);
+    output.push_str(line);
+    if !line.ends_with('\n') {
+        output.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+fn resolve(path: &Path) -> Result<PathBuf> {
+    resolve_path(&absolute(path))
+}
+
+/// `path` relative to `root` with `/` separators, as used in diff headers.
+fn display_path(path: &Path, root: &Path) -> String {
+    let relative = diff_paths(path, root).unwrap_or_else(|| path.to_path_buf());
+    path_to_slash(&relative).unwrap_or_else(|| relative.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_hunks_merges_nearby_changes_and_trims_context() {
+        let old = "1\n2\n3\n4\nold a\n6\n7\nold b\n9\n10\n11\n12\n13\n";
+        let new = "1\n2\n3\n4\nnew a\n6\n7\nnew b\n9\n10\n11\n12\n13\n";
+        let mut output = String::new();
+
+        write_hunks(&mut output, old, new);
+
+        assert_eq!(
+            output,
+            "@@ -2,10 +2,10 @@\n 2\n 3\n 4\n-old a\n+new a\n 6\n 7\n-old b\n+new b\n 9\n 10\n 11\n"
+        );
+    }
+
+    #[test]
+    fn test_write_hunks_marks_missing_trailing_newline() {
+        let mut output = String::new();
+
+        write_hunks(&mut output, "keep\n[A](a.md)", "keep\n[A](b.md)");
+
+        assert_eq!(
+            output,
+            "@@ -1,2 +1,2 @@\n keep\n-[A](a.md)\n\\ No newline at end of file\n+[A](b.md)\n\\ No newline at end of file\n"
+        );
+    }
+}
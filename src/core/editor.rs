@@ -0,0 +1,232 @@
+//! Building blocks for editor integrations such as `mdref lsp`.
+//!
+//! Editors hold documents whose unsaved contents differ from disk. The
+//! functions here take an [`Overlay`] of those contents and read overlaid
+//! files from it wherever the rest of the library would read them from disk,
+//! so answers match what the user sees. Other files are read from disk.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    anchor::{collect_anchors, collect_headings},
+    check::{AnchorIndex, check_file},
+    config::is_markdown,
+    find::{FileHashes, collect_local_links, find_references_matching},
+    graph::resolve_target,
+    model::{BrokenLink, Heading, MovePreview},
+    mv::{
+        plan::{LineCache, build_replacement, split_link_and_anchor},
+        preview_batch_in_overlay,
+    },
+    progress::ProgressReporter,
+    util::url_decode_link,
+    wikilink::{WikiVault, absolute},
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+/// Unsaved contents of files, keyed by absolute path, that take precedence
+/// over what is on disk.
+pub type Overlay = HashMap<PathBuf, String>;
+
+/// Like [`crate::find_references`], but files in `overlay` are read from there.
+///
+/// When `root_dir` has a link index, it still answers for the files on disk
+/// and only the overlaid files are parsed.
+///
+/// # Progress
+///
+/// Callers report progress through a [`ProgressReporter`] trait object. Pass
+/// [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+/// The reporter is called with [`ProgressReporter::set_total`] once before scanning,
+/// and with [`ProgressReporter::inc`] once per Markdown file as it is processed.
+pub fn find_references_in_overlay<P, B>(
+    path: P,
+    root_dir: B,
+    overlay: &Overlay,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<Reference>>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let canonical_path = path.canonicalize().map_err(|e| MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    Ok(find_references_matching(
+        &[canonical_path],
+        root_dir.as_ref(),
+        progress,
+        &mut FileHashes::new(),
+        overlay,
+    )?
+    .into_iter()
+    .map(|(reference, _)| reference)
+    .collect())
+}
+
+/// Check the local links of the Markdown file at `path` like
+/// [`crate::check_links`] does for a whole root.
+///
+/// The file, and any Markdown file its `#fragment`s point into, are read from
+/// `overlay` when present. Wikilinks resolve against the files under
+/// `root_dir`. The returned [`BrokenLink`]s are sorted by line and column.
+pub fn check_document<P, B>(
+    path: P,
+    root_dir: B,
+    check_anchors: bool,
+    overlay: &Overlay,
+) -> Result<Vec<BrokenLink>>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let content = read_document(path, overlay)?;
+    let vault = WikiVault::new(root_dir.as_ref());
+    let anchor_index: AnchorIndex = if check_anchors {
+        overlay
+            .iter()
+            .filter(|(path, _)| is_markdown(path))
+            .filter_map(|(path, content)| {
+                Some((path.canonicalize().ok()?, collect_anchors(content)))
+            })
+            .collect()
+    } else {
+        AnchorIndex::new()
+    };
+
+    let mut broken_links = check_file(path, &content, check_anchors, &anchor_index, &vault);
+    broken_links.sort_by_key(|link| (link.line, link.column));
+    Ok(broken_links)
+}
+
+/// The local link of `content`, the text of the file at `path`, under the
+/// 1-based `line` and byte `column`.
+///
+/// A link covers everything from its first character to the end of its URL,
+/// so the cursor may sit on the link text of `[text](url)` too.
+pub fn link_at<P: AsRef<Path>>(
+    path: P,
+    content: &str,
+    line: usize,
+    column: usize,
+) -> Option<Reference> {
+    let path = path.as_ref();
+    let mut cache = line_cache(path, content);
+    collect_local_links(content, path)
+        .into_iter()
+        .filter(|link| link.line == line && link.column <= column)
+        .find(|link| url_span(link, &mut cache).is_some_and(|(_, end)| column <= end + 1))
+}
+
+/// The byte range, within line [`Reference::line`] of `content`, of the part
+/// of `link` a move rewrites: the URL, or the target of a wikilink.
+pub fn link_span(content: &str, link: &Reference) -> Option<(usize, usize)> {
+    url_span(link, &mut line_cache(&link.path, content))
+}
+
+/// Resolve `link` to the existing file it points at, together with the
+/// heading its `#fragment` names when the target is a Markdown file.
+///
+/// Fragments match a heading's anchor, except on wikilinks, where they name
+/// the heading by its text. A pure `#fragment` points into the linking file.
+/// Headings are read from `overlay` when the target is overlaid.
+pub fn resolve_link_target<R: AsRef<Path>>(
+    link: &Reference,
+    root_dir: R,
+    overlay: &Overlay,
+) -> Option<(PathBuf, Option<Heading>)> {
+    let (url, fragment) = split_link_and_anchor(&link.link_text);
+    let target = if url.is_empty() {
+        link.path.clone()
+    } else {
+        resolve_target(&link.path, link, &WikiVault::new(root_dir.as_ref()))?
+    };
+
+    let heading = fragment
+        .filter(|fragment| !fragment.is_empty() && is_markdown(&target))
+        .and_then(|fragment| {
+            let content = read_document(&target, overlay).ok()?;
+            collect_headings(&content).into_iter().find(|heading| {
+                if link.link_type == LinkType::WikiLink {
+                    heading.text.eq_ignore_ascii_case(fragment.trim())
+                } else {
+                    heading.anchor == url_decode_link(fragment)
+                }
+            })
+        });
+
+    Some((target, heading))
+}
+
+/// Preview moving each `(source, destination)` pair like [`crate::preview_move`]
+/// (for a single pair) or [`crate::preview_move_many`], planning overlaid
+/// files from their overlaid contents.
+///
+/// Links that exist only in an overlaid file are rewritten too, and links
+/// deleted there are not. Paths in the preview are unchanged: a moved file's
+/// replacements are listed under its destination, see
+/// [`crate::core::util::pre_move_path`].
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_move_in_overlay<P, B, D>(
+    moves: &[(P, B)],
+    root: D,
+    overlay: &Overlay,
+    progress: &dyn ProgressReporter,
+) -> Result<MovePreview>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    let moves: Vec<(PathBuf, PathBuf)> = moves
+        .iter()
+        .map(|(source, dest)| (source.as_ref().to_path_buf(), dest.as_ref().to_path_buf()))
+        .collect();
+    preview_batch_in_overlay(&moves, root.as_ref(), overlay, progress)
+}
+
+fn read_document<'a>(path: &Path, overlay: &'a Overlay) -> Result<Cow<'a, str>> {
+    match overlay.get(&absolute(path)) {
+        Some(content) => Ok(Cow::Borrowed(content)),
+        None => fs::read_to_string(path)
+            .map(Cow::Owned)
+            .map_err(|e| MdrefError::IoRead {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+    }
+}
+
+fn line_cache(path: &Path, content: &str) -> LineCache {
+    LineCache::from([(
+        path.to_path_buf(),
+        content.lines().map(str::to_string).collect(),
+    )])
+}
+
+fn url_span(link: &Reference, cache: &mut LineCache) -> Option<(usize, usize)> {
+    let replacement = build_replacement(link, "", cache).ok()?;
+    let line = cache.get(&link.path)?.get(link.line.checked_sub(1)?)?;
+    let from = replacement.column.saturating_sub(1);
+    let start = from + line.get(from..)?.find(&replacement.old_pattern)?;
+    let end = start + replacement.old_pattern.len();
+
+    match link.link_type {
+        // The pattern of an inline link is `](url)`.
+        LinkType::Inline => Some((start + 2, end - 1)),
+        _ => Some((start, end)),
+    }
+}
@@ -1,29 +1,83 @@
 use std::{
-    collections::HashSet,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
 };
 
 use comrak::{
     Arena,
-    nodes::{AstNode, NodeValue},
+    nodes::{AstNode, NodeValue, Sourcepos},
     parse_document,
 };
 use rayon::prelude::*;
 
 use super::{
+    config::{ScanConfig, is_markdown},
+    editor::Overlay,
+    html::scan_links,
+    index::LinkIndex,
+    mdx::{MdxDocument, is_mdx},
     progress::ProgressReporter,
     util::{
-        collect_markdown_files, is_external_url, strip_anchor, strip_utf8_bom_prefix,
+        collect_files, content_hash, is_external_url, strip_anchor, strip_utf8_bom_prefix,
         url_decode_link,
     },
+    wikilink::{WikiVault, absolute, scan_wikilinks},
 };
-use crate::{Reference, Result};
+use crate::{LinkType, Reference, Result};
+
+/// Content hash of every scanned file that yielded links, keyed by the path
+/// it was read from. Lets a mutating operation notice a file that changed
+/// between scanning and writing.
+pub(crate) type FileHashes = HashMap<PathBuf, String>;
+
+/// A reference and the indices of the searched targets it refers to.
+pub(crate) type MatchedReference = (Reference, Vec<usize>);
+
+/// The searched targets keyed by canonical path, so a resolved link finds
+/// the targets it refers to with one lookup per ancestor instead of a
+/// comparison with every target.
+pub(crate) struct TargetLookup<'a> {
+    by_path: HashMap<&'a Path, Vec<usize>>,
+}
+
+impl<'a> TargetLookup<'a> {
+    pub(crate) fn new(targets_canonical: &'a [PathBuf]) -> Self {
+        let mut by_path: HashMap<&Path, Vec<usize>> = HashMap::new();
+        for (index, target) in targets_canonical.iter().enumerate() {
+            by_path.entry(target.as_path()).or_default().push(index);
+        }
+        Self { by_path }
+    }
+
+    /// The target paths, each listed once.
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &'a Path> + '_ {
+        self.by_path.keys().copied()
+    }
+
+    /// Indices of the targets that `canonical_link` is or lies inside of, in
+    /// ascending order.
+    pub(crate) fn matching(&self, canonical_link: &Path) -> Vec<usize> {
+        let mut matched: Vec<usize> = canonical_link
+            .ancestors()
+            .filter_map(|ancestor| self.by_path.get(ancestor))
+            .flatten()
+            .copied()
+            .collect();
+        matched.sort_unstable();
+        matched
+    }
+}
 
 /// Find all references to a given file within Markdown files in the specified root directory.
 ///
 /// Returns a vector of [`Reference`]s containing the referencing file path, line number,
-/// column number, and the link text.
+/// column number, and the link text. Wikilinks such as `[[Page]]` are resolved
+/// with vault semantics relative to `root_dir`; see [`WikiVault`].
+///
+/// When `root_dir` has a link index (see [`crate::core::index`]), it is
+/// refreshed and consulted instead of parsing every Markdown file.
 ///
 /// # Progress
 ///
@@ -40,58 +94,301 @@ where
     P: AsRef<Path>,
     B: AsRef<Path>,
 {
-    let canonical_path = path
-        .as_ref()
-        .canonicalize()
-        .map_err(|e| crate::MdrefError::IoRead {
-            path: path.as_ref().to_path_buf(),
-            source: e,
-        })?;
-    let markdown_files = collect_markdown_files(root_dir.as_ref());
+    find_references_with_hashes(
+        path.as_ref(),
+        root_dir.as_ref(),
+        progress,
+        &mut FileHashes::new(),
+    )
+}
+
+/// Like [`find_references`], but also records in `hashes` the content hash of
+/// every file a reference was found in.
+pub(crate) fn find_references_with_hashes(
+    path: &Path,
+    root_dir: &Path,
+    progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
+) -> Result<Vec<Reference>> {
+    let canonical_path = path.canonicalize().map_err(|e| crate::MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    find_references_to_any(&[canonical_path], root_dir, progress, hashes)
+}
+
+/// Find the references to each of `targets` with a single scan of
+/// `root_dir`, for example to audit which assets of a directory are used.
+///
+/// Returns one list per target, in the order of `targets`. A reference to a
+/// file inside a directory target is listed under the directory, and also
+/// under the file when both are targets. Links resolve like in
+/// [`find_references`], which also consults the link index.
+///
+/// # Progress
+///
+/// Callers report progress through a [`ProgressReporter`] trait object. Pass
+/// [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+/// The reporter is called with [`ProgressReporter::set_total`] once before scanning,
+/// and with [`ProgressReporter::inc`] once per Markdown file as it is processed.
+pub fn find_references_many<P, B>(
+    targets: &[P],
+    root_dir: B,
+    progress: &dyn ProgressReporter,
+) -> Result<Vec<Vec<Reference>>>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let targets_canonical = targets
+        .iter()
+        .map(|target| {
+            let target = target.as_ref();
+            target
+                .canonicalize()
+                .map_err(|e| crate::MdrefError::IoRead {
+                    path: target.to_path_buf(),
+                    source: e,
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut references_by_target: Vec<Vec<Reference>> = vec![Vec::new(); targets.len()];
+    for (reference, matched) in find_references_matching(
+        &targets_canonical,
+        root_dir.as_ref(),
+        progress,
+        &mut FileHashes::new(),
+        &Overlay::new(),
+    )? {
+        for index in matched {
+            references_by_target[index].push(reference.clone());
+        }
+    }
+
+    Ok(references_by_target)
+}
+
+/// Like [`find_references_with_hashes`], but collects references to any of
+/// `targets_canonical` (canonical paths) in a single scan of `root_dir`.
+pub(crate) fn find_references_to_any(
+    targets_canonical: &[PathBuf],
+    root_dir: &Path,
+    progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
+) -> Result<Vec<Reference>> {
+    Ok(find_references_matching(
+        targets_canonical,
+        root_dir,
+        progress,
+        hashes,
+        &Overlay::new(),
+    )?
+    .into_iter()
+    .map(|(reference, _)| reference)
+    .collect())
+}
+
+/// Scan `root_dir` once for references to any of `targets_canonical`,
+/// pairing each with the indices of the targets it refers to. Files in
+/// `overlay` are read from there instead of from disk.
+pub(super) fn find_references_matching(
+    targets_canonical: &[PathBuf],
+    root_dir: &Path,
+    progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
+    overlay: &Overlay,
+) -> Result<Vec<MatchedReference>> {
+    let files = collect_files(root_dir);
+    let vault = WikiVault::from_files(root_dir, &files);
+    let scan = ScanConfig::for_path(root_dir);
+    let markdown_files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|path| scan.is_markdown(path))
+        .collect();
 
     progress.set_total(markdown_files.len() as u64);
 
-    let results: Vec<Result<Vec<Reference>>> = markdown_files
+    let lookup = TargetLookup::new(targets_canonical);
+    let match_content = |path: &Path, content: &str| -> Vec<MatchedReference> {
+        collect_local_links(content, path)
+            .into_iter()
+            .filter_map(|reference| {
+                let canonical_link = resolve_link_target(
+                    path,
+                    Some(&vault),
+                    &reference.link_text,
+                    &reference.link_type,
+                )?;
+                let matched = lookup.matching(&canonical_link);
+                (!matched.is_empty()).then_some((reference, matched))
+            })
+            .collect()
+    };
+
+    if let Some(mut index) = LinkIndex::load(root_dir) {
+        index.refresh(root_dir, &markdown_files, progress)?;
+        // The index is only a cache; failing to persist it must not fail the lookup.
+        let _ = index.save(root_dir);
+        let mut references = index.references_matching(root_dir, &markdown_files, &lookup, &vault);
+        for (reference, _) in &references {
+            if let Some(hash) = index.file_hash(root_dir, &reference.path) {
+                hashes.insert(reference.path.clone(), hash.to_string());
+            }
+        }
+        if !overlay.is_empty() {
+            // The index describes the files on disk; re-match the overlaid ones.
+            references.retain(|(reference, _)| !overlay.contains_key(&absolute(&reference.path)));
+            for path in &markdown_files {
+                if let Some(content) = overlay.get(&absolute(path)) {
+                    references.extend(match_content(path, content));
+                }
+            }
+        }
+        return Ok(references);
+    }
+
+    let results: Vec<Result<(Vec<MatchedReference>, String)>> = markdown_files
         .par_iter()
         .map(|path| {
-            let content = fs::read_to_string(path).map_err(|e| crate::MdrefError::IoRead {
-                path: path.clone(),
-                source: e,
-            })?;
-            let refs = process_md_file(&content, path, Some(&canonical_path));
+            let content =
+                match overlay.get(&absolute(path)) {
+                    Some(content) => Cow::Borrowed(content.as_str()),
+                    None => Cow::Owned(fs::read_to_string(path).map_err(|e| {
+                        crate::MdrefError::IoRead {
+                            path: path.clone(),
+                            source: e,
+                        }
+                    })?),
+                };
+            let refs = match_content(path, &content);
             progress.inc(1);
-            Ok(refs)
+            Ok((refs, content_hash(content.as_bytes())))
         })
         .collect();
 
     let mut references = Vec::new();
-    for result in results {
-        references.extend(result?);
+    for (path, result) in markdown_files.iter().zip(results) {
+        let (refs, hash) = result?;
+        if !refs.is_empty() {
+            hashes.insert(path.clone(), hash);
+        }
+        references.extend(refs);
     }
 
     Ok(references)
 }
 
 /// Process a single Markdown file to find any file links.
+///
+/// Files without one of the Markdown extensions of the `.mdref.toml`
+/// governing them have no links.
 pub fn find_links<P: AsRef<Path>>(filepath: P) -> Result<Vec<Reference>> {
     let filepath = filepath.as_ref();
-
-    // Only markdown files are processed.
-    if filepath.extension().and_then(|s| s.to_str()) != Some("md") {
+    if !is_markdown(filepath) {
         return Ok(Vec::new());
     }
+    find_links_with_hash(filepath, &mut FileHashes::new())
+}
+
+/// Like [`find_links`], but also records the file's content hash in `hashes`
+/// when it has links. Callers make sure `filepath` is a Markdown file, as
+/// whole-root scans already know which files are.
+pub(crate) fn find_links_with_hash(
+    filepath: &Path,
+    hashes: &mut FileHashes,
+) -> Result<Vec<Reference>> {
     let content = fs::read_to_string(filepath).map_err(|e| crate::MdrefError::IoRead {
         path: filepath.to_path_buf(),
         source: e,
     })?;
-    Ok(process_md_file(&content, filepath, None))
+    let links = process_md_file(&content, filepath, None);
+    if !links.is_empty() {
+        hashes.insert(filepath.to_path_buf(), content_hash(content.as_bytes()));
+    }
+    Ok(links)
 }
 
 /// Process a single Markdown file's content to find links referencing the target file.
-fn process_md_file(
+pub(super) fn process_md_file(
     content: &str,
     file_path: &Path,
     target_canonical: Option<&Path>,
+) -> Vec<Reference> {
+    match_md_links(content, file_path, target_canonical, None)
+}
+
+/// Like [`process_md_file`], but resolves wikilinks against `vault`. Without a
+/// vault, wikilinks are only listed (when `target_canonical` is `None`).
+fn match_md_links(
+    content: &str,
+    file_path: &Path,
+    target_canonical: Option<&Path>,
+    vault: Option<&WikiVault>,
+) -> Vec<Reference> {
+    collect_md_links(content, file_path, &|url, link_type| {
+        link_matches_target(file_path, target_canonical, vault, url, link_type)
+    })
+}
+
+/// Determine whether a link of `link_type` found in `file_path` refers to
+/// `target_canonical` (or, when `None`, is a local link at all).
+pub(super) fn link_matches_target(
+    file_path: &Path,
+    target_canonical: Option<&Path>,
+    vault: Option<&WikiVault>,
+    url: &str,
+    link_type: &LinkType,
+) -> bool {
+    match link_type {
+        LinkType::WikiLink => process_wikilink(file_path, target_canonical, vault, url),
+        _ => process_link(file_path, target_canonical, url),
+    }
+}
+
+/// Collect every local link of a Markdown file's content, including pure
+/// fragment links such as `#section`. Only external URLs are skipped.
+pub(super) fn collect_local_links(content: &str, file_path: &Path) -> Vec<Reference> {
+    collect_md_links(content, file_path, &|url, _| !is_external_url(url))
+}
+
+/// Collect the links of a Markdown file's content whose URL passes `accept`.
+///
+/// MDX files are read through [`MdxDocument`]: links are found in the
+/// Markdown between their JSX, and relative imports are links too.
+fn collect_md_links(
+    content: &str,
+    file_path: &Path,
+    accept: &dyn Fn(&str, &LinkType) -> bool,
+) -> Vec<Reference> {
+    if !is_mdx(file_path) {
+        return collect_markdown_links(content, file_path, accept);
+    }
+
+    let document = MdxDocument::parse(content);
+    let mut results = collect_markdown_links(&document.markdown, file_path, accept);
+    for reference in &mut results {
+        reference.column = document.column(reference.line, reference.column);
+    }
+    for import in document.imports {
+        if accept(&import.specifier, &LinkType::Import) {
+            results.push(Reference::with_link_type(
+                file_path.to_path_buf(),
+                import.line,
+                import.column,
+                import.specifier,
+                LinkType::Import,
+            ));
+        }
+    }
+    results
+}
+
+fn collect_markdown_links(
+    content: &str,
+    file_path: &Path,
+    accept: &dyn Fn(&str, &LinkType) -> bool,
 ) -> Vec<Reference> {
     let arena = Arena::new();
     let root = parse_document(&arena, content, &comrak::Options::default());
@@ -107,17 +404,32 @@ fn process_md_file(
     // We detect reference-style links by checking if the source text at the
     // node's position contains the `](` pattern (inline) vs `][` pattern (reference).
     let mut results = Vec::new();
-    collect_links(root, file_path, target_canonical, content, &mut results);
+    collect_links(root, file_path, accept, content, &mut results);
 
     // Step 3: Add reference definitions as References.
     for (line_number, url, column) in &ref_defs {
-        if process_link(file_path, target_canonical, url) {
+        if accept(url, &LinkType::ReferenceDefinition) {
             results.push(Reference::with_link_type(
                 file_path.to_path_buf(),
                 *line_number,
                 *column,
                 url.clone(),
-                crate::LinkType::ReferenceDefinition,
+                LinkType::ReferenceDefinition,
+            ));
+        }
+    }
+
+    // Step 4: Add wikilinks and embeds. comrak does not parse embeds, so they
+    // are scanned from raw text, skipping code blocks and code spans.
+    let code_spans = collect_code_spans(root);
+    for link in scan_wikilinks(content, &ignored_lines, &code_spans) {
+        if accept(&link.target, &LinkType::WikiLink) {
+            results.push(Reference::with_link_type(
+                file_path.to_path_buf(),
+                link.line,
+                link.column,
+                link.target,
+                LinkType::WikiLink,
             ));
         }
     }
@@ -125,6 +437,63 @@ fn process_md_file(
     results
 }
 
+/// Locate every inline or reference-style link and image in `content` whose
+/// URL passes `accept`, as `(line, start, end)`: the 1-based line and the
+/// byte range of the whole `[text](url)`, `![alt](url)` or `[text][label]` on
+/// it. Links that span several lines are returned with `start == end`.
+///
+/// `file_path` decides whether `content` is read as MDX.
+pub(crate) fn find_link_spans(
+    content: &str,
+    file_path: &Path,
+    accept: &dyn Fn(&str) -> bool,
+) -> Vec<(usize, usize, usize)> {
+    if is_mdx(file_path) {
+        let document = MdxDocument::parse(content);
+        return find_markdown_link_spans(&document.markdown, accept)
+            .into_iter()
+            .map(|(line, start, end)| {
+                let indent = document.indent(line);
+                (line, start + indent, end + indent)
+            })
+            .collect();
+    }
+    find_markdown_link_spans(content, accept)
+}
+
+fn find_markdown_link_spans(
+    content: &str,
+    accept: &dyn Fn(&str) -> bool,
+) -> Vec<(usize, usize, usize)> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &comrak::Options::default());
+    let mut spans = Vec::new();
+
+    for node in root.descendants() {
+        let data = node.data.borrow();
+        let url = match &data.value {
+            NodeValue::Link(link) => &link.url,
+            NodeValue::Image(image) => &image.url,
+            _ => continue,
+        };
+        if !accept(url) {
+            continue;
+        }
+
+        let start = data.sourcepos.start;
+        let end = data.sourcepos.end;
+        let start_offset = start.column.saturating_sub(1);
+        let end_offset = if start.line == end.line {
+            end.column
+        } else {
+            start_offset
+        };
+        spans.push((start.line, start_offset, end_offset));
+    }
+
+    spans
+}
+
 /// Parse link reference definitions from raw Markdown text.
 ///
 /// A link reference definition has the form:
@@ -227,6 +596,15 @@ fn collect_code_block_lines<'a>(node: &'a AstNode<'a>, ignored_lines: &mut HashS
     }
 }
 
+fn collect_code_spans<'a>(root: &'a AstNode<'a>) -> Vec<Sourcepos> {
+    root.descendants()
+        .filter_map(|node| {
+            let data = node.data.borrow();
+            matches!(data.value, NodeValue::Code(_)).then_some(data.sourcepos)
+        })
+        .collect()
+}
+
 /// Recursively collect links from the AST.
 ///
 /// For each `NodeValue::Link`, we check the original source text at the node's
@@ -236,7 +614,7 @@ fn collect_code_block_lines<'a>(node: &'a AstNode<'a>, ignored_lines: &mut HashS
 fn collect_links<'a>(
     node: &'a AstNode<'a>,
     file_path: &Path,
-    target_canonical: Option<&Path>,
+    accept: &dyn Fn(&str, &LinkType) -> bool,
     source_content: &str,
     results: &mut Vec<Reference>,
 ) {
@@ -249,7 +627,7 @@ fn collect_links<'a>(
             // by inspecting the source text at the node's position.
             // Reference-style links are handled via definition-line scanning.
             if !is_reference_style_link(source_content, &data.sourcepos)
-                && process_link(file_path, target_canonical, url)
+                && accept(url, &LinkType::Inline)
             {
                 let line = data.sourcepos.start.line;
                 let column = data.sourcepos.start.column;
@@ -263,7 +641,7 @@ fn collect_links<'a>(
         }
         NodeValue::Image(image) => {
             let url = &image.url;
-            if process_link(file_path, target_canonical, url) {
+            if accept(url, &LinkType::Inline) {
                 let line = data.sourcepos.start.line;
                 let column = data.sourcepos.start.column;
                 results.push(Reference::new(
@@ -274,11 +652,87 @@ fn collect_links<'a>(
                 ));
             }
         }
+        NodeValue::HtmlBlock(block) => {
+            collect_html_links(
+                &block.literal,
+                &data.sourcepos,
+                file_path,
+                accept,
+                source_content,
+                results,
+            );
+        }
+        NodeValue::HtmlInline(literal) => {
+            collect_html_links(
+                literal,
+                &data.sourcepos,
+                file_path,
+                accept,
+                source_content,
+                results,
+            );
+        }
         _ => {}
     }
 
     for child in node.children() {
-        collect_links(child, file_path, target_canonical, source_content, results);
+        collect_links(child, file_path, accept, source_content, results);
+    }
+}
+
+/// Collect links from the attributes of a raw HTML node, e.g. `<img src="...">`.
+///
+/// comrak strips container prefixes (such as `> ` in block quotes) from the
+/// literal, so each literal line is located in the source line to compute a
+/// column that points at the first character of the URL.
+fn collect_html_links(
+    literal: &str,
+    sourcepos: &Sourcepos,
+    file_path: &Path,
+    accept: &dyn Fn(&str, &LinkType) -> bool,
+    source_content: &str,
+    results: &mut Vec<Reference>,
+) {
+    for link in scan_links(literal) {
+        if !accept(link.url, &LinkType::HtmlAttribute) {
+            continue;
+        }
+
+        let before = &literal[..link.start];
+        let line_delta = before.matches('\n').count();
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line_end = literal[line_start..]
+            .find('\n')
+            .map_or(literal.len(), |newline| line_start + newline);
+        let literal_line = literal[line_start..line_end].trim_end_matches('\r');
+
+        let line = sourcepos.start.line + line_delta;
+        let Some(source_line) = source_content.lines().nth(line.saturating_sub(1)) else {
+            continue;
+        };
+        let hint = sourcepos.start.column.saturating_sub(1);
+        let offset = if line_delta == 0
+            && source_line
+                .get(hint..)
+                .is_some_and(|rest| rest.starts_with(literal_line))
+        {
+            Some(hint)
+        } else if source_line.ends_with(literal_line) {
+            Some(source_line.len() - literal_line.len())
+        } else {
+            source_line.find(literal_line)
+        };
+        let Some(offset) = offset else {
+            continue;
+        };
+
+        results.push(Reference::with_link_type(
+            file_path.to_path_buf(),
+            line,
+            offset + link.start - line_start + 1,
+            link.url.to_string(),
+            LinkType::HtmlAttribute,
+        ));
     }
 }
 
@@ -360,6 +814,47 @@ fn process_link(file_path: &Path, target_canonical: Option<&Path>, link: &str) -
     match_link_to_target(&canonical_link, target)
 }
 
+/// Determine whether a wikilink target (found in `file_path`) refers to `target_canonical`.
+///
+/// Behaves like [`process_link`], except that the target is resolved through
+/// `vault`. Without a vault, only listing (`target_canonical == None`) succeeds.
+fn process_wikilink(
+    file_path: &Path,
+    target_canonical: Option<&Path>,
+    vault: Option<&WikiVault>,
+    link: &str,
+) -> bool {
+    let Some(link_without_anchor) = strip_anchor(link) else {
+        return false;
+    };
+
+    let Some(target) = target_canonical else {
+        return true;
+    };
+
+    resolve_link_target(file_path, vault, link_without_anchor, &LinkType::WikiLink)
+        .is_some_and(|canonical_link| match_link_to_target(&canonical_link, target))
+}
+
+/// The canonical path a local link of `link_type` in `file_path` points to,
+/// or `None` for external and fragment-only links and for links that do not
+/// resolve. Wikilinks resolve through `vault`, and never without one.
+pub(crate) fn resolve_link_target(
+    file_path: &Path,
+    vault: Option<&WikiVault>,
+    url: &str,
+    link_type: &LinkType,
+) -> Option<PathBuf> {
+    match link_type {
+        LinkType::WikiLink => vault?
+            .resolve(file_path, strip_anchor(url)?)?
+            .canonicalize()
+            .ok(),
+        _ if is_external_url(url) => None,
+        _ => resolve_and_canonicalize_link(file_path, strip_anchor(url)?),
+    }
+}
+
 /// Resolve a link path and canonicalize it.
 ///
 /// Returns `None` if the link cannot be resolved or canonicalized.
@@ -389,7 +884,7 @@ fn match_link_to_target(canonical_link: &Path, target: &Path) -> bool {
 /// Handles both absolute and relative links.
 /// For relative links, resolves them relative to the base file's parent directory.
 /// Also handles URL-encoded characters in the link path (e.g., `%20` for space).
-fn resolve_link(base_path: &Path, link_path: &Path) -> Option<PathBuf> {
+pub(super) fn resolve_link(base_path: &Path, link_path: &Path) -> Option<PathBuf> {
     if link_path.is_absolute() {
         return Some(link_path.to_path_buf());
     }
@@ -952,4 +1447,51 @@ mod tests {
             link_texts
         );
     }
+
+    #[test]
+    fn test_process_md_file_collects_wikilinks_outside_code() {
+        let content =
+            "[[Note]] ![[img.png|100]] [[#Local]]\n\n`[[code]]`\n\n```\n[[fenced]]\n```\n";
+        let results = process_md_file(content, Path::new("test.md"), None);
+
+        let links: Vec<(&str, usize, usize)> = results
+            .iter()
+            .map(|r| (r.link_text.as_str(), r.line, r.column))
+            .collect();
+        assert_eq!(links, vec![("Note", 1, 1), ("img.png", 1, 10)]);
+        assert!(results.iter().all(|r| r.link_type == LinkType::WikiLink));
+    }
+
+    #[test]
+    fn test_process_md_file_collects_html_attribute_links_with_columns() {
+        let content = "<p align=\"center\">\n  <img src=\"docs/logo.png\" srcset=\"docs/logo@2x.png 2x\">\n</p>\n\nSee <a href='guide.md#install'>the guide</a>.\n\n> <img src=\"quoted.png\">\n\n`<img src=\"code.png\">`\n";
+        let results = process_md_file(content, Path::new("test.md"), None);
+
+        let links: Vec<(&str, usize, usize)> = results
+            .iter()
+            .map(|r| (r.link_text.as_str(), r.line, r.column))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("docs/logo.png", 2, 13),
+                ("docs/logo@2x.png", 2, 36),
+                ("guide.md#install", 5, 14),
+                ("quoted.png", 7, 13),
+            ]
+        );
+        assert!(
+            results
+                .iter()
+                .all(|r| r.link_type == LinkType::HtmlAttribute)
+        );
+    }
+
+    #[test]
+    fn test_process_md_file_html_attribute_filters_external_and_anchor_links() {
+        let content = "<a href=\"https://example.com\"><img src=\"//cdn.example.com/x.png\"></a> <a href=\"#top\">top</a>\n";
+        let results = process_md_file(content, Path::new("test.md"), None);
+
+        assert!(results.is_empty(), "Got: {:?}", results);
+    }
 }
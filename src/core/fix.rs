@@ -0,0 +1,531 @@
+//! Repair links broken by moves made outside mdref.
+//!
+//! A link whose target no longer exists cannot be canonicalized, so
+//! [`crate::find_references`] never sees it. `fix` starts from the broken links
+//! reported by [`crate::check_links`] instead and looks for the file each one
+//! meant: first a file with the content the link index recorded for the old
+//! path, then the only file with the old file name, and among several such
+//! files the one whose path is most similar to the old one. Links without a
+//! single best candidate are left for manual review. Wikilinks resolve by name
+//! anywhere in the vault, so a broken one means the page itself was renamed;
+//! they are always left for review.
+//!
+//! When the files were moved with `git mv`, git already knows where they went:
+//! [`fix_from_git`] reads the renames since a revision and replays them as if
+//! `mv` had made them, without guessing. [`fix_moved`] does the same for
+//! moves reported some other way, such as by `mdref watch`.
+
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use super::{
+    check::check_links,
+    config::ScanConfig,
+    find::FileHashes,
+    git::renames_since,
+    index::LinkIndex,
+    journal::record_operation,
+    model::{BrokenLinkKind, FixMatch, FixPreview, LinkFix, MoveChangeKind, MoveTransaction},
+    mv::{
+        apply::{apply_replacements, execute_with_rollback, verify_snapshots},
+        plan::{
+            LineCache, ReplacementPlan, build_replacement, plan_replayed_replacements,
+            split_link_and_anchor,
+        },
+        preview::{build_move_changes, print_move_changes},
+    },
+    progress::ProgressReporter,
+    recover::WriteAheadLog,
+    util::{collect_files, content_hash, relative_path, resolve_keeping_name, url_decode_link},
+    wikilink::absolute,
+};
+use crate::{LinkType, Reference, Result};
+
+/// Point every broken link under `root` that has a single best candidate at
+/// that candidate.
+///
+/// Candidates are found as described in the [module docs](self); the content
+/// hash step needs a link index built with [`crate::build_index`] before the
+/// files were moved. Links that need manual review are left unchanged. All
+/// file writes are rolled back if any of them fails, and a completed fix is
+/// recorded in the undo journal under `root`.
+///
+/// When `dry_run` is `true`, no files are modified. Instead, the function
+/// prints all changes that *would* be made.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn fix<P: AsRef<Path>>(root: P, dry_run: bool, progress: &dyn ProgressReporter) -> Result<()> {
+    let root = root.as_ref();
+    let (preview, replacements_by_file) = plan_fix(root, progress)?;
+
+    if dry_run {
+        print_dry_run_report(&preview);
+        return Ok(());
+    }
+    apply_fix(root, &replacements_by_file, &FileHashes::new())
+}
+
+/// Rewrite the links under `root` affected by the files git detected as
+/// renamed between `rev` and the working tree, as if `mv` had moved them.
+///
+/// Renames come from `git diff --name-status -M <rev>`, so both committed and
+/// staged `git mv`s are included. Nothing is moved on disk: inbound links to
+/// the renamed files and the renamed files' own relative links and wikilinks
+/// are rewritten in place. Renames whose source exists again or whose
+/// destination is gone are ignored. As with [`fix`], the writes are rolled
+/// back together on failure and recorded in the undo journal.
+///
+/// When `dry_run` is `true`, no files are modified. Instead, the function
+/// prints all changes that *would* be made.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn fix_from_git<P, R>(
+    root: P,
+    rev: R,
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
+where
+    P: AsRef<Path>,
+    R: AsRef<str>,
+{
+    let root = root.as_ref();
+    let (preview, replacements_by_file, scanned_hashes) =
+        plan_fix_from_git(root, rev.as_ref(), progress)?;
+
+    if dry_run {
+        print_dry_run_report(&preview);
+        return Ok(());
+    }
+    apply_fix(root, &replacements_by_file, &scanned_hashes)
+}
+
+fn apply_fix(
+    root: &Path,
+    replacements_by_file: &ReplacementPlan,
+    scanned_hashes: &FileHashes,
+) -> Result<()> {
+    if replacements_by_file.is_empty() {
+        return Ok(());
+    }
+
+    let mut transaction = MoveTransaction::for_batch();
+    for file_path in replacements_by_file.keys() {
+        transaction.snapshot_file(file_path)?;
+    }
+    verify_snapshots(&transaction, scanned_hashes)?;
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root,
+        "fix",
+        &[],
+        replacements_by_file,
+    )?);
+
+    execute_with_rollback(&transaction, || {
+        for (file_path, replacements) in replacements_by_file {
+            apply_replacements(file_path, replacements)?;
+        }
+        Ok(())
+    })?;
+
+    transaction.commit()?;
+    record_operation(root, "fix", &[], &transaction.file_snapshots);
+    Ok(())
+}
+
+/// Find the broken links under `root` and their candidate targets without
+/// mutating the filesystem.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_fix<P: AsRef<Path>>(root: P, progress: &dyn ProgressReporter) -> Result<FixPreview> {
+    Ok(plan_fix(root.as_ref(), progress)?.0)
+}
+
+/// Preview [`fix_from_git`] without mutating the filesystem.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_fix_from_git<P, R>(
+    root: P,
+    rev: R,
+    progress: &dyn ProgressReporter,
+) -> Result<FixPreview>
+where
+    P: AsRef<Path>,
+    R: AsRef<str>,
+{
+    Ok(plan_fix_from_git(root.as_ref(), rev.as_ref(), progress)?.0)
+}
+
+/// Rewrite the links under `root` affected by `moves` that were already made
+/// on disk, as if `mv` had made them.
+///
+/// This is [`fix_from_git`] with the renames given instead of read from git:
+/// nothing is moved, and moves whose source exists again or whose destination
+/// is gone are ignored. The writes are rolled back together on failure and
+/// recorded in the undo journal.
+///
+/// When `dry_run` is `true`, no files are modified. Instead, the function
+/// prints all changes that *would* be made.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn fix_moved<R, P, B>(
+    root: R,
+    moves: &[(P, B)],
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
+where
+    R: AsRef<Path>,
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let (preview, replacements_by_file, scanned_hashes) = plan_fix_moved(root, moves, progress)?;
+
+    if dry_run {
+        print_dry_run_report(&preview);
+        return Ok(());
+    }
+    apply_fix(root, &replacements_by_file, &scanned_hashes)
+}
+
+/// Preview [`fix_moved`] without mutating the filesystem.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_fix_moved<R, P, B>(
+    root: R,
+    moves: &[(P, B)],
+    progress: &dyn ProgressReporter,
+) -> Result<FixPreview>
+where
+    R: AsRef<Path>,
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    Ok(plan_fix_moved(root.as_ref(), moves, progress)?.0)
+}
+
+fn plan_fix_from_git(
+    root: &Path,
+    rev: &str,
+    progress: &dyn ProgressReporter,
+) -> Result<(FixPreview, ReplacementPlan, FileHashes)> {
+    plan_fix_moved(root, &renames_since(root, rev)?, progress)
+}
+
+fn plan_fix_moved<P: AsRef<Path>, B: AsRef<Path>>(
+    root: &Path,
+    moves: &[(P, B)],
+    progress: &dyn ProgressReporter,
+) -> Result<(FixPreview, ReplacementPlan, FileHashes)> {
+    let mut renames = Vec::new();
+    for (source, destination) in moves {
+        let (source, destination) = (source.as_ref(), destination.as_ref());
+        if source.exists() || !destination.exists() {
+            continue;
+        }
+        renames.push((resolve_keeping_name(source)?, destination.canonicalize()?));
+    }
+
+    let mut scanned_hashes = FileHashes::new();
+    let replacements_by_file =
+        plan_replayed_replacements(&renames, root, progress, &mut scanned_hashes)?;
+    let changes = build_move_changes(replacements_by_file.clone(), |path| {
+        if renames
+            .iter()
+            .any(|(_, destination)| absolute(path).starts_with(destination))
+        {
+            MoveChangeKind::MovedFileUpdate
+        } else {
+            MoveChangeKind::ReferenceUpdate
+        }
+    });
+
+    Ok((
+        FixPreview {
+            root: root.to_path_buf(),
+            fixes: Vec::new(),
+            renames,
+            changes,
+        },
+        replacements_by_file,
+        scanned_hashes,
+    ))
+}
+
+fn plan_fix(root: &Path, progress: &dyn ProgressReporter) -> Result<(FixPreview, ReplacementPlan)> {
+    let broken_links = check_links(root, false, progress)?;
+    let finder = CandidateFinder::new(root);
+    let mut cache: HashMap<PathBuf, (Vec<PathBuf>, Option<FixMatch>)> = HashMap::new();
+    let mut line_cache = LineCache::new();
+    let mut replacements_by_file = ReplacementPlan::new();
+    let mut fixes = Vec::new();
+
+    for link in broken_links {
+        if link.kind != BrokenLinkKind::MissingTarget {
+            continue;
+        }
+
+        let (link_path_only, anchor) = split_link_and_anchor(&link.link_text);
+        let anchor = anchor.map(str::to_string);
+        let (candidates, matched_by) = if link.link_type == LinkType::WikiLink {
+            (Vec::new(), None)
+        } else {
+            let old_target = old_target_path(&link.path, link_path_only);
+            cache
+                .entry(old_target)
+                .or_insert_with_key(|old_target| finder.candidates_for(old_target))
+                .clone()
+        };
+
+        let fix = LinkFix {
+            link,
+            candidates,
+            matched_by,
+        };
+        if let Some(target) = fix.target() {
+            let new_link_path = relative_path(&absolute(&fix.link.path), target)?;
+            let new_url = match &anchor {
+                Some(anchor) => format!("{}#{}", new_link_path.display(), anchor),
+                None => new_link_path.display().to_string(),
+            };
+            let reference = Reference::with_link_type(
+                fix.link.path.clone(),
+                fix.link.line,
+                fix.link.column,
+                fix.link.link_text.clone(),
+                fix.link.link_type.clone(),
+            );
+            replacements_by_file
+                .entry(fix.link.path.clone())
+                .or_default()
+                .push(build_replacement(&reference, &new_url, &mut line_cache)?);
+        }
+        fixes.push(fix);
+    }
+
+    let changes = build_move_changes(replacements_by_file.clone(), |_| {
+        MoveChangeKind::ReferenceUpdate
+    });
+
+    Ok((
+        FixPreview {
+            root: root.to_path_buf(),
+            fixes,
+            renames: Vec::new(),
+            changes,
+        },
+        replacements_by_file,
+    ))
+}
+
+/// The absolute path a local link written in `base_file` pointed at.
+fn old_target_path(base_file: &Path, link_path: &str) -> PathBuf {
+    let decoded = PathBuf::from(url_decode_link(link_path));
+    let joined = if decoded.is_absolute() {
+        decoded
+    } else {
+        base_file.parent().unwrap_or(Path::new("")).join(decoded)
+    };
+    resolve_keeping_name(&joined).unwrap_or_else(|_| absolute(&joined))
+}
+
+/// Looks up candidate targets among the files under a root.
+struct CandidateFinder {
+    root: PathBuf,
+    files: Vec<PathBuf>,
+    index: Option<LinkIndex>,
+    /// Content hashes of the Markdown files, computed on first use.
+    hashes: OnceCell<HashMap<PathBuf, String>>,
+}
+
+impl CandidateFinder {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: absolute(root),
+            files: collect_files(root),
+            index: LinkIndex::load(root),
+            hashes: OnceCell::new(),
+        }
+    }
+
+    /// Candidates for `old_target` (absolute), best first, and how the first
+    /// one was chosen.
+    fn candidates_for(&self, old_target: &Path) -> (Vec<PathBuf>, Option<FixMatch>) {
+        let recorded_hash = self
+            .index
+            .as_ref()
+            .and_then(|index| index.recorded_hash(&self.root, old_target));
+        if let Some(hash) = recorded_hash {
+            let same_content: Vec<PathBuf> = self
+                .hashes()
+                .iter()
+                .filter(|(_, file_hash)| *file_hash == hash)
+                .map(|(path, _)| path.clone())
+                .collect();
+            if !same_content.is_empty() {
+                return self.rank(old_target, same_content, FixMatch::ContentHash);
+            }
+        }
+
+        let Some(name) = old_target.file_name().and_then(|name| name.to_str()) else {
+            return (Vec::new(), None);
+        };
+        let with_name = |matches: &dyn Fn(&str) -> bool| -> Vec<PathBuf> {
+            self.files
+                .iter()
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|file_name| file_name.to_str())
+                        .is_some_and(matches)
+                })
+                .cloned()
+                .collect()
+        };
+        let mut same_name = with_name(&|file_name| file_name == name);
+        if same_name.is_empty() {
+            same_name = with_name(&|file_name| file_name.eq_ignore_ascii_case(name));
+        }
+
+        self.rank(old_target, same_name, FixMatch::UniqueFilename)
+    }
+
+    /// Order `candidates` by similarity to `old_target`. A single candidate is
+    /// matched by `single`; otherwise the most similar one wins unless it ties.
+    fn rank(
+        &self,
+        old_target: &Path,
+        mut candidates: Vec<PathBuf>,
+        single: FixMatch,
+    ) -> (Vec<PathBuf>, Option<FixMatch>) {
+        if candidates.len() <= 1 {
+            let matched_by = (!candidates.is_empty()).then_some(single);
+            return (candidates, matched_by);
+        }
+
+        let old_components = self.components(old_target);
+        let mut scored: Vec<((usize, usize), PathBuf)> = candidates
+            .drain(..)
+            .map(|path| {
+                let score = similarity(&old_components, &self.components(&absolute(&path)));
+                (score, path)
+            })
+            .collect();
+        scored.sort_by(|left, right| right.0.cmp(&left.0).then(left.1.cmp(&right.1)));
+
+        let matched_by = (scored[0].0 > scored[1].0).then_some(FixMatch::PathSimilarity);
+        (
+            scored.into_iter().map(|(_, path)| path).collect(),
+            matched_by,
+        )
+    }
+
+    /// Lowercased components of `path` relative to the root.
+    fn components(&self, path: &Path) -> Vec<String> {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().to_lowercase()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn hashes(&self) -> &HashMap<PathBuf, String> {
+        self.hashes.get_or_init(|| {
+            let scan = ScanConfig::for_path(&self.root);
+            self.files
+                .par_iter()
+                .filter(|path| scan.is_markdown(path))
+                .filter_map(|path| Some((path.clone(), content_hash(&fs::read(path).ok()?))))
+                .collect()
+        })
+    }
+}
+
+/// How alike two paths are: the number of trailing components they share,
+/// then the number of leading ones.
+fn similarity(left: &[String], right: &[String]) -> (usize, usize) {
+    let trailing = left
+        .iter()
+        .rev()
+        .zip(right.iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let leading = left
+        .iter()
+        .zip(right)
+        .take_while(|(left, right)| left == right)
+        .count();
+    (trailing, leading)
+}
+
+/// Print the replayed renames or the planned fixes and the links left for
+/// manual review, followed by the rewrites.
+fn print_dry_run_report(preview: &FixPreview) {
+    for (source, destination) in &preview.renames {
+        println!(
+            "[dry-run] Replaying git rename: {} -> {}",
+            source.display(),
+            destination.display()
+        );
+    }
+    for fix in &preview.fixes {
+        match fix.target() {
+            Some(target) => println!(
+                "[dry-run] Would fix {} -> {} ({})",
+                fix.link,
+                target.display(),
+                fix.matched_by.map_or("", FixMatch::description)
+            ),
+            None => println!("[dry-run] Needs manual review: {}", fix.link),
+        }
+    }
+
+    print_move_changes(&preview.changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn components(path: &str) -> Vec<String> {
+        path.split('/').map(str::to_string).collect()
+    }
+
+    #[rstest]
+    #[case::same_directory("docs/guide/setup.md", "docs/guide/setup.md", (3, 3))]
+    #[case::moved_parent("docs/guide/setup.md", "archive/guide/setup.md", (2, 0))]
+    #[case::same_root("docs/guide/setup.md", "docs/setup.md", (1, 1))]
+    #[case::unrelated("a/setup.md", "b/other.md", (0, 0))]
+    fn test_similarity(#[case] left: &str, #[case] right: &str, #[case] expected: (usize, usize)) {
+        assert_eq!(similarity(&components(left), &components(right)), expected);
+    }
+}
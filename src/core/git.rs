@@ -0,0 +1,207 @@
+//! Read file renames from git history and move files with `git mv`, through
+//! the local `git` CLI.
+
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{MdrefError, Result};
+
+/// The files git detected as renamed between `rev` and the working tree of
+/// the repository containing `root`, as absolute `(source, destination)`
+/// pairs.
+///
+/// Runs `git diff --name-status -M <rev>`, which compares `<rev>` with the
+/// tracked files of the working tree. A rename is only seen once its
+/// destination is tracked, that is committed or staged (`git mv`, or
+/// `git add` of both paths); after a plain `mv` the destination is untracked
+/// and the source shows up as deleted.
+pub(crate) fn renames_since(root: &Path, rev: &str) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let toplevel = run_git(root, &["rev-parse", "--show-toplevel"])?;
+    let toplevel = PathBuf::from(toplevel.trim_end_matches(['\n', '\r']));
+    let output = run_git(root, &["diff", "--name-status", "-z", "-M", rev, "--"])?;
+
+    Ok(parse_renames(&output)
+        .into_iter()
+        .map(|(source, destination)| (toplevel.join(source), toplevel.join(destination)))
+        .collect())
+}
+
+/// The git work tree a move runs in when it is performed with `git mv`.
+#[derive(Debug, Clone)]
+pub(crate) struct GitWorkTree {
+    toplevel: PathBuf,
+}
+
+impl GitWorkTree {
+    /// The work tree containing `path`, which must exist. Fails when `path`
+    /// is not inside one or git is not installed.
+    pub(crate) fn discover(path: &Path) -> Result<Self> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+        };
+        let toplevel = run_git(dir, &["rev-parse", "--show-toplevel"])?;
+        Ok(Self {
+            toplevel: PathBuf::from(toplevel.trim_end_matches(['\n', '\r'])),
+        })
+    }
+
+    /// Whether git tracks `path`, or for a directory, any file inside it.
+    pub(crate) fn is_tracked(&self, path: &Path) -> Result<bool> {
+        let path = absolute(path)?;
+        let output = self.run(&[
+            "ls-files".as_ref(),
+            "-z".as_ref(),
+            "--".as_ref(),
+            path.as_os_str(),
+        ])?;
+        Ok(!output.is_empty())
+    }
+
+    /// Whether a tracked file at or under `path` has staged or unstaged
+    /// changes. Untracked files are not counted.
+    pub(crate) fn has_uncommitted_changes(&self, path: &Path) -> Result<bool> {
+        let path = absolute(path)?;
+        let output = self.run(&[
+            "status".as_ref(),
+            "--porcelain".as_ref(),
+            "--untracked-files=no".as_ref(),
+            "--".as_ref(),
+            path.as_os_str(),
+        ])?;
+        Ok(!output.is_empty())
+    }
+
+    /// Move `from` to `to` with `git mv`, so the index records the rename.
+    pub(crate) fn mv(&self, from: &Path, to: &Path) -> Result<()> {
+        let (from, to) = (absolute(from)?, absolute(to)?);
+        self.run(&[
+            "mv".as_ref(),
+            "--".as_ref(),
+            from.as_os_str(),
+            to.as_os_str(),
+        ])?;
+        Ok(())
+    }
+
+    /// Stage the current content of the tracked files among `paths`.
+    /// Untracked files stay untracked.
+    pub(crate) fn stage<'a>(&self, paths: impl IntoIterator<Item = &'a PathBuf>) -> Result<()> {
+        let paths = paths
+            .into_iter()
+            .map(|path| absolute(path))
+            .collect::<Result<Vec<_>>>()?;
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut args: Vec<&OsStr> = vec!["add".as_ref(), "--update".as_ref(), "--".as_ref()];
+        args.extend(paths.iter().map(|path| path.as_os_str()));
+        self.run(&args)?;
+        Ok(())
+    }
+
+    /// The path of the index file and its current content, or `None` when
+    /// the repository has no index yet.
+    pub(crate) fn read_index(&self) -> Result<(PathBuf, Option<Vec<u8>>)> {
+        let index = self.run(&[
+            "rev-parse".as_ref(),
+            "--git-path".as_ref(),
+            "index".as_ref(),
+        ])?;
+        let index = self.toplevel.join(index.trim_end_matches(['\n', '\r']));
+        let content = match fs::read(&index) {
+            Ok(content) => Some(content),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => {
+                return Err(MdrefError::IoRead {
+                    path: index,
+                    source: error,
+                });
+            }
+        };
+        Ok((index, content))
+    }
+
+    fn run(&self, args: &[&OsStr]) -> Result<String> {
+        run_git(&self.toplevel, args)
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(std::path::absolute(path)?)
+}
+
+fn run_git<S: AsRef<OsStr>>(root: &Path, args: &[S]) -> Result<String> {
+    let git_error = |details: String| MdrefError::Git {
+        path: root.to_path_buf(),
+        details,
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|error| git_error(format!("cannot run git: {error}")))?;
+
+    if !output.status.success() {
+        return Err(git_error(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| git_error("git printed a non-UTF-8 path".to_string()))
+}
+
+/// Extract the rename pairs from NUL-separated `git diff --name-status -z`
+/// output. Each entry is a status followed by one path, or two for renames
+/// (`R<score>`) and copies (`C<score>`).
+fn parse_renames(output: &str) -> Vec<(String, String)> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut renames = Vec::new();
+
+    while let Some(status) = fields.next() {
+        match status.chars().next() {
+            Some('R') => {
+                if let (Some(source), Some(destination)) = (fields.next(), fields.next()) {
+                    renames.push((source.to_string(), destination.to_string()));
+                }
+            }
+            Some('C') => {
+                fields.next();
+                fields.next();
+            }
+            _ => {
+                fields.next();
+            }
+        }
+    }
+
+    renames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_renames_skips_other_statuses() {
+        let output = "M\0index.md\0R100\0guide.md\0docs/guide.md\0C075\0a.md\0b.md\0\
+                      D\0gone.md\0R087\0old name.md\0new name.md\0";
+
+        assert_eq!(
+            parse_renames(output),
+            vec![
+                ("guide.md".to_string(), "docs/guide.md".to_string()),
+                ("old name.md".to_string(), "new name.md".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Build the link graph of a whole root and render it as DOT, Mermaid, or
+//! GraphML. For JSON, [`LinkGraph`] is serializable with serde.
+
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use super::{
+    config::ScanConfig,
+    find::{process_md_file, resolve_link},
+    model::{GraphEdge, LinkGraph},
+    pathdiff::diff_paths,
+    progress::ProgressReporter,
+    util::{collect_files, strip_anchor},
+    wikilink::{WikiVault, path_to_slash},
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+/// Build the [`LinkGraph`] of every Markdown file under `root` in one
+/// parallel pass.
+///
+/// Links resolve like in [`crate::check_links`]: relative to the linking
+/// file, and wikilinks with vault semantics (see [`WikiVault`]).
+///
+/// # Progress
+///
+/// Callers report progress through a [`ProgressReporter`] trait object. Pass
+/// [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+/// The reporter is called with [`ProgressReporter::set_total`] once before scanning,
+/// and with [`ProgressReporter::inc`] once per Markdown file as it is processed.
+pub fn build_link_graph<P: AsRef<Path>>(
+    root: P,
+    progress: &dyn ProgressReporter,
+) -> Result<LinkGraph> {
+    let root = root.as_ref();
+    scan_link_graph(root, collect_files(root), progress)
+}
+
+/// Build the [`LinkGraph`] of `root` from `files`, every file under it as
+/// returned by [`collect_files`].
+pub(crate) fn scan_link_graph(
+    root: &Path,
+    files: Vec<PathBuf>,
+    progress: &dyn ProgressReporter,
+) -> Result<LinkGraph> {
+    let root_canonical = root.canonicalize().map_err(|e| MdrefError::IoRead {
+        path: root.to_path_buf(),
+        source: e,
+    })?;
+    let vault = WikiVault::from_files(root, &files);
+    let scan = ScanConfig::for_path(root);
+    let markdown_files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|path| scan.is_markdown(path))
+        .collect();
+
+    progress.set_total(markdown_files.len() as u64);
+
+    let scanned = markdown_files
+        .par_iter()
+        .map(|path| {
+            let content = fs::read_to_string(path).map_err(|e| MdrefError::IoRead {
+                path: path.clone(),
+                source: e,
+            })?;
+            let source = node_path(&root_canonical, path);
+            let edges: Vec<GraphEdge> = process_md_file(&content, path, None)
+                .into_iter()
+                .filter_map(|link| {
+                    let target = resolve_target(path, &link, &vault)?;
+                    Some(GraphEdge {
+                        source: source.clone()?,
+                        target: node_path(&root_canonical, &target)?,
+                        line: link.line,
+                        column: link.column,
+                        link_text: link.link_text,
+                        link_type: link.link_type,
+                    })
+                })
+                .collect();
+            progress.inc(1);
+            Ok((source, edges))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut nodes = BTreeSet::new();
+    let mut edges = Vec::new();
+    for (source, file_edges) in scanned {
+        nodes.extend(source);
+        for edge in file_edges {
+            nodes.insert(edge.target.clone());
+            edges.push(edge);
+        }
+    }
+    edges.sort_by(|left, right| {
+        left.source
+            .cmp(&right.source)
+            .then(left.line.cmp(&right.line))
+            .then(left.column.cmp(&right.column))
+    });
+
+    Ok(LinkGraph {
+        root: root_canonical,
+        nodes: nodes.into_iter().collect(),
+        edges,
+    })
+}
+
+/// The existing file or directory `link` in `base_file` points at.
+pub(crate) fn resolve_target(
+    base_file: &Path,
+    link: &Reference,
+    vault: &WikiVault,
+) -> Option<PathBuf> {
+    let url = strip_anchor(&link.link_text).filter(|url| !url.is_empty())?;
+    let target = match link.link_type {
+        LinkType::WikiLink => vault.resolve(base_file, url)?,
+        _ => resolve_link(base_file, Path::new(url))?,
+    };
+    target.exists().then_some(target)
+}
+
+/// `path` relative to the canonical root, or `None` if it cannot be canonicalized.
+pub(crate) fn node_path(root_canonical: &Path, path: &Path) -> Option<PathBuf> {
+    diff_paths(path.canonicalize().ok()?, root_canonical)
+}
+
+fn label(path: &Path) -> String {
+    path_to_slash(path).unwrap_or_else(|| path.display().to_string())
+}
+
+// ============= Renderers =============
+
+pub(crate) fn render_dot(graph: &LinkGraph) -> String {
+    let quote = |path: &Path| {
+        format!(
+            "\"{}\"",
+            label(path).replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    };
+    let scan = ScanConfig::for_path(&graph.root);
+    let mut out = String::from("digraph links {\n");
+    for node in &graph.nodes {
+        let shape = if scan.is_markdown(node) {
+            ""
+        } else {
+            " [shape=box]"
+        };
+        let _ = writeln!(out, "    {}{shape};", quote(node));
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    {} -> {} [label=\"{} {}:{}\"];",
+            quote(&edge.source),
+            quote(&edge.target),
+            edge.link_type.as_str(),
+            edge.line,
+            edge.column
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+pub(crate) fn render_mermaid(graph: &LinkGraph) -> String {
+    let scan = ScanConfig::for_path(&graph.root);
+    let mut out = String::from("flowchart LR\n");
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let text = label(node).replace('"', "#quot;");
+        let (open, close) = if scan.is_markdown(node) {
+            ("[", "]")
+        } else {
+            ("(", ")")
+        };
+        let _ = writeln!(out, "    n{index}{open}\"{text}\"{close}");
+    }
+    for edge in &graph.edges {
+        let (Ok(source), Ok(target)) = (
+            graph.nodes.binary_search(&edge.source),
+            graph.nodes.binary_search(&edge.target),
+        ) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "    n{source} -->|\"{} {}:{}\"| n{target}",
+            edge.link_type.as_str(),
+            edge.line,
+            edge.column
+        );
+    }
+    out
+}
+
+pub(crate) fn render_graphml(graph: &LinkGraph) -> String {
+    let id = |path: &Path| escape_xml(&label(path));
+    let scan = ScanConfig::for_path(&graph.root);
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         \x20 <key id=\"markdown\" for=\"node\" attr.name=\"markdown\" attr.type=\"boolean\"/>\n\
+         \x20 <key id=\"line\" for=\"edge\" attr.name=\"line\" attr.type=\"int\"/>\n\
+         \x20 <key id=\"column\" for=\"edge\" attr.name=\"column\" attr.type=\"int\"/>\n\
+         \x20 <key id=\"link_type\" for=\"edge\" attr.name=\"link_type\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"link_text\" for=\"edge\" attr.name=\"link_text\" attr.type=\"string\"/>\n\
+         \x20 <graph id=\"links\" edgedefault=\"directed\">\n",
+    );
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "    <node id=\"{}\"><data key=\"markdown\">{}</data></node>",
+            id(node),
+            scan.is_markdown(node)
+        );
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\">\
+             <data key=\"line\">{}</data>\
+             <data key=\"column\">{}</data>\
+             <data key=\"link_type\">{}</data>\
+             <data key=\"link_text\">{}</data></edge>",
+            id(&edge.source),
+            id(&edge.target),
+            edge.line,
+            edge.column,
+            edge.link_type.as_str(),
+            escape_xml(&edge.link_text)
+        );
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(source: &str, target: &str) -> GraphEdge {
+        GraphEdge {
+            source: PathBuf::from(source),
+            target: PathBuf::from(target),
+            line: 2,
+            column: 5,
+            link_text: target.to_string(),
+            link_type: LinkType::Inline,
+        }
+    }
+
+    fn graph() -> LinkGraph {
+        LinkGraph {
+            root: PathBuf::from("/docs"),
+            nodes: vec![PathBuf::from("a \"b\".md"), PathBuf::from("img/logo.png")],
+            edges: vec![edge("a \"b\".md", "img/logo.png")],
+        }
+    }
+
+    #[test]
+    fn test_render_dot_escapes_quotes_and_boxes_assets() {
+        assert_eq!(
+            render_dot(&graph()),
+            "digraph links {\n    \"a \\\"b\\\".md\";\n    \"img/logo.png\" [shape=box];\n    \
+             \"a \\\"b\\\".md\" -> \"img/logo.png\" [label=\"inline 2:5\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_render_mermaid_numbers_nodes() {
+        assert_eq!(
+            render_mermaid(&graph()),
+            "flowchart LR\n    n0[\"a #quot;b#quot;.md\"]\n    n1(\"img/logo.png\")\n    \
+             n0 -->|\"inline 2:5\"| n1\n"
+        );
+    }
+
+    #[test]
+    fn test_render_graphml_escapes_ids() {
+        let graphml = render_graphml(&graph());
+
+        assert!(
+            graphml.contains(
+                "<node id=\"a &quot;b&quot;.md\"><data key=\"markdown\">true</data></node>"
+            )
+        );
+        assert!(graphml.contains("<edge source=\"a &quot;b&quot;.md\" target=\"img/logo.png\">"));
+        assert!(graphml.ends_with("  </graph>\n</graphml>\n"));
+    }
+}
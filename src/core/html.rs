@@ -0,0 +1,300 @@
+//! A small, forgiving scanner for start tags inside raw HTML snippets.
+//!
+//! comrak hands raw HTML through as opaque `HtmlBlock` / `HtmlInline` literals.
+//! This scanner extracts the start tags and their attributes from such a
+//! literal, keeping byte offsets and quoting so callers can rewrite attribute
+//! values in place. It is not a full HTML parser: comments, end tags, and
+//! declarations are skipped, and malformed input never panics.
+//!
+//! [`scan_links`] builds on the tag scanner to extract the URLs carried by
+//! link-bearing attributes such as `<a href>`, `<img src>`, and `srcset`.
+
+/// Link-bearing attributes, as `(tag, attribute)` pairs.
+const LINK_ATTRIBUTES: &[(&str, &str)] = &[
+    ("a", "href"),
+    ("area", "href"),
+    ("link", "href"),
+    ("img", "src"),
+    ("img", "srcset"),
+    ("source", "src"),
+    ("source", "srcset"),
+    ("video", "src"),
+    ("video", "poster"),
+    ("audio", "src"),
+    ("track", "src"),
+    ("iframe", "src"),
+    ("embed", "src"),
+];
+
+/// A single `name=value` attribute of an [`HtmlTag`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlAttribute<'a> {
+    /// Attribute name as written in the source.
+    pub name: &'a str,
+    /// Attribute value without surrounding quotes.
+    pub value: &'a str,
+    /// Byte offset of `value` within the scanned snippet.
+    pub value_start: usize,
+    /// The quote character around the value, or `None` when unquoted.
+    pub quote: Option<char>,
+}
+
+/// A start tag found in a raw HTML snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlTag<'a> {
+    /// Tag name as written in the source.
+    pub name: &'a str,
+    /// Attributes that carry a value. Boolean attributes are omitted.
+    pub attributes: Vec<HtmlAttribute<'a>>,
+}
+
+impl HtmlTag<'_> {
+    /// Return the value of the first attribute named `name` (ASCII case-insensitive).
+    pub fn attribute(&self, name: &str) -> Option<&HtmlAttribute<'_>> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A URL found in a link-bearing attribute of a raw HTML snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlLink<'a> {
+    /// The URL as written, without quotes or `srcset` descriptors.
+    pub url: &'a str,
+    /// Byte offset of `url` within the scanned snippet.
+    pub start: usize,
+}
+
+/// Scan `html` for URLs in link-bearing attributes such as `<a href>` and
+/// `<img src>`. Each `srcset` candidate is reported as its own link.
+pub fn scan_links(html: &str) -> Vec<HtmlLink<'_>> {
+    let mut links = Vec::new();
+
+    for tag in scan_tags(html) {
+        for attribute in &tag.attributes {
+            let is_link_attribute = LINK_ATTRIBUTES.iter().any(|(tag_name, name)| {
+                tag.name.eq_ignore_ascii_case(tag_name) && attribute.name.eq_ignore_ascii_case(name)
+            });
+            if !is_link_attribute {
+                continue;
+            }
+
+            if attribute.name.eq_ignore_ascii_case("srcset") {
+                links.extend(scan_srcset(attribute.value, attribute.value_start));
+            } else {
+                let url = attribute.value.trim();
+                if !url.is_empty() {
+                    let leading = attribute.value.len() - attribute.value.trim_start().len();
+                    links.push(HtmlLink {
+                        url,
+                        start: attribute.value_start + leading,
+                    });
+                }
+            }
+        }
+    }
+
+    links
+}
+
+/// Split a `srcset` value into its candidate URLs, e.g.
+/// `"a.png 1x, b.png 2x"` -> `a.png`, `b.png`.
+fn scan_srcset(value: &str, value_start: usize) -> Vec<HtmlLink<'_>> {
+    let bytes = value.as_bytes();
+    let mut links = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        index = scan_while(bytes, index, |b| b.is_ascii_whitespace() || b == b',');
+        let url_start = index;
+        index = scan_while(bytes, index, |b| !b.is_ascii_whitespace());
+        let url = value[url_start..index].trim_end_matches(',');
+        if !url.is_empty() {
+            links.push(HtmlLink {
+                url,
+                start: value_start + url_start,
+            });
+        }
+        if value[url_start..index].ends_with(',') {
+            continue;
+        }
+
+        // Skip the descriptor (e.g. `2x` or `480w`) up to the next candidate.
+        index = value[index..]
+            .find(',')
+            .map_or(bytes.len(), |comma| index + comma + 1);
+    }
+
+    links
+}
+
+/// Scan `html` for start tags and their valued attributes.
+pub fn scan_tags(html: &str) -> Vec<HtmlTag<'_>> {
+    let bytes = html.as_bytes();
+    let mut tags = Vec::new();
+    let mut index = 0;
+
+    while let Some(offset) = html[index..].find('<') {
+        let tag_start = index + offset + 1;
+        index = tag_start;
+
+        if html[tag_start..].starts_with("!--") {
+            index = html[tag_start..]
+                .find("-->")
+                .map_or(html.len(), |end| tag_start + end + 3);
+            continue;
+        }
+
+        let name_end = scan_while(bytes, tag_start, |b| b.is_ascii_alphanumeric() || b == b'-');
+        if name_end == tag_start || !bytes[tag_start].is_ascii_alphabetic() {
+            continue;
+        }
+
+        let (attributes, tag_end) = scan_attributes(html, name_end);
+        tags.push(HtmlTag {
+            name: &html[tag_start..name_end],
+            attributes,
+        });
+        index = tag_end;
+    }
+
+    tags
+}
+
+/// Scan attributes starting at `start` until the closing `>` of the tag.
+///
+/// Returns the attributes and the byte offset just past the tag.
+fn scan_attributes(html: &str, start: usize) -> (Vec<HtmlAttribute<'_>>, usize) {
+    let bytes = html.as_bytes();
+    let mut attributes = Vec::new();
+    let mut index = start;
+
+    loop {
+        index = scan_while(bytes, index, |b| b.is_ascii_whitespace() || b == b'/');
+        if index >= bytes.len() {
+            return (attributes, index);
+        }
+        if bytes[index] == b'>' {
+            return (attributes, index + 1);
+        }
+
+        let name_start = index;
+        index = scan_while(bytes, index, |b| {
+            !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/')
+        });
+        let name = &html[name_start..index];
+
+        let after_name = scan_while(bytes, index, |b| b.is_ascii_whitespace());
+        if after_name >= bytes.len() || bytes[after_name] != b'=' {
+            index = after_name;
+            continue;
+        }
+
+        let value_start = scan_while(bytes, after_name + 1, |b| b.is_ascii_whitespace());
+        if value_start >= bytes.len() {
+            return (attributes, value_start);
+        }
+
+        let quote = bytes[value_start];
+        if quote == b'"' || quote == b'\'' {
+            let inner_start = value_start + 1;
+            let inner_end = html[inner_start..]
+                .find(quote as char)
+                .map_or(html.len(), |end| inner_start + end);
+            attributes.push(HtmlAttribute {
+                name,
+                value: &html[inner_start..inner_end],
+                value_start: inner_start,
+                quote: Some(quote as char),
+            });
+            index = (inner_end + 1).min(html.len());
+        } else {
+            let value_end = scan_while(bytes, value_start, |b| {
+                !b.is_ascii_whitespace() && b != b'>'
+            });
+            attributes.push(HtmlAttribute {
+                name,
+                value: &html[value_start..value_end],
+                value_start,
+                quote: None,
+            });
+            index = value_end;
+        }
+    }
+}
+
+fn scan_while(bytes: &[u8], start: usize, predicate: impl Fn(u8) -> bool) -> usize {
+    let mut index = start;
+    while index < bytes.len() && predicate(bytes[index]) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_tags_reads_quoted_and_unquoted_attributes() {
+        let html = r#"<img src="logo.png" width=200 alt='Logo'>"#;
+        let tags = scan_tags(html);
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "img");
+        let src = tags[0].attribute("SRC").unwrap();
+        assert_eq!(src.value, "logo.png");
+        assert_eq!(src.quote, Some('"'));
+        assert_eq!(&html[src.value_start..src.value_start + 8], "logo.png");
+        assert_eq!(tags[0].attribute("width").unwrap().quote, None);
+        assert_eq!(tags[0].attribute("alt").unwrap().quote, Some('\''));
+    }
+
+    #[test]
+    fn test_scan_tags_skips_comments_end_tags_and_boolean_attributes() {
+        let html = "<!-- <a href=\"x.md\"> --><details open><summary>S</summary></details>";
+        let tags = scan_tags(html);
+
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name).collect();
+        assert_eq!(names, vec!["details", "summary"]);
+        assert!(tags[0].attributes.is_empty());
+    }
+
+    #[test]
+    fn test_scan_links_reads_link_attributes_and_srcset_candidates() {
+        let html = r#"<a href="../CONTRIBUTING.md" title="x.md"><img src=docs/logo.png width=200></a>
+<picture><source srcset="dark.png 1x, dark@2x.png 2x" media="(prefers-color-scheme: dark)"></picture>"#;
+        let links = scan_links(html);
+
+        let urls: Vec<&str> = links.iter().map(|link| link.url).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "../CONTRIBUTING.md",
+                "docs/logo.png",
+                "dark.png",
+                "dark@2x.png"
+            ]
+        );
+        for link in &links {
+            assert_eq!(&html[link.start..link.start + link.url.len()], link.url);
+        }
+    }
+
+    #[test]
+    fn test_scan_links_ignores_unrelated_attributes_and_empty_values() {
+        let links = scan_links(r#"<div src="x.md"><a href="" name="top"><img alt="a.png">"#);
+
+        assert!(links.is_empty());
+    }
+
+    #[test]
+    fn test_scan_tags_tolerates_truncated_input() {
+        let tags = scan_tags("<a href=\"unterminated");
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].attribute("href").unwrap().value, "unterminated");
+        assert!(scan_tags("< <1 <").is_empty());
+    }
+}
@@ -0,0 +1,604 @@
+//! Persistent on-disk link index, stored at `<root>/.mdref/index`.
+//!
+//! The index is an optional cache for large trees. Once built with
+//! [`build_index`], [`crate::find_references`] (and therefore `mv`, `rename`
+//! and `rename-heading`) consults it instead of re-parsing every Markdown file.
+//!
+//! Each file entry is keyed by its root-relative path and records the file's
+//! modification time, size, and content hash together with its parsed links.
+//! A reverse map from link target to referring files narrows a lookup down to
+//! the files that can match. Entries are refreshed incrementally on every use:
+//! a file is only re-read when its size or modification time changed, and only
+//! re-parsed when its content hash changed. Files that disappeared are dropped.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    find::{MatchedReference, TargetLookup, process_md_file, resolve_link_target},
+    model::IndexStatus,
+    progress::ProgressReporter,
+    util::{collect_markdown_files, content_hash, strip_anchor, url_decode_link},
+    wikilink::{WikiVault, absolute, normalize, path_to_slash},
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+/// Directory under the root that holds mdref's on-disk state.
+pub const INDEX_DIR: &str = ".mdref";
+const INDEX_FILE: &str = "index";
+/// Bumped whenever the on-disk format changes; older indexes are rebuilt.
+const INDEX_VERSION: u32 = 1;
+
+/// Return the location of the link index for `root`.
+pub fn index_path<P: AsRef<Path>>(root: P) -> PathBuf {
+    root.as_ref().join(INDEX_DIR).join(INDEX_FILE)
+}
+
+/// Build the link index for `root`, or bring an existing one up to date.
+///
+/// # Progress
+///
+/// The reporter is called with [`ProgressReporter::set_total`] once before
+/// scanning, and with [`ProgressReporter::inc`] once per Markdown file.
+pub fn build_index<P: AsRef<Path>>(
+    root: P,
+    progress: &dyn ProgressReporter,
+) -> Result<IndexStatus> {
+    let root = root.as_ref();
+    let markdown_files = collect_markdown_files(root);
+    progress.set_total(markdown_files.len() as u64);
+
+    let mut index = LinkIndex::load(root).unwrap_or_else(LinkIndex::empty);
+    index.refresh(root, &markdown_files, progress)?;
+    index.save(root)?;
+
+    Ok(index.status(root, &markdown_files))
+}
+
+/// Compare the link index for `root` with the Markdown files on disk without
+/// modifying it.
+pub fn index_status<P: AsRef<Path>>(root: P) -> Result<IndexStatus> {
+    let root = root.as_ref();
+    let markdown_files = collect_markdown_files(root);
+
+    Ok(match LinkIndex::load(root) {
+        Some(index) => index.status(root, &markdown_files),
+        None => IndexStatus {
+            path: index_path(root),
+            exists: false,
+            indexed_files: 0,
+            stale_files: 0,
+            new_files: markdown_files.len(),
+            removed_files: 0,
+            links: 0,
+        },
+    })
+}
+
+/// Delete the link index for `root`. Returns `false` if there was none.
+pub fn clear_index<P: AsRef<Path>>(root: P) -> Result<bool> {
+    let path = index_path(&root);
+    match fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(source) => return Err(MdrefError::IoWrite { path, source }),
+    }
+
+    // Remove the state directory too when nothing but its `.gitignore` is left.
+    let dir = root.as_ref().join(INDEX_DIR);
+    let only_gitignore = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .all(|entry| entry.file_name() == ".gitignore");
+    if only_gitignore {
+        fs::remove_dir_all(&dir).map_err(|source| MdrefError::IoWrite { path: dir, source })?;
+    }
+
+    Ok(true)
+}
+
+/// Create `<root>/.mdref` with a `.gitignore` that keeps its contents out of
+/// version control, and return its path.
+pub(crate) fn create_state_dir(root: &Path) -> Result<PathBuf> {
+    let dir = root.join(INDEX_DIR);
+    fs::create_dir_all(&dir).map_err(|source| MdrefError::IoWrite {
+        path: dir.clone(),
+        source,
+    })?;
+
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, "*\n").map_err(|source| MdrefError::IoWrite {
+            path: gitignore,
+            source,
+        })?;
+    }
+
+    Ok(dir)
+}
+
+/// The persisted index: parsed links per file plus a target → referrers map.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct LinkIndex {
+    version: u32,
+    /// Indexed Markdown files, keyed by root-relative path.
+    files: BTreeMap<String, IndexedFile>,
+    /// Link targets (root-relative when under the root) and the files linking
+    /// to them. Wikilinks resolve through the vault and are not recorded here.
+    referrers: BTreeMap<String, BTreeSet<String>>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    /// Modification time in nanoseconds since the Unix epoch.
+    modified: u64,
+    size: u64,
+    hash: String,
+    links: Vec<IndexedLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedLink {
+    line: usize,
+    column: usize,
+    link_text: String,
+    link_type: LinkType,
+}
+
+/// The result of checking one file against its index entry.
+enum FileUpdate {
+    Unchanged,
+    /// Same content, new modification time or size.
+    Touched(String, IndexedFile),
+    /// New or changed content, with the target keys of its links.
+    Parsed(String, IndexedFile, Vec<String>),
+}
+
+impl LinkIndex {
+    fn empty() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            files: BTreeMap::new(),
+            referrers: BTreeMap::new(),
+            dirty: true,
+        }
+    }
+
+    /// Load the index for `root`. Returns `None` when there is no index file;
+    /// an unreadable or outdated index loads as empty so it gets rebuilt.
+    pub(crate) fn load(root: &Path) -> Option<Self> {
+        let bytes = match fs::read(index_path(root)) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(_) => return Some(Self::empty()),
+        };
+
+        match serde_json::from_slice::<Self>(&bytes) {
+            Ok(index) if index.version == INDEX_VERSION => Some(index),
+            _ => Some(Self::empty()),
+        }
+    }
+
+    /// Write the index to `<root>/.mdref/index` if it changed since loading.
+    pub(crate) fn save(&mut self, root: &Path) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        create_state_dir(root)?;
+
+        let bytes = serde_json::to_vec(self).map_err(|error| MdrefError::SerializationFailed {
+            details: format!("failed to serialize link index: {error}"),
+        })?;
+
+        // Write a sibling file first so a crash never leaves a truncated index.
+        let path = index_path(root);
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, bytes).map_err(|source| MdrefError::IoWrite {
+            path: temp_path.clone(),
+            source,
+        })?;
+        fs::rename(&temp_path, &path).map_err(|source| MdrefError::IoWrite {
+            path: path.clone(),
+            source,
+        })?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Bring the index in line with `markdown_files`, re-parsing only files
+    /// whose content changed and dropping files that no longer exist.
+    pub(crate) fn refresh(
+        &mut self,
+        root: &Path,
+        markdown_files: &[PathBuf],
+        progress: &dyn ProgressReporter,
+    ) -> Result<()> {
+        let canonical_root = absolute(root);
+
+        let updates = markdown_files
+            .par_iter()
+            .map(|path| {
+                let update = self.check_file(root, &canonical_root, path);
+                progress.inc(1);
+                update
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let current: HashSet<String> = markdown_files
+            .iter()
+            .map(|path| file_key(root, path))
+            .collect();
+        let mut stale: HashSet<String> = self
+            .files
+            .keys()
+            .filter(|key| !current.contains(*key))
+            .cloned()
+            .collect();
+        for key in &stale {
+            self.files.remove(key);
+        }
+
+        let mut parsed = Vec::new();
+        for update in updates {
+            match update {
+                FileUpdate::Unchanged => continue,
+                FileUpdate::Touched(key, entry) => {
+                    self.files.insert(key, entry);
+                }
+                FileUpdate::Parsed(key, entry, targets) => {
+                    stale.insert(key.clone());
+                    self.files.insert(key.clone(), entry);
+                    parsed.push((key, targets));
+                }
+            }
+            self.dirty = true;
+        }
+
+        if !stale.is_empty() {
+            self.dirty = true;
+            self.referrers.retain(|_, files| {
+                files.retain(|file| !stale.contains(file));
+                !files.is_empty()
+            });
+        }
+        for (key, targets) in parsed {
+            for target in targets {
+                self.referrers
+                    .entry(target)
+                    .or_default()
+                    .insert(key.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_file(&self, root: &Path, canonical_root: &Path, path: &Path) -> Result<FileUpdate> {
+        let key = file_key(root, path);
+        let metadata = fs::metadata(path).map_err(|source| MdrefError::IoRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let modified = modified_nanos(&metadata);
+        let size = metadata.len();
+
+        let cached = self.files.get(&key);
+        if cached.is_some_and(|entry| entry.modified == modified && entry.size == size) {
+            return Ok(FileUpdate::Unchanged);
+        }
+
+        let bytes = fs::read(path).map_err(|source| MdrefError::IoRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let hash = content_hash(&bytes);
+        if let Some(entry) = cached.filter(|entry| entry.hash == hash) {
+            return Ok(FileUpdate::Touched(
+                key,
+                IndexedFile {
+                    modified,
+                    size,
+                    ..entry.clone()
+                },
+            ));
+        }
+
+        let content = String::from_utf8(bytes).map_err(|error| MdrefError::IoRead {
+            path: path.to_path_buf(),
+            source: io::Error::new(io::ErrorKind::InvalidData, error),
+        })?;
+        let links: Vec<IndexedLink> = process_md_file(&content, path, None)
+            .into_iter()
+            .map(|reference| IndexedLink {
+                line: reference.line,
+                column: reference.column,
+                link_text: reference.link_text,
+                link_type: reference.link_type,
+            })
+            .collect();
+        let targets: BTreeSet<String> = links
+            .iter()
+            .filter(|link| link.link_type != LinkType::WikiLink)
+            .filter_map(|link| target_key(canonical_root, path, &link.link_text))
+            .collect();
+
+        Ok(FileUpdate::Parsed(
+            key,
+            IndexedFile {
+                modified,
+                size,
+                hash,
+                links,
+            },
+            targets.into_iter().collect(),
+        ))
+    }
+
+    /// Find the references to any target of `lookup` among `markdown_files`,
+    /// paired with the indices of the targets they refer to. The reverse map
+    /// skips files that cannot link to a target, and each remaining link is
+    /// resolved once.
+    ///
+    /// Call [`Self::refresh`] first so every file has an up-to-date entry.
+    pub(crate) fn references_matching(
+        &self,
+        root: &Path,
+        markdown_files: &[PathBuf],
+        lookup: &TargetLookup,
+        vault: &WikiVault,
+    ) -> Vec<MatchedReference> {
+        let canonical_root = absolute(root);
+        let targets: HashSet<String> = lookup
+            .paths()
+            .map(|target| key_for(&canonical_root, target))
+            .collect();
+        let candidates: HashSet<&str> = self
+            .referrers
+            .iter()
+            .filter(|(key, _)| is_within_any(key, &targets))
+            .flat_map(|(_, files)| files.iter().map(String::as_str))
+            .collect();
+
+        let mut references = Vec::new();
+        for path in markdown_files {
+            let key = file_key(root, path);
+            let Some(entry) = self.files.get(&key) else {
+                continue;
+            };
+            let is_candidate = candidates.contains(key.as_str());
+
+            for link in &entry.links {
+                if !is_candidate && link.link_type != LinkType::WikiLink {
+                    continue;
+                }
+                let Some(canonical_link) =
+                    resolve_link_target(path, Some(vault), &link.link_text, &link.link_type)
+                else {
+                    continue;
+                };
+                let matched = lookup.matching(&canonical_link);
+                if !matched.is_empty() {
+                    let reference = Reference::with_link_type(
+                        path.clone(),
+                        link.line,
+                        link.column,
+                        link.link_text.clone(),
+                        link.link_type.clone(),
+                    );
+                    references.push((reference, matched));
+                }
+            }
+        }
+
+        references
+    }
+
+    /// Content hash recorded for `path` when it was last parsed.
+    pub(crate) fn file_hash(&self, root: &Path, path: &Path) -> Option<&str> {
+        self.files
+            .get(&file_key(root, path))
+            .map(|entry| entry.hash.as_str())
+    }
+
+    /// Content hash recorded for the file at absolute `path`, which may have
+    /// been moved or deleted since the index was last refreshed.
+    pub(crate) fn recorded_hash(&self, root: &Path, path: &Path) -> Option<&str> {
+        self.files
+            .get(&key_for(&absolute(root), path))
+            .map(|entry| entry.hash.as_str())
+    }
+
+    fn status(&self, root: &Path, markdown_files: &[PathBuf]) -> IndexStatus {
+        let mut stale_files = 0;
+        let mut new_files = 0;
+        let mut current = HashSet::new();
+
+        for path in markdown_files {
+            let key = file_key(root, path);
+            match self.files.get(&key) {
+                Some(entry) => {
+                    let unchanged = fs::metadata(path).is_ok_and(|metadata| {
+                        entry.modified == modified_nanos(&metadata) && entry.size == metadata.len()
+                    });
+                    if !unchanged {
+                        stale_files += 1;
+                    }
+                }
+                None => new_files += 1,
+            }
+            current.insert(key);
+        }
+
+        IndexStatus {
+            path: index_path(root),
+            exists: true,
+            indexed_files: self.files.len(),
+            stale_files,
+            new_files,
+            removed_files: self
+                .files
+                .keys()
+                .filter(|key| !current.contains(*key))
+                .count(),
+            links: self.files.values().map(|entry| entry.links.len()).sum(),
+        }
+    }
+}
+
+/// Modification time in nanoseconds since the Unix epoch, or `0` if unknown.
+fn modified_nanos(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| {
+            u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+        })
+}
+
+/// The root-relative key of a Markdown file found by scanning `root`.
+fn file_key(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(normalize(root)).unwrap_or(path);
+    path_to_slash(relative).unwrap_or_else(|| relative.display().to_string())
+}
+
+/// The reverse-map key of the file a local link points to, whether or not it
+/// exists yet.
+fn target_key(canonical_root: &Path, base_file: &Path, link: &str) -> Option<String> {
+    let link = strip_anchor(link)?;
+    let link_path = Path::new(link);
+    let resolved = if link_path.is_absolute() {
+        link_path.to_path_buf()
+    } else {
+        base_file.parent()?.join(url_decode_link(link))
+    };
+    Some(key_for(canonical_root, &absolute(&resolved)))
+}
+
+/// Root-relative slash path for paths under the root, absolute otherwise.
+fn key_for(canonical_root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(canonical_root).unwrap_or(path);
+    path_to_slash(relative).unwrap_or_else(|| relative.display().to_string())
+}
+
+/// Whether target `key` is one of `targets` or lies inside one of them.
+fn is_within_any(key: &str, targets: &HashSet<String>) -> bool {
+    targets.contains("")
+        || targets.contains(key)
+        || key
+            .match_indices('/')
+            .any(|(slash, _)| targets.contains(&key[..slash]))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{NoopProgress, test_utils::write_file};
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_build_index_records_links_and_reverse_map() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("guide.md"), "# Guide");
+        write_file(
+            root.join("docs/index.md"),
+            "[Guide](../guide.md#intro) [[guide]] [Site](https://example.com)\n",
+        );
+
+        let status = build_index(root, &NoopProgress).unwrap();
+
+        assert!(status.is_fresh());
+        assert_eq!(status.indexed_files, 2);
+        assert_eq!(status.links, 2);
+        let index = LinkIndex::load(root).unwrap();
+        assert_eq!(
+            index.referrers.get("guide.md").unwrap(),
+            &BTreeSet::from(["docs/index.md".to_string()])
+        );
+        assert!(root.join(".mdref/.gitignore").exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_refresh_reparses_changed_files_and_drops_removed_ones() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        write_file(root.join("b.md"), "# B");
+        write_file(root.join("index.md"), "[A](a.md)\n");
+        write_file(root.join("old.md"), "[A](a.md)\n");
+        build_index(root, &NoopProgress).unwrap();
+
+        write_file(root.join("index.md"), "[B](b.md)\n");
+        fs::remove_file(root.join("old.md")).unwrap();
+        let status = index_status(root).unwrap();
+        assert_eq!((status.stale_files, status.removed_files), (1, 1));
+
+        let status = build_index(root, &NoopProgress).unwrap();
+
+        assert!(status.is_fresh());
+        let index = LinkIndex::load(root).unwrap();
+        assert!(!index.referrers.contains_key("a.md"));
+        assert_eq!(
+            index.referrers.get("b.md").unwrap(),
+            &BTreeSet::from(["index.md".to_string()])
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_load_treats_corrupt_index_as_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(index_path(root), "not json");
+
+        let index = LinkIndex::load(root).unwrap();
+
+        assert!(index.files.is_empty());
+        assert!(LinkIndex::load(&root.join("missing")).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_clear_index_removes_state_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_file(root.join("a.md"), "# A");
+        build_index(root, &NoopProgress).unwrap();
+
+        assert!(clear_index(root).unwrap());
+        assert!(!root.join(INDEX_DIR).exists());
+        assert!(!clear_index(root).unwrap());
+    }
+
+    #[test]
+    fn test_is_within_any_matches_targets_and_descendants() {
+        let targets =
+            |keys: &[&str]| -> HashSet<String> { keys.iter().map(|key| key.to_string()).collect() };
+
+        assert!(is_within_any(
+            "docs/a.md",
+            &targets(&["img/b.png", "docs/a.md"])
+        ));
+        assert!(is_within_any("docs/guide/a.md", &targets(&["docs"])));
+        assert!(is_within_any("docs/a.md", &targets(&[""])));
+        assert!(!is_within_any(
+            "docs-old/a.md",
+            &targets(&["docs", "docs/a.md"])
+        ));
+        assert!(!is_within_any("docs", &targets(&["docs/a.md"])));
+    }
+}
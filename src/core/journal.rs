@@ -0,0 +1,380 @@
+//! Undo journal, stored at `<root>/.mdref/history`.
+//!
+//! Every completed move, batch move, applied plan and heading rename appends
+//! an entry recording the paths it moved and, for each file it rewrote, the
+//! original content together with a hash of the rewritten content. [`undo`]
+//! reverts the newest entry: it refuses when a rewritten file changed since,
+//! and otherwise restores the original contents and moves every path back
+//! under one rollback-protected transaction. Only the newest
+//! [`HISTORY_LIMIT`] entries are kept.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    index::{INDEX_DIR, create_state_dir},
+    model::{HistoryEntry, MoveTransaction},
+    mv::apply::{execute_with_rollback, move_paths, rollback_on_error},
+    recover::{LoggedFile, WriteAheadLog},
+    util::{content_hash, post_move_path, resolve_keeping_name, write_atomic},
+};
+use crate::{MdrefError, Result};
+
+/// Number of operations kept in the journal; older entries are dropped.
+pub const HISTORY_LIMIT: usize = 20;
+const HISTORY_FILE: &str = "history";
+/// Bumped whenever the on-disk format changes.
+const HISTORY_VERSION: u32 = 1;
+
+/// Return the location of the undo journal for `root`.
+pub fn history_path<P: AsRef<Path>>(root: P) -> PathBuf {
+    root.as_ref().join(INDEX_DIR).join(HISTORY_FILE)
+}
+
+/// List the operations recorded for `root`, newest first.
+pub fn history<P: AsRef<Path>>(root: P) -> Result<Vec<HistoryEntry>> {
+    let journal = Journal::load(root.as_ref())?;
+    Ok(journal
+        .entries
+        .iter()
+        .rev()
+        .map(JournalEntry::summary)
+        .collect())
+}
+
+/// Revert the newest operation recorded for `root` and drop it from the
+/// journal, returning what was undone.
+///
+/// Nothing is touched when a file the operation rewrote has changed since,
+/// when a moved path no longer exists at its destination, or when something
+/// new occupies a path the operation moved away from.
+pub fn undo<P: AsRef<Path>>(root: P) -> Result<HistoryEntry> {
+    let root = root.as_ref();
+    let mut journal = Journal::load(root)?;
+    let Some(entry) = journal.entries.pop() else {
+        return Err(MdrefError::PathValidation {
+            path: history_path(root),
+            details: "no operation to undo".to_string(),
+        });
+    };
+    verify_undoable(&entry)?;
+
+    let reverse_moves: Vec<(PathBuf, PathBuf)> = entry
+        .moves
+        .iter()
+        .rev()
+        .map(|(source, destination)| (destination.clone(), source.clone()))
+        .collect();
+
+    let mut transaction = MoveTransaction::for_batch();
+    let mut logged_files = Vec::new();
+    for file in &entry.files {
+        transaction.snapshot_file(&file.path)?;
+        logged_files.push(LoggedFile {
+            original_path: file.path.clone(),
+            final_path: post_move_path(&reverse_moves, &file.path),
+            original: transaction.file_snapshots[&file.path].clone(),
+            rewritten: file.original.clone(),
+        });
+    }
+    transaction.attach_log(WriteAheadLog::for_undo(
+        root,
+        entry.id,
+        reverse_moves.clone(),
+        logged_files,
+    )?);
+
+    execute_with_rollback(&transaction, || {
+        for file in &entry.files {
+            write_atomic(&file.path, file.original.as_bytes()).map_err(|source| {
+                MdrefError::IoWrite {
+                    path: file.path.clone(),
+                    source,
+                }
+            })?;
+        }
+        Ok(())
+    })?;
+
+    let moved = move_paths(&reverse_moves, &mut transaction);
+    rollback_on_error(&transaction, moved)?;
+
+    rollback_on_error(&transaction, journal.save(root))?;
+    transaction.commit()?;
+    Ok(entry.summary())
+}
+
+/// Append a completed operation to the journal of `root`.
+///
+/// `moves` are the `(source, destination)` pairs the operation moved and
+/// `snapshots` the original contents of the files it rewrote, keyed by their
+/// pre-move paths. The operation has already succeeded at this point, so a
+/// journal that cannot be written is skipped rather than reported.
+pub(crate) fn record_operation(
+    root: &Path,
+    operation: &str,
+    moves: &[(PathBuf, PathBuf)],
+    snapshots: &HashMap<PathBuf, String>,
+) {
+    let _ = try_record_operation(root, operation, moves, snapshots);
+}
+
+fn try_record_operation(
+    root: &Path,
+    operation: &str,
+    moves: &[(PathBuf, PathBuf)],
+    snapshots: &HashMap<PathBuf, String>,
+) -> Result<()> {
+    let mut resolved_moves = Vec::new();
+    for (source, destination) in moves {
+        if source != destination {
+            resolved_moves.push((
+                resolve_keeping_name(source)?,
+                resolve_keeping_name(destination)?,
+            ));
+        }
+    }
+
+    let mut files: Vec<JournalFile> = Vec::new();
+    for (path, original) in snapshots {
+        let path = post_move_path(&resolved_moves, &resolve_keeping_name(path)?);
+        if files.iter().any(|file| file.path == path) {
+            continue;
+        }
+        let hash = hash_file(&path)?;
+        files.push(JournalFile {
+            path,
+            original: original.clone(),
+            hash,
+        });
+    }
+    files.sort_by(|left, right| left.path.cmp(&right.path));
+
+    if resolved_moves.is_empty() && files.is_empty() {
+        return Ok(());
+    }
+
+    let mut journal = Journal::load(root)?;
+    journal.push(operation, resolved_moves, files);
+    journal.save(root)
+}
+
+/// Drop entry `id` from the journal of `root`, if it is still there.
+pub(crate) fn forget_entry(root: &Path, id: u64) -> Result<()> {
+    let mut journal = Journal::load(root)?;
+    let count = journal.entries.len();
+    journal.entries.retain(|entry| entry.id != id);
+    if journal.entries.len() == count {
+        return Ok(());
+    }
+    journal.save(root)
+}
+
+/// Refuse to undo `entry` if the tree no longer looks the way it left it.
+fn verify_undoable(entry: &JournalEntry) -> Result<()> {
+    let changed: Vec<&JournalFile> = entry
+        .files
+        .iter()
+        .filter(|file| hash_file(&file.path).ok().as_ref() != Some(&file.hash))
+        .collect();
+    if let Some(first) = changed.first() {
+        let names: Vec<String> = changed
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect();
+        return Err(MdrefError::PathValidation {
+            path: first.path.clone(),
+            details: format!(
+                "{} file(s) changed since {} #{}: {}; refusing to undo",
+                changed.len(),
+                entry.operation,
+                entry.id,
+                names.join(", ")
+            ),
+        });
+    }
+
+    for (source, destination) in &entry.moves {
+        if !destination.exists() {
+            return Err(MdrefError::PathValidation {
+                path: destination.clone(),
+                details: "moved path no longer exists; refusing to undo".to_string(),
+            });
+        }
+        // A swap or chain frees the source again; a case-only rename sees
+        // the source through the destination on case-insensitive filesystems.
+        let reoccupied = source.exists()
+            && !entry.moves.iter().any(|(_, other)| other == source)
+            && !source
+                .as_os_str()
+                .eq_ignore_ascii_case(destination.as_os_str());
+        if reoccupied {
+            return Err(MdrefError::PathValidation {
+                path: source.clone(),
+                details: "path was created again after the move; refusing to undo".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(content_hash(&bytes))
+}
+
+/// The persisted journal, oldest entry first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Journal {
+    version: u32,
+    next_id: u64,
+    entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    id: u64,
+    operation: String,
+    timestamp: u64,
+    moves: Vec<(PathBuf, PathBuf)>,
+    files: Vec<JournalFile>,
+}
+
+/// A file rewritten by an operation, at its post-operation path.
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalFile {
+    path: PathBuf,
+    /// Content before the operation.
+    original: String,
+    /// Hash of the content the operation left behind.
+    hash: String,
+}
+
+impl Journal {
+    /// Load the journal for `root`; a missing journal loads as empty.
+    fn load(root: &Path) -> Result<Self> {
+        let path = history_path(root);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    version: HISTORY_VERSION,
+                    ..Self::default()
+                });
+            }
+            Err(source) => return Err(MdrefError::IoRead { path, source }),
+        };
+
+        match serde_json::from_slice::<Self>(&bytes) {
+            Ok(journal) if journal.version == HISTORY_VERSION => Ok(journal),
+            Ok(journal) => Err(MdrefError::PathValidation {
+                path,
+                details: format!("unsupported undo journal version {}", journal.version),
+            }),
+            Err(error) => Err(MdrefError::PathValidation {
+                path,
+                details: format!("invalid undo journal: {error}"),
+            }),
+        }
+    }
+
+    fn push(&mut self, operation: &str, moves: Vec<(PathBuf, PathBuf)>, files: Vec<JournalFile>) {
+        self.next_id += 1;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        self.entries.push(JournalEntry {
+            id: self.next_id,
+            operation: operation.to_string(),
+            timestamp,
+            moves,
+            files,
+        });
+
+        let excess = self.entries.len().saturating_sub(HISTORY_LIMIT);
+        self.entries.drain(..excess);
+    }
+
+    /// Write the journal to `<root>/.mdref/history`.
+    fn save(&self, root: &Path) -> Result<()> {
+        create_state_dir(root)?;
+
+        let bytes = serde_json::to_vec(self).map_err(|error| MdrefError::SerializationFailed {
+            details: format!("failed to serialize undo journal: {error}"),
+        })?;
+
+        let path = history_path(root);
+        write_atomic(&path, &bytes).map_err(|source| MdrefError::IoWrite { path, source })
+    }
+}
+
+impl JournalEntry {
+    fn summary(&self) -> HistoryEntry {
+        HistoryEntry {
+            id: self.id,
+            operation: self.operation.clone(),
+            timestamp: self.timestamp,
+            moves: self.moves.clone(),
+            files: self.files.iter().map(|file| file.path.clone()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::test_utils::write_file;
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_record_operation_keeps_only_newest_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let file = root.join("a.md");
+        write_file(&file, "# A");
+        let snapshots = HashMap::from([(file.clone(), "# Old".to_string())]);
+
+        for _ in 0..HISTORY_LIMIT + 2 {
+            record_operation(root, "rename-heading", &[], &snapshots);
+        }
+
+        let entries = history(root).unwrap();
+        assert_eq!(entries.len(), HISTORY_LIMIT);
+        assert_eq!(entries[0].id, HISTORY_LIMIT as u64 + 2);
+        assert_eq!(entries[HISTORY_LIMIT - 1].id, 3);
+        assert!(root.join(".mdref/.gitignore").exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_record_operation_without_changes_writes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+
+        record_operation(temp_dir.path(), "mv", &[], &HashMap::new());
+
+        assert!(!history_path(temp_dir.path()).exists());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_undo_empty_history_returns_error() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let error = undo(temp_dir.path()).unwrap_err();
+
+        assert!(error.to_string().contains("no operation to undo"));
+    }
+}
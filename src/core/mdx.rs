@@ -0,0 +1,430 @@
+//! MDX documents: the Markdown between their JSX and the files their imports
+//! load.
+//!
+//! MDX mixes Markdown with JSX components, `{expressions}`, and ESM `import`
+//! / `export` blocks. [`MdxDocument::parse`] blanks those out with spaces, so
+//! the Markdown parser only sees the Markdown around and inside components,
+//! and lists the relative paths the imports name. A leading YAML frontmatter
+//! block is blanked too, and an ESM block may start right after it. MDX has
+//! no indented code blocks, so every line is also dedented; columns found in
+//! the result map back to the original with [`MdxDocument::column`].
+
+use std::path::Path;
+
+/// Whether `path` is an MDX document, whose links are found through
+/// [`MdxDocument`].
+pub(crate) fn is_mdx(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mdx"))
+}
+
+/// A relative path named by an `import` or `export … from` statement.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct EsmImport {
+    /// 1-based line number.
+    pub(crate) line: usize,
+    /// 1-based byte column of the path's first character, inside the quotes.
+    pub(crate) column: usize,
+    pub(crate) specifier: String,
+}
+
+/// The Markdown view of an MDX document.
+pub(crate) struct MdxDocument {
+    /// The document with JSX, expressions and ESM blanked out and every line
+    /// dedented. It has the same lines as the original.
+    pub(crate) markdown: String,
+    /// Bytes of leading whitespace removed from each line.
+    indents: Vec<usize>,
+    /// Imports of files by relative path, like `./intro.mdx`. Package imports
+    /// and paths without an extension are left out, since they do not name a
+    /// file directly.
+    pub(crate) imports: Vec<EsmImport>,
+}
+
+impl MdxDocument {
+    pub(crate) fn parse(content: &str) -> Self {
+        let bytes = content.as_bytes();
+        let mut masked = bytes.to_vec();
+        let mut imports = Vec::new();
+
+        let mut fence: Option<(u8, usize)> = None;
+        let mut in_esm = false;
+        let mut previous_blank = true;
+        // The first byte not scanned yet; JSX tags and expressions may span
+        // lines.
+        let mut cursor = 0;
+        let lines = line_ranges(content);
+        let frontmatter = frontmatter_end(content, &lines);
+        for (index, (start, end)) in lines.into_iter().enumerate() {
+            let line = &content[start..end];
+            let blank = line.trim().is_empty();
+            if frontmatter.is_some_and(|last| index <= last) {
+                // YAML, not Markdown; an ESM block may follow right after it.
+                blank_out(&mut masked, start, end);
+                previous_blank = true;
+                continue;
+            }
+            if cursor > start {
+                if cursor < end {
+                    cursor = mask_inline(bytes, &mut masked, cursor, end);
+                }
+                previous_blank = blank;
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            if let Some((marker, length)) = fence {
+                if fence_marker(trimmed).is_some_and(|(m, l)| m == marker && l >= length) {
+                    fence = None;
+                }
+            } else if in_esm || (previous_blank && is_esm_start(line)) {
+                in_esm = !blank;
+                if in_esm {
+                    blank_out(&mut masked, start, end);
+                    imports.extend(import_specifiers(line).into_iter().map(
+                        |(offset, specifier)| EsmImport {
+                            line: index + 1,
+                            column: offset + 1,
+                            specifier: specifier.to_string(),
+                        },
+                    ));
+                }
+            } else if let Some(marker) = fence_marker(trimmed) {
+                fence = Some(marker);
+            } else {
+                cursor = mask_inline(bytes, &mut masked, start, end);
+            }
+            previous_blank = blank;
+        }
+
+        let masked = String::from_utf8(masked).unwrap_or_else(|_| content.to_string());
+        let mut markdown = String::with_capacity(masked.len());
+        let mut indents = Vec::new();
+        for line in masked.split_inclusive('\n') {
+            let dedented = line.trim_start_matches([' ', '\t']);
+            indents.push(line.len() - dedented.len());
+            markdown.push_str(dedented);
+        }
+
+        Self {
+            markdown,
+            indents,
+            imports,
+        }
+    }
+
+    /// The column in the original document of `column` on `line` (both
+    /// 1-based) of [`Self::markdown`].
+    pub(crate) fn column(&self, line: usize, column: usize) -> usize {
+        column + self.indent(line)
+    }
+
+    /// Bytes of leading whitespace removed from `line` (1-based).
+    pub(crate) fn indent(&self, line: usize) -> usize {
+        line.checked_sub(1)
+            .and_then(|index| self.indents.get(index))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Byte ranges of the lines of `content`, without their line endings.
+fn line_ranges(content: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        ranges.push((start, start + text.len()));
+        start += line.len();
+    }
+    ranges
+}
+
+/// The index of the `---` line closing a YAML frontmatter block that opens
+/// the document, if there is one.
+fn frontmatter_end(content: &str, lines: &[(usize, usize)]) -> Option<usize> {
+    let is_delimiter = |&(start, end): &(usize, usize)| content[start..end].trim_end() == "---";
+    if !is_delimiter(lines.first()?) {
+        return None;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(is_delimiter)
+        .map(|position| position + 1)
+}
+
+/// The marker byte and length of a code fence opening or closing `trimmed`.
+fn fence_marker(trimmed: &str) -> Option<(u8, usize)> {
+    let marker = *trimmed.as_bytes().first()?;
+    if marker != b'`' && marker != b'~' {
+        return None;
+    }
+    let length = trimmed.bytes().take_while(|byte| *byte == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+/// Whether `line` starts an ESM block: `import` or `export` at the very start
+/// of a line.
+fn is_esm_start(line: &str) -> bool {
+    ["import", "export"].iter().any(|keyword| {
+        line.strip_prefix(keyword)
+            .is_some_and(|rest| rest.starts_with([' ', '\t', '{', '*']))
+    })
+}
+
+/// The relative file paths in the `from '…'` clauses and side-effect
+/// `import '…'` statements of `line`, with their byte offsets.
+fn import_specifiers(line: &str) -> Vec<(usize, &str)> {
+    let bytes = line.as_bytes();
+    let mut specifiers = Vec::new();
+    for keyword in ["from", "import"] {
+        let mut search = 0;
+        while let Some(found) = line[search..].find(keyword) {
+            let begin = search + found;
+            let after = begin + keyword.len();
+            search = after;
+            if begin > 0 && is_identifier_byte(bytes[begin - 1]) {
+                continue;
+            }
+
+            let quote_at = after + (line[after..].len() - line[after..].trim_start().len());
+            let Some(quote) = line[quote_at..].chars().next() else {
+                continue;
+            };
+            if quote != '\'' && quote != '"' {
+                continue;
+            }
+            let path_start = quote_at + 1;
+            let Some(length) = line[path_start..].find(quote) else {
+                continue;
+            };
+            let specifier = &line[path_start..path_start + length];
+            let relative = specifier.starts_with("./") || specifier.starts_with("../");
+            if relative && Path::new(specifier).extension().is_some() {
+                specifiers.push((path_start, specifier));
+            }
+        }
+    }
+    specifiers.sort_unstable();
+    specifiers
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$'
+}
+
+/// Blank out the JSX tags and `{expressions}` that start between `start` and
+/// `end`, skipping code spans. Returns the first byte after the last one
+/// scanned, which is past `end` when a tag or expression continues on a later
+/// line.
+fn mask_inline(bytes: &[u8], masked: &mut [u8], start: usize, end: usize) -> usize {
+    let mut pos = start;
+    while pos < end {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'`' => {
+                let run = bytes[pos..end].iter().take_while(|b| **b == b'`').count();
+                pos += run;
+                if let Some(close) = closing_backticks(&bytes[pos..end], run) {
+                    pos += close + run;
+                }
+            }
+            b'<' if is_jsx_tag_start(bytes, pos) => match jsx_tag_end(bytes, pos) {
+                Some(tag_end) => {
+                    blank_out(masked, pos, tag_end);
+                    pos = tag_end;
+                }
+                None => pos += 1,
+            },
+            b'{' => match expression_end(bytes, pos) {
+                Some(expression_end) => {
+                    blank_out(masked, pos, expression_end);
+                    pos = expression_end;
+                }
+                None => pos += 1,
+            },
+            _ => pos += 1,
+        }
+    }
+    pos
+}
+
+/// The offset in `bytes` of a run of exactly `run` backticks.
+fn closing_backticks(bytes: &[u8], run: usize) -> Option<usize> {
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if bytes[pos] == b'`' {
+            let length = bytes[pos..].iter().take_while(|b| **b == b'`').count();
+            if length == run {
+                return Some(pos);
+            }
+            pos += length;
+        } else {
+            pos += 1;
+        }
+    }
+    None
+}
+
+/// Whether the `<` at `pos` opens a JSX component tag or fragment: `<Tabs>`,
+/// `</Tabs>`, `<>` or `</>`. Lowercase tags are left to the HTML handling, and
+/// a `<` right after `(` or `]:` opens a link destination like `<My File.md>`.
+fn is_jsx_tag_start(bytes: &[u8], pos: usize) -> bool {
+    let before = bytes[..pos]
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace());
+    let destination = before.is_some_and(|at| {
+        bytes[at] == b'(' || (bytes[at] == b':' && at > 0 && bytes[at - 1] == b']')
+    });
+    if destination {
+        return false;
+    }
+    let rest = &bytes[pos + 1..];
+    let name = rest.strip_prefix(b"/").unwrap_or(rest);
+    name.first()
+        .is_some_and(|first| first.is_ascii_uppercase() || *first == b'>')
+}
+
+/// The first byte after the JSX tag opening at `pos`, skipping quoted
+/// attribute values and `{expressions}`.
+fn jsx_tag_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    let mut pos = pos + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'>' => return Some(pos + 1),
+            b'{' => pos = expression_end(bytes, pos)?,
+            quote @ (b'"' | b'\'') => {
+                pos += 1 + bytes[pos + 1..].iter().position(|b| *b == quote)? + 1;
+            }
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+/// The first byte after the `{expression}` opening at `pos`, skipping nested
+/// braces, strings and comments.
+fn expression_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = pos;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != quote {
+                    pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                let close = bytes[pos + 2..].windows(2).position(|w| w == b"*/")?;
+                pos += 2 + close + 1;
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'/') => {
+                pos += bytes[pos..].iter().position(|b| *b == b'\n')?;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Replace `bytes[start..end]` with spaces, keeping line endings.
+fn blank_out(bytes: &mut [u8], start: usize, end: usize) {
+    for byte in &mut bytes[start..end] {
+        if *byte != b'\n' && *byte != b'\r' {
+            *byte = b' ';
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blanks_jsx_and_esm_but_keeps_markdown() {
+        let content = "import Tabs from '@theme/Tabs';\n\
+                       import Intro, { meta } from \"./intro.mdx\";\n\
+                       \n\
+                       # Title {#title}\n\
+                       \n\
+                       <Tabs groupId=\"os\" items={['a', 'b']}>\n\
+                       \x20   <TabItem value=\"a\">\n\
+                       \x20     See [the guide](guide.md) and <Badge>new</Badge>.\n\
+                       \x20   </TabItem>\n\
+                       </Tabs>\n\
+                       \n\
+                       ```jsx\n\
+                       <Tabs>{x}</Tabs>\n\
+                       ```\n";
+
+        let document = MdxDocument::parse(content);
+        let lines: Vec<&str> = document.markdown.lines().collect();
+
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[3], format!("# Title {}", " ".repeat(8)));
+        assert_eq!(lines[5], "");
+        assert_eq!(
+            lines[7],
+            "See [the guide](guide.md) and        new        ."
+        );
+        assert_eq!(document.column(8, 5), 11);
+        assert_eq!(lines[12], "<Tabs>{x}</Tabs>");
+        assert_eq!(
+            document.imports,
+            [EsmImport {
+                line: 2,
+                column: 30,
+                specifier: "./intro.mdx".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_blanks_frontmatter_and_reads_the_imports_after_it() {
+        let content = "---\ntitle: A\n---\nimport Y from \"../comp/y.mdx\";\n\n[Z](z.md)\n";
+
+        let document = MdxDocument::parse(content);
+
+        assert_eq!(document.markdown, "\n\n\n\n\n[Z](z.md)\n");
+        assert_eq!(
+            document.imports,
+            [EsmImport {
+                line: 4,
+                column: 16,
+                specifier: "../comp/y.mdx".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_angle_destinations_and_code_spans() {
+        let content = "[Doc](<My Doc.md>) `{` and `<Tabs>`\n\n[ref]: <Other Doc.md>\n";
+
+        let document = MdxDocument::parse(content);
+
+        assert_eq!(document.markdown, content);
+    }
+
+    #[test]
+    fn test_import_specifiers_only_lists_relative_files() {
+        let line = "import a from './a.mdx'; import './b.css'; import c from 'pkg/c.js'; \
+                    import d from './d'; export { e } from \"../e.md\"";
+
+        let specifiers: Vec<&str> = import_specifiers(line)
+            .into_iter()
+            .map(|(_, specifier)| specifier)
+            .collect();
+
+        assert_eq!(specifiers, ["./a.mdx", "./b.css", "../e.md"]);
+    }
+}
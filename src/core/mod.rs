@@ -1,7 +1,25 @@
+pub mod anchor;
+pub mod check;
+pub mod config;
+pub mod cp;
+pub mod diff;
+pub mod editor;
 pub mod find;
+pub mod fix;
+pub mod git;
+pub mod graph;
+pub mod html;
+pub mod index;
+pub mod journal;
+pub mod mdx;
 pub mod model;
 pub mod mv;
+pub mod orphans;
 pub mod pathdiff;
 pub mod progress;
+pub mod recover;
 pub mod rename;
+pub mod rename_heading;
+pub mod rm;
 pub mod util;
+pub mod wikilink;
@@ -0,0 +1,65 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use super::{LinkType, Reference};
+
+/// Why a link was reported as broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokenLinkKind {
+    /// The linked file does not exist.
+    MissingTarget,
+    /// The linked file exists but defines no anchor matching the `#fragment`.
+    MissingAnchor,
+}
+
+impl BrokenLinkKind {
+    /// Short human-readable description, e.g. `"missing target"`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::MissingTarget => "missing target",
+            Self::MissingAnchor => "missing anchor",
+        }
+    }
+}
+
+/// A local link whose target could not be resolved on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub link_text: String,
+    pub link_type: LinkType,
+    pub kind: BrokenLinkKind,
+}
+
+impl BrokenLink {
+    /// Build a broken link report from the offending [`Reference`].
+    pub fn new(reference: Reference, kind: BrokenLinkKind) -> Self {
+        Self {
+            path: reference.path,
+            line: reference.line,
+            column: reference.column,
+            link_text: reference.link_text,
+            link_type: reference.link_type,
+            kind,
+        }
+    }
+}
+
+impl Display for BrokenLink {
+    /// Format as "path:line:column - link_text (reason)"
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{} - {} ({})",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.link_text,
+            self.kind.description()
+        )
+    }
+}
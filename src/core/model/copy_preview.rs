@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+use super::MoveChange;
+
+/// The planned effect of copying a file or directory: where the copy lands
+/// and the link rewrites inside the copied Markdown files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyPreview {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    /// Rewrites keyed by the copies' paths; the originals are never touched.
+    pub changes: Vec<MoveChange>,
+}
@@ -0,0 +1,16 @@
+/// A Markdown heading together with the fragment anchor it defines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// 1-based line where the heading starts.
+    pub line: usize,
+    /// 1-based column where the heading starts (after any container markers).
+    pub column: usize,
+    /// Heading level, from 1 to 6.
+    pub level: u8,
+    /// Plain heading text, without any explicit `{#id}` attribute.
+    pub text: String,
+    /// The fragment anchor that links use to point at this heading.
+    pub anchor: String,
+    /// Whether `anchor` comes from an explicit `{#id}` attribute rather than the slug.
+    pub explicit_anchor: bool,
+}
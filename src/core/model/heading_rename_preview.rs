@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+use super::MoveChange;
+
+/// The planned effect of renaming a heading: the anchor change and every
+/// file rewrite needed to keep inbound `#fragment` links working.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingRenamePreview {
+    /// The document that contains the heading.
+    pub path: PathBuf,
+    pub old_heading: String,
+    pub new_heading: String,
+    pub old_anchor: String,
+    pub new_anchor: String,
+    pub changes: Vec<MoveChange>,
+}
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// One completed operation recorded in the undo journal under a root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryEntry {
+    /// Sequence number, increasing with every recorded operation.
+    pub id: u64,
+    /// Operation that produced the entry, e.g. `mv`, `apply` or `rename-heading`.
+    pub operation: String,
+    /// Completion time in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Absolute `(source, destination)` pairs moved, in the order requested.
+    pub moves: Vec<(PathBuf, PathBuf)>,
+    /// Absolute post-operation paths of the files whose content was rewritten.
+    pub files: Vec<PathBuf>,
+}
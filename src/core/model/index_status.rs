@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+/// How the persistent link index under a root compares to the files on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexStatus {
+    /// Location of the index file, e.g. `docs/.mdref/index`.
+    pub path: PathBuf,
+    /// Whether the index file exists.
+    pub exists: bool,
+    /// Markdown files recorded in the index.
+    pub indexed_files: usize,
+    /// Indexed files whose size or modification time changed since indexing.
+    pub stale_files: usize,
+    /// Markdown files under the root that are not indexed yet.
+    pub new_files: usize,
+    /// Indexed files that no longer exist under the root.
+    pub removed_files: usize,
+    /// Local links recorded across all indexed files.
+    pub links: usize,
+}
+
+impl IndexStatus {
+    /// Whether the index exists and matches every Markdown file under the root.
+    pub fn is_fresh(&self) -> bool {
+        self.exists && self.stale_files == 0 && self.new_files == 0 && self.removed_files == 0
+    }
+}
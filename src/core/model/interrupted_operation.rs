@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+/// An operation whose write-ahead log was left behind by a crash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterruptedOperation {
+    /// Operation that was interrupted, e.g. `mv`, `apply` or `undo`.
+    pub operation: String,
+    /// Absolute `(source, destination)` pairs the operation planned to move.
+    pub moves: Vec<(PathBuf, PathBuf)>,
+    /// Path moves that had started, including moves to temporary parking paths.
+    pub started_moves: usize,
+    /// Absolute paths the rewritten files end up at once the operation completes.
+    pub files: Vec<PathBuf>,
+}
@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+use super::{BrokenLink, MoveChange};
+
+/// How `fix` picked the target of a broken link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixMatch {
+    /// The file has the content the link index recorded for the old target.
+    ContentHash,
+    /// The file is the only one under the root with the old target's name.
+    UniqueFilename,
+    /// Of several files with the old target's name, this one shares the most
+    /// leading and trailing directories with the old path.
+    PathSimilarity,
+}
+
+impl FixMatch {
+    /// Short human-readable description, e.g. `"unique filename"`.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::ContentHash => "content hash",
+            Self::UniqueFilename => "unique filename",
+            Self::PathSimilarity => "path similarity",
+        }
+    }
+}
+
+/// A link whose target is missing, with the files it may have meant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkFix {
+    pub link: BrokenLink,
+    /// Candidate targets, best first.
+    pub candidates: Vec<PathBuf>,
+    /// How the first candidate was chosen, or `None` when the link needs
+    /// manual review because there is no candidate or no clear winner.
+    pub matched_by: Option<FixMatch>,
+}
+
+impl LinkFix {
+    /// The file the link will be pointed at, if one was chosen.
+    pub fn target(&self) -> Option<&Path> {
+        self.matched_by
+            .and(self.candidates.first())
+            .map(PathBuf::as_path)
+    }
+}
+
+/// The planned repair of the broken links under a root.
+#[derive(Debug)]
+pub struct FixPreview {
+    pub root: PathBuf,
+    /// Every link with a missing target, sorted by path, line, and column.
+    /// Empty when repairing from git history or from moves already made.
+    pub fixes: Vec<LinkFix>,
+    /// The `(source, destination)` renames replayed, as absolute paths. Empty
+    /// unless repairing from git history or from moves already made.
+    pub renames: Vec<(PathBuf, PathBuf)>,
+    /// The rewrites for the links that have a [`LinkFix::target`], or for
+    /// every link affected by the replayed renames.
+    pub changes: Vec<MoveChange>,
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::LinkType;
+use crate::{
+    MdrefError, Result,
+    core::graph::{render_dot, render_graphml, render_mermaid},
+};
+
+/// A link from one file to another, as an edge of a [`LinkGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// The Markdown file containing the link, relative to the root.
+    pub source: PathBuf,
+    /// The file or directory the link resolves to, relative to the root.
+    pub target: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub link_text: String,
+    pub link_type: LinkType,
+}
+
+/// Every file under a root and the local links between them.
+///
+/// Nodes are the Markdown files under the root plus every existing file or
+/// directory they link to; links to external URLs, pure `#fragment` links,
+/// and links whose target is missing are left out. All paths are relative to
+/// [`Self::root`] (targets outside it start with `..`). Nodes are sorted, and
+/// edges are sorted by source, line, and column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkGraph {
+    /// The canonical root the graph was built from.
+    pub root: PathBuf,
+    pub nodes: Vec<PathBuf>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl LinkGraph {
+    /// The part of the graph inside `dir`: its nodes and the edges between
+    /// them. `dir` is resolved like any other path, not against the root.
+    pub fn subtree<P: AsRef<Path>>(&self, dir: P) -> Result<Self> {
+        let dir = self.node_path(dir.as_ref())?;
+        Ok(self.retain(|node| node.starts_with(&dir)))
+    }
+
+    /// The nodes at most `depth` links away from `file`, following links in
+    /// either direction, and the edges between them.
+    pub fn neighborhood<P: AsRef<Path>>(&self, file: P, depth: usize) -> Result<Self> {
+        let start = self.node_path(file.as_ref())?;
+        let mut adjacent: HashMap<&Path, Vec<&Path>> = HashMap::new();
+        for edge in &self.edges {
+            adjacent.entry(&edge.source).or_default().push(&edge.target);
+            adjacent.entry(&edge.target).or_default().push(&edge.source);
+        }
+
+        let mut reached: HashSet<&Path> = HashSet::from([start.as_path()]);
+        let mut queue = VecDeque::from([(start.as_path(), 0)]);
+        while let Some((node, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for &next in adjacent.get(node).into_iter().flatten() {
+                if reached.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        Ok(self.retain(|node| reached.contains(node)))
+    }
+
+    /// Render as a Graphviz DOT digraph.
+    pub fn to_dot(&self) -> String {
+        render_dot(self)
+    }
+
+    /// Render as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        render_mermaid(self)
+    }
+
+    /// Render as a GraphML document.
+    pub fn to_graphml(&self) -> String {
+        render_graphml(self)
+    }
+
+    fn retain(&self, keep: impl Fn(&Path) -> bool) -> Self {
+        Self {
+            root: self.root.clone(),
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|node| keep(node))
+                .cloned()
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| keep(&edge.source) && keep(&edge.target))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// `path` relative to the root, as the graph names it.
+    fn node_path(&self, path: &Path) -> Result<PathBuf> {
+        let canonical = path.canonicalize().map_err(|e| MdrefError::IoRead {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        canonical
+            .strip_prefix(&self.root)
+            .map(Path::to_path_buf)
+            .map_err(|_| MdrefError::PathValidation {
+                path: path.to_path_buf(),
+                details: format!("not inside the graph root '{}'", self.root.display()),
+            })
+    }
+}
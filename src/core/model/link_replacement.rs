@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// A pending replacement: which line/column to find the old pattern, and what to replace it with.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LinkReplacement {
     pub line: usize,
     pub column: usize,
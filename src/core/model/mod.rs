@@ -1,9 +1,31 @@
+pub mod broken_link;
+pub mod copy_preview;
+pub mod heading;
+pub mod heading_rename_preview;
+pub mod history_entry;
+pub mod index_status;
+pub mod interrupted_operation;
+pub mod link_fix;
+pub mod link_graph;
 pub mod link_replacement;
 pub mod move_preview;
 pub mod move_transaction;
+pub mod orphan_report;
 pub mod reference;
+pub mod remove_preview;
 
+pub use broken_link::{BrokenLink, BrokenLinkKind};
+pub use copy_preview::CopyPreview;
+pub use heading::Heading;
+pub use heading_rename_preview::HeadingRenamePreview;
+pub use history_entry::HistoryEntry;
+pub use index_status::IndexStatus;
+pub use interrupted_operation::InterruptedOperation;
+pub use link_fix::{FixMatch, FixPreview, LinkFix};
+pub use link_graph::{GraphEdge, LinkGraph};
 pub use link_replacement::LinkReplacement;
 pub use move_preview::{MoveChange, MoveChangeKind, MovePreview};
 pub use move_transaction::MoveTransaction;
+pub use orphan_report::OrphanReport;
 pub use reference::{LinkType, Reference};
+pub use remove_preview::RemovePreview;
@@ -1,23 +1,72 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
 
 use super::LinkReplacement;
+use crate::{
+    Result,
+    core::{diff::render_move_preview, wikilink::absolute},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MoveChangeKind {
     ReferenceUpdate,
     MovedFileUpdate,
+    /// The file whose heading was renamed (the heading line and its own `#slug` links).
+    HeadingUpdate,
+    /// A copy whose links were rewritten for its new location.
+    CopiedFileUpdate,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MoveChange {
     pub path: PathBuf,
     pub kind: MoveChangeKind,
     pub replacements: Vec<LinkReplacement>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MovePreview {
     pub source: PathBuf,
     pub destination: PathBuf,
+    /// Every `(source, destination)` pair moved; a single move lists one pair,
+    /// a batch move lists all of them.
+    pub moves: Vec<(PathBuf, PathBuf)>,
     pub changes: Vec<MoveChange>,
+    /// Content hash ([`crate::core::util::content_hash`]) of every file the
+    /// move rewrites or carries, keyed by its absolute pre-move path.
+    /// `apply_move_preview` refuses to run when any of them changed.
+    #[serde(default)]
+    pub file_hashes: BTreeMap<PathBuf, String>,
+}
+
+impl MovePreview {
+    /// Return the preview with every path made absolute against the current
+    /// directory, so a saved plan can be applied from anywhere.
+    pub fn into_absolute(mut self) -> Self {
+        self.source = absolute(&self.source);
+        self.destination = absolute(&self.destination);
+        for (source, destination) in &mut self.moves {
+            *source = absolute(source);
+            *destination = absolute(destination);
+        }
+        for change in &mut self.changes {
+            change.path = absolute(&change.path);
+        }
+        self
+    }
+
+    /// Render the preview as a unified diff with paths relative to `root`.
+    ///
+    /// Moved files get `rename from` / `rename to` headers and every rewrite
+    /// becomes a hunk with context lines, so the output can be read in a pager
+    /// or applied from `root` with `git apply`. Files are read from disk, so
+    /// call this before the move is applied.
+    pub fn to_unified_diff<P: AsRef<Path>>(&self, root: P) -> Result<String> {
+        render_move_preview(self, root.as_ref())
+    }
 }
@@ -1,4 +1,17 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    Result,
+    core::{
+        git::GitWorkTree,
+        recover::WriteAheadLog,
+        util::{remove_path, write_atomic},
+    },
+};
 
 #[derive(Clone, Copy)]
 enum MoveKind {
@@ -6,12 +19,34 @@ enum MoveKind {
     RenamedPath,
 }
 
+/// One step of a batch move, recorded after it completed.
+struct MovedPath {
+    from: PathBuf,
+    to: PathBuf,
+    /// Moved by copying and deleting `from` rather than renaming.
+    copied: bool,
+}
+
 /// Tracks all filesystem mutations so they can be rolled back on failure.
 ///
 /// The transaction records three kinds of operations:
 /// 1. **File snapshots** – original content of files that will be modified in-place.
 /// 2. **Copied destination** – the new file created by `fs::copy`.
 /// 3. **Removed source** – set after the original file is deleted, so rollback can restore it.
+///
+/// Batch moves (see [`Self::for_batch`]) instead record every completed path
+/// move with [`Self::record_move`]; rollback undoes them in reverse order
+/// before restoring snapshots, which are keyed by pre-move paths.
+///
+/// A transaction with an attached write-ahead log also persists its plan, so
+/// an operation cut short by a crash can be recovered with
+/// [`crate::core::recover::recover`]. Every path move is logged with
+/// [`Self::log_move`] before it starts, and [`Self::commit`] or a clean
+/// rollback removes the log.
+///
+/// A move performed with `git mv` also snapshots the git index with
+/// [`Self::snapshot_git_index`]; rollback writes it back, so the index is
+/// reset to what it was before the move.
 pub struct MoveTransaction {
     pub file_snapshots: HashMap<PathBuf, String>,
     pub copied_destination: Option<PathBuf>,
@@ -19,6 +54,11 @@ pub struct MoveTransaction {
     pub source_path: PathBuf,
     pub destination_path: PathBuf,
     move_kind: Option<MoveKind>,
+    moved_paths: Vec<MovedPath>,
+    log: Option<WriteAheadLog>,
+    /// The git index path and its content before the move, `None` when the
+    /// index did not exist yet.
+    git_index: Option<(PathBuf, Option<Vec<u8>>)>,
 }
 
 impl MoveTransaction {
@@ -30,11 +70,46 @@ impl MoveTransaction {
             source_path,
             destination_path,
             move_kind: None,
+            moved_paths: Vec::new(),
+            log: None,
+            git_index: None,
+        }
+    }
+
+    /// Create a transaction for a batch of moves recorded with [`Self::record_move`].
+    pub fn for_batch() -> Self {
+        Self::new(PathBuf::new(), PathBuf::new())
+    }
+
+    /// Record that `from` was moved to `to`, by rename or (when `copied`) by
+    /// copying and then deleting `from`.
+    pub fn record_move(&mut self, from: PathBuf, to: PathBuf, copied: bool) {
+        self.moved_paths.push(MovedPath { from, to, copied });
+    }
+
+    /// Persist the transaction through `log` from here on.
+    pub(crate) fn attach_log(&mut self, log: WriteAheadLog) {
+        self.log = Some(log);
+    }
+
+    /// Durably record that `from` is about to move to `to`.
+    pub(crate) fn log_move(&self, from: &Path, to: &Path) -> Result<()> {
+        match &self.log {
+            Some(log) => log.log_move(from, to),
+            None => Ok(()),
+        }
+    }
+
+    /// Mark the transaction complete by removing its write-ahead log.
+    pub(crate) fn commit(&self) -> Result<()> {
+        match &self.log {
+            Some(log) => log.remove(),
+            None => Ok(()),
         }
     }
 
     /// Snapshot a file's current content before modifying it.
-    pub fn snapshot_file(&mut self, path: &std::path::Path) -> std::io::Result<()> {
+    pub fn snapshot_file(&mut self, path: &Path) -> std::io::Result<()> {
         if !self.file_snapshots.contains_key(path) {
             let content = fs::read_to_string(path)?;
             self.file_snapshots.insert(path.to_path_buf(), content);
@@ -42,6 +117,14 @@ impl MoveTransaction {
         Ok(())
     }
 
+    /// Snapshot the index of `git` before the first `git mv` or `git add`.
+    pub(crate) fn snapshot_git_index(&mut self, git: &GitWorkTree) -> Result<()> {
+        if self.git_index.is_none() {
+            self.git_index = Some(git.read_index()?);
+        }
+        Ok(())
+    }
+
     /// Record that the destination file was created via copy.
     pub fn mark_copied(&mut self) {
         self.copied_destination = Some(self.destination_path.clone());
@@ -64,6 +147,22 @@ impl MoveTransaction {
     pub fn rollback(&self) -> Vec<String> {
         let mut errors = Vec::new();
 
+        for moved in self.moved_paths.iter().rev() {
+            let result = if moved.copied {
+                fs::copy(&moved.to, &moved.from).and_then(|_| remove_path(&moved.to))
+            } else {
+                fs::rename(&moved.to, &moved.from)
+            };
+            if let Err(err) = result {
+                errors.push(format!(
+                    "Failed to move {} back to {}: {}",
+                    moved.to.display(),
+                    moved.from.display(),
+                    err
+                ));
+            }
+        }
+
         match self.move_kind {
             Some(MoveKind::RenamedPath) => {
                 if self.source_removed
@@ -81,14 +180,14 @@ impl MoveTransaction {
                 }
 
                 for (path, original_content) in &self.file_snapshots {
-                    if let Err(err) = fs::write(path, original_content) {
+                    if let Err(err) = write_atomic(path, original_content.as_bytes()) {
                         errors.push(format!("Failed to restore {}: {}", path.display(), err));
                     }
                 }
             }
             _ => {
                 for (path, original_content) in &self.file_snapshots {
-                    if let Err(err) = fs::write(path, original_content) {
+                    if let Err(err) = write_atomic(path, original_content.as_bytes()) {
                         errors.push(format!("Failed to restore {}: {}", path.display(), err));
                     }
                 }
@@ -120,14 +219,31 @@ impl MoveTransaction {
             }
         }
 
-        errors
-    }
-}
+        if let Some((index, content)) = &self.git_index {
+            let result = match content {
+                Some(content) => write_atomic(index, content),
+                None => remove_path(index).or_else(|err| match err.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(err),
+                }),
+            };
+            if let Err(err) = result {
+                errors.push(format!(
+                    "Failed to reset git index {}: {}",
+                    index.display(),
+                    err
+                ));
+            }
+        }
+
+        // Only a clean rollback makes the write-ahead log obsolete; otherwise
+        // it is kept for `recover`.
+        if errors.is_empty()
+            && let Err(err) = self.commit()
+        {
+            errors.push(err.to_string());
+        }
 
-fn remove_path(path: &std::path::Path) -> std::io::Result<()> {
-    if path.is_dir() {
-        fs::remove_dir_all(path)
-    } else {
-        fs::remove_file(path)
+        errors
     }
 }
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Pages and assets under a root that readers cannot navigate to, and pages
+/// they cannot navigate away from. All paths are relative to [`Self::root`]
+/// and sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanReport {
+    /// The canonical root that was scanned.
+    pub root: PathBuf,
+    /// The Markdown pages reachability starts from.
+    pub entrypoints: Vec<PathBuf>,
+    /// Markdown pages no other file links to, entrypoints excepted.
+    pub orphans: Vec<PathBuf>,
+    /// Markdown pages that cannot be reached by following links from an
+    /// entrypoint, grouped into sets connected by links in either direction.
+    /// Groups are sorted by their first page.
+    pub unreachable: Vec<Vec<PathBuf>>,
+    /// Non-Markdown files no Markdown page links to.
+    pub unused_assets: Vec<PathBuf>,
+    /// Markdown pages that link to no other Markdown page.
+    pub dead_ends: Vec<PathBuf>,
+}
+
+impl OrphanReport {
+    /// Whether nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty()
+            && self.unreachable.is_empty()
+            && self.unused_assets.is_empty()
+            && self.dead_ends.is_empty()
+    }
+}
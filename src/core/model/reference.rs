@@ -3,17 +3,41 @@ use std::{
     path::PathBuf,
 };
 
+use serde::{Deserialize, Serialize};
+
 /// The type of Markdown link that produced this reference.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LinkType {
     /// An inline link: `[text](url)` or `![alt](url)`
     Inline,
     /// A link reference definition: `[label]: url`
     ReferenceDefinition,
+    /// A wikilink or embed: `[[Page]]`, `[[Page#Heading|alias]]` or `![[file.png]]`
+    WikiLink,
+    /// A URL in a raw HTML attribute: `<a href="url">`, `<img src="url">` or
+    /// `<source srcset="url 2x">`
+    HtmlAttribute,
+    /// A relative path in an MDX `import` or `export … from` statement:
+    /// `import Intro from './intro.mdx'`
+    Import,
+}
+
+impl LinkType {
+    /// The snake_case name also used when serializing, e.g. `"wiki_link"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inline => "inline",
+            Self::ReferenceDefinition => "reference_definition",
+            Self::WikiLink => "wiki_link",
+            Self::HtmlAttribute => "html_attribute",
+            Self::Import => "import",
+        }
+    }
 }
 
 /// Struct to hold reference information
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Reference {
     pub path: PathBuf,
     pub line: usize,
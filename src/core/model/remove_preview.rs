@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use super::{MoveChange, Reference};
+
+/// The planned effect of removing a file or directory: the links that still
+/// point at it and the rewrites that unlink or redirect them.
+#[derive(Debug)]
+pub struct RemovePreview {
+    pub path: PathBuf,
+    /// Inbound references from files that are not being removed.
+    pub references: Vec<Reference>,
+    /// Empty unless the references are unlinked or redirected.
+    pub changes: Vec<MoveChange>,
+}
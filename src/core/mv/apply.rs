@@ -4,31 +4,46 @@
 //! Callers are expected to have already built a [`super::plan::ReplacementPlan`]
 //! and a `MoveTransaction` snapshotting every file that will be touched.
 //!
-//! The module covers three sub-concerns:
+//! The module covers five sub-concerns:
 //!
-//! - rollback orchestration: [`execute_with_rollback`]
+//! - rollback orchestration: [`execute_with_rollback`], [`rollback_on_error`]
+//! - concurrent-edit detection before the first mutation: [`verify_snapshots`]
+//! - batch path moves that respect chains and swaps: [`move_paths`]
 //! - regular-file rename with cross-device fallback: [`RegularFileMoveMethod`],
 //!   [`try_rename_regular_file`] (and the injectable variant used in tests)
-//! - in-place file rewriting that preserves original line endings:
-//!   [`apply_replacements`] plus the `LineEnding` helpers
+//! - crash-safe file rewriting that preserves original line endings:
+//!   [`apply_replacements`] / [`rewrite_content`] plus the `LineEnding` helpers
 
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     MdrefError, Result,
-    core::model::{LinkReplacement, MoveTransaction},
+    core::{
+        find::FileHashes,
+        model::{LinkReplacement, MoveTransaction},
+        util::{content_hash, write_atomic},
+    },
 };
 
 // ============= Rollback orchestration =============
 
 /// Execute a fallible closure within a transaction context.
 /// If the closure returns an error, the transaction is rolled back automatically.
-pub(super) fn execute_with_rollback<F>(transaction: &MoveTransaction, operation: F) -> Result<()>
+pub(crate) fn execute_with_rollback<F>(transaction: &MoveTransaction, operation: F) -> Result<()>
 where
     F: FnOnce() -> Result<()>,
 {
-    match operation() {
-        Ok(()) => Ok(()),
+    rollback_on_error(transaction, operation())
+}
+
+/// Roll the transaction back if `result` is an error, folding any rollback
+/// failures into [`MdrefError::RollbackFailed`].
+pub(crate) fn rollback_on_error<T>(transaction: &MoveTransaction, result: Result<T>) -> Result<T> {
+    match result {
+        Ok(value) => Ok(value),
         Err(original_error) => {
             let rollback_errors = transaction.rollback();
             if rollback_errors.is_empty() {
@@ -43,6 +58,109 @@ where
     }
 }
 
+// ============= Concurrent-edit detection =============
+
+/// Refuse to go on when a snapshotted file no longer has the content it had
+/// when it was scanned, so replacements planned from that content never land
+/// in a file someone edited meanwhile. Files without a scan hash are skipped.
+///
+/// Call after snapshotting and before the first mutation: a failure leaves
+/// nothing to roll back.
+pub(crate) fn verify_snapshots(transaction: &MoveTransaction, scanned: &FileHashes) -> Result<()> {
+    let mut changed: Vec<PathBuf> = transaction
+        .file_snapshots
+        .iter()
+        .filter(|(path, content)| {
+            scanned
+                .get(*path)
+                .is_some_and(|hash| *hash != content_hash(content.as_bytes()))
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    changed.sort();
+    Err(MdrefError::ConcurrentModification { paths: changed })
+}
+
+// ============= Batch path moves =============
+
+/// Move every `(from, to)` pair on disk, recording each step in `transaction`.
+///
+/// A move waits while its destination is still the source of a pending move,
+/// so chains land back to front. Cycles such as swaps are broken by parking
+/// one source at a temporary sibling path first.
+pub(crate) fn move_paths(
+    moves: &[(PathBuf, PathBuf)],
+    transaction: &mut MoveTransaction,
+) -> Result<()> {
+    let mut pending = moves.to_vec();
+
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(_, to)| !pending.iter().any(|(from, _)| from == to));
+
+        match ready {
+            Some(index) => {
+                let (from, to) = pending.remove(index);
+                move_path(&from, &to, transaction)?;
+            }
+            None => {
+                let (from, to) = pending.remove(0);
+                let parked = parking_path(&from);
+                move_path(&from, &parked, transaction)?;
+                pending.push((parked, to));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn move_path(from: &Path, to: &Path, transaction: &mut MoveTransaction) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    transaction.log_move(from, to)?;
+
+    if from.is_dir() {
+        fs::rename(from, to)?;
+        transaction.record_move(from.to_path_buf(), to.to_path_buf(), false);
+        return Ok(());
+    }
+
+    match try_rename_regular_file(from, to)? {
+        RegularFileMoveMethod::Renamed => {
+            transaction.record_move(from.to_path_buf(), to.to_path_buf(), false);
+        }
+        RegularFileMoveMethod::CopyAndDelete => {
+            fs::copy(from, to)?;
+            transaction.record_move(from.to_path_buf(), to.to_path_buf(), true);
+            fs::remove_file(from)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// An unused hidden sibling of `path`, e.g. `.a.md.mdref-tmp`.
+pub(crate) fn parking_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut candidate = path.with_file_name(format!(".{name}.mdref-tmp"));
+    let mut counter = 1;
+    while candidate.exists() {
+        candidate = path.with_file_name(format!(".{name}.mdref-tmp{counter}"));
+        counter += 1;
+    }
+    candidate
+}
+
 // ============= Regular-file rename with fallback =============
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -125,13 +243,30 @@ fn split_lines_preserving_endings(content: &str) -> Vec<(String, LineEnding)> {
 
 /// Apply all pending replacements to a single file in one read-write cycle.
 /// Replacements are sorted in reverse order (by line desc, then column desc) so that
-/// earlier replacements do not shift the positions of later ones.
-pub(super) fn apply_replacements(file_path: &Path, replacements: &[LinkReplacement]) -> Result<()> {
+/// earlier replacements do not shift the positions of later ones. The file is
+/// replaced through [`write_atomic`], so a crash never leaves it truncated.
+pub(crate) fn apply_replacements(file_path: &Path, replacements: &[LinkReplacement]) -> Result<()> {
     let content = fs::read_to_string(file_path).map_err(|e| MdrefError::IoRead {
         path: file_path.to_path_buf(),
         source: e,
     })?;
-    let mut lines = split_lines_preserving_endings(&content);
+    let new_content = rewrite_content(file_path, &content, replacements)?;
+    write_atomic(file_path, new_content.as_bytes()).map_err(|e| MdrefError::IoWrite {
+        path: file_path.to_path_buf(),
+        source: e,
+    })?;
+
+    Ok(())
+}
+
+/// Apply `replacements` to `content` in memory; `file_path` is only used in
+/// error messages.
+pub(crate) fn rewrite_content(
+    file_path: &Path,
+    content: &str,
+    replacements: &[LinkReplacement],
+) -> Result<String> {
+    let mut lines = split_lines_preserving_endings(content);
 
     // Sort replacements in reverse order (bottom-right to top-left) so that
     // replacing one link does not invalidate the positions of subsequent ones.
@@ -180,14 +315,8 @@ pub(super) fn apply_replacements(file_path: &Path, replacements: &[LinkReplaceme
         }
     }
 
-    let new_content = lines
+    Ok(lines
         .into_iter()
         .map(|(line, ending)| format!("{line}{}", ending.as_str()))
-        .collect::<String>();
-    fs::write(file_path, new_content).map_err(|e| MdrefError::IoWrite {
-        path: file_path.to_path_buf(),
-        source: e,
-    })?;
-
-    Ok(())
+        .collect())
 }
@@ -14,7 +14,7 @@ use super::{
     plan::{LineCache, ReplacementPlan, build_replacement, split_link_and_anchor},
     validate::{canonicalize_destination, resolve_destination},
 };
-use crate::{MdrefError, Reference, Result, core::pathdiff::diff_paths};
+use crate::{LinkType, MdrefError, Reference, Result, core::pathdiff::diff_paths};
 
 /// Detect a case-only rename within the same parent directory.
 ///
@@ -96,6 +96,11 @@ pub(super) fn plan_case_only_external_replacements(
     let mut line_cache = LineCache::new();
 
     for reference in references {
+        // Wikilinks resolve case-insensitively, so they keep working as written.
+        if reference.link_type == LinkType::WikiLink {
+            continue;
+        }
+
         let (_link_path_only, anchor) = split_link_and_anchor(&reference.link_text);
         let new_link_path = relative_path_preserving_filename_case(&reference.path, resolved_dest)?;
 
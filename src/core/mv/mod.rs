@@ -8,36 +8,54 @@
 //! - [`apply`]: mutate the filesystem (rename / copy+delete, rewrite files,
 //!   execute under a rollback-protected transaction)
 //! - [`preview`]: render dry-run reports and the structured [`MovePreview`]
+//! - [`replay`]: hash the files a preview touches and apply a saved preview
 //!
-//! This top-level file keeps only the public API (`mv`, `preview_move`) and the
-//! three orchestration routines for regular files, case-only renames, and
-//! directory moves.
+//! This top-level file keeps only the public API (`mv`, `mv_with_git`, `preview_move`,
+//! `mv_many`, `preview_move_many`, `apply_move_preview`) and the orchestration routines for regular
+//! files, case-only renames, directory moves, and batch moves.
 
-mod apply;
+pub(crate) mod apply;
 mod case_only;
-mod plan;
-mod preview;
-mod validate;
-
-use std::{collections::HashMap, fs, path::Path};
+pub(crate) mod plan;
+pub(crate) mod preview;
+mod replay;
+pub(crate) mod validate;
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use self::{
     apply::{
-        RegularFileMoveMethod, apply_replacements, execute_with_rollback, try_rename_regular_file,
+        RegularFileMoveMethod, apply_replacements, execute_with_rollback, move_paths,
+        rollback_on_error, try_rename_regular_file, verify_snapshots,
     },
     case_only::{plan_case_only_external_replacements, resolve_case_only_destination},
     plan::{
         add_destination_replacements, move_source_replacements_to_destination,
-        plan_directory_replacements, plan_external_replacements, plan_internal_replacements,
+        plan_batch_replacements, plan_directory_replacements, plan_external_replacements,
+        plan_internal_replacements,
     },
-    preview::{build_move_preview, print_dry_run_report},
-    validate::validate_move_paths,
+    preview::{build_batch_move_preview, build_move_preview, print_dry_run_report},
+    replay::{apply_preview, record_file_hashes},
+    validate::{validate_batch_moves, validate_move_paths},
 };
 // Re-export the structured preview shape so callers can match on it.
 pub use crate::core::model::MovePreview;
 use crate::{
-    Result,
-    core::{find::find_references, model::MoveTransaction, progress::ProgressReporter},
+    MdrefError, Result,
+    core::{
+        editor::Overlay,
+        find::{FileHashes, find_references, find_references_with_hashes},
+        git::GitWorkTree,
+        journal::record_operation,
+        model::MoveTransaction,
+        progress::ProgressReporter,
+        recover::WriteAheadLog,
+        wikilink::VaultMove,
+    },
 };
 
 // ============= Public API =============
@@ -57,6 +75,9 @@ use crate::{
 /// If the destination path is an existing directory, the source file will be moved into that
 /// directory with its original filename preserved.
 ///
+/// A completed move is recorded in the undo journal under `root`, so
+/// [`crate::undo`] can revert it.
+///
 /// # Progress
 ///
 /// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
@@ -68,6 +89,48 @@ pub fn mv<P, B, D>(
     dry_run: bool,
     progress: &dyn ProgressReporter,
 ) -> Result<()>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    mv_with_git(source, dest, root, GitMode::Off, false, dry_run, progress)
+}
+
+/// Whether [`mv_with_git`] performs the move with `git mv`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GitMode {
+    /// Move on the filesystem only, like [`mv`].
+    #[default]
+    Off,
+    /// Use git when the source is inside a git work tree.
+    Auto,
+    /// Use git, and fail when the source is not inside a git work tree.
+    Always,
+}
+
+/// Move a Markdown file or directory like [`mv`], optionally through git.
+///
+/// When git is used, a tracked source is moved with `git mv` so the index
+/// records a rename, an untracked one is moved on the filesystem, and every
+/// tracked file the move rewrites is staged afterwards. The move is refused
+/// with [`MdrefError::UncommittedChanges`] when a tracked file at or under
+/// `source` has staged or unstaged changes, unless `force` is set. If a later
+/// step fails, rollback also writes back the index as it was before the move.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn mv_with_git<P, B, D>(
+    source: P,
+    dest: B,
+    root: D,
+    mode: GitMode,
+    force: bool,
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
 where
     P: AsRef<Path>,
     B: AsRef<Path>,
@@ -77,17 +140,34 @@ where
     let dest = dest.as_ref();
     let root = root.as_ref();
 
+    // A missing source is reported by path validation instead.
+    let git = match mode {
+        _ if !source.exists() => None,
+        GitMode::Off => None,
+        GitMode::Auto => GitWorkTree::discover(source).ok(),
+        GitMode::Always => Some(GitWorkTree::discover(source)?),
+    };
+    if let Some(git) = &git
+        && !force
+        && git.has_uncommitted_changes(source)?
+    {
+        return Err(MdrefError::UncommittedChanges {
+            path: source.to_path_buf(),
+        });
+    }
+
     if source.is_dir() {
-        return mv_directory(source, dest, root, dry_run, progress);
+        return mv_directory(source, dest, root, git.as_ref(), dry_run, progress);
     }
 
-    mv_regular_file(source, dest, root, dry_run, progress)
+    mv_regular_file(source, dest, root, git.as_ref(), dry_run, progress)
 }
 
 /// Preview a Markdown move without mutating the filesystem.
 ///
-/// The returned preview contains the resolved destination path and all link
-/// replacements that would be applied by the move.
+/// The returned preview contains the resolved destination path, all link
+/// replacements that would be applied by the move, and the content hashes
+/// [`apply_move_preview`] checks before executing it.
 ///
 /// # Progress
 ///
@@ -108,11 +188,160 @@ where
     let dest = dest.as_ref();
     let root = root.as_ref();
 
-    if source.is_dir() {
-        return preview_directory_move(source, dest, root, progress);
+    let mut preview = if source.is_dir() {
+        preview_directory_move(source, dest, root, progress)?
+    } else {
+        preview_regular_file_move(source, dest, root, progress)?
+    };
+    record_file_hashes(&mut preview)?;
+
+    Ok(preview)
+}
+
+/// Move several Markdown files or directories at once and update all references.
+///
+/// Every `(source, destination)` pair is validated before anything is touched:
+/// chains (`a -> b`, `b -> c`) and swaps (`a -> b`, `b -> a`) are allowed,
+/// while two sources sharing a destination, a destination that already exists,
+/// or overlapping sources are rejected. References are found with a single
+/// scan of `root`, and links between moved files are rewritten relative to
+/// their new locations.
+///
+/// **Atomicity guarantee**: all moves and rewrites run under one transaction.
+/// If any step fails, every completed move is undone and every modified file is
+/// restored, so either all moves land or none do.
+///
+/// When `dry_run` is `true`, the combined changes are printed instead.
+/// Otherwise the batch is recorded in the undo journal under `root` as a
+/// single operation.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn mv_many<P, B, D>(
+    moves: &[(P, B)],
+    root: D,
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let moves = validate_batch_moves(&to_path_pairs(moves))?;
+
+    // Phase 1: Plan — pure computation, no side effects.
+    let mut scanned_hashes = FileHashes::new();
+    let (replacements_by_file, snapshot_paths) =
+        plan_batch_replacements(&moves, root, progress, &mut scanned_hashes, &Overlay::new())?;
+
+    if dry_run {
+        let preview = build_batch_move_preview(&moves, replacements_by_file);
+        print_dry_run_report(&preview);
+        return Ok(());
     }
 
-    preview_regular_file_move(source, dest, root, progress)
+    // Phase 2: Execute — every move and rewrite is tracked for rollback.
+    let mut transaction = MoveTransaction::for_batch();
+    for snapshot_path in snapshot_paths {
+        transaction.snapshot_file(&snapshot_path)?;
+    }
+    verify_snapshots(&transaction, &scanned_hashes)?;
+
+    let path_moves: Vec<(PathBuf, PathBuf)> = moves
+        .iter()
+        .map(|entry| (entry.source_canonical.clone(), entry.dest_canonical.clone()))
+        .collect();
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root,
+        "mv",
+        &path_moves,
+        &replacements_by_file,
+    )?);
+
+    let moved = move_paths(&path_moves, &mut transaction);
+    rollback_on_error(&transaction, moved)?;
+
+    execute_with_rollback(&transaction, || {
+        for (file_path, replacements) in &replacements_by_file {
+            apply_replacements(file_path, replacements)?;
+        }
+        Ok(())
+    })?;
+
+    transaction.commit()?;
+    record_operation(root, "mv", &path_moves, &transaction.file_snapshots);
+    Ok(())
+}
+
+/// Preview a batch move without mutating the filesystem.
+///
+/// The returned preview lists every resolved `(source, destination)` pair in
+/// [`MovePreview::moves`] and the combined link replacements.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_move_many<P, B, D>(
+    moves: &[(P, B)],
+    root: D,
+    progress: &dyn ProgressReporter,
+) -> Result<MovePreview>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    preview_batch_in_overlay(
+        &to_path_pairs(moves),
+        root.as_ref(),
+        &Overlay::new(),
+        progress,
+    )
+}
+
+/// Like [`preview_move_many`], with the files in `overlay` planned from their
+/// overlaid contents.
+pub(crate) fn preview_batch_in_overlay(
+    moves: &[(PathBuf, PathBuf)],
+    root: &Path,
+    overlay: &Overlay,
+    progress: &dyn ProgressReporter,
+) -> Result<MovePreview> {
+    let moves = validate_batch_moves(moves)?;
+    let (replacements_by_file, _snapshot_paths) =
+        plan_batch_replacements(&moves, root, progress, &mut FileHashes::new(), overlay)?;
+    let mut preview = build_batch_move_preview(&moves, replacements_by_file);
+    record_file_hashes(&mut preview)?;
+
+    Ok(preview)
+}
+
+/// Apply a preview returned by [`preview_move`] or [`preview_move_many`],
+/// possibly after a round trip through JSON, exactly as planned.
+///
+/// Nothing is re-planned: the recorded moves and replacements are executed
+/// as-is under one transaction. Before any mutation, every file the preview
+/// rewrites or moves is hashed and compared with [`MovePreview::file_hashes`];
+/// if one is missing, changed, or has no recorded hash, the function returns
+/// an error without touching anything.
+///
+/// Relative paths in the preview are resolved against the current directory;
+/// save a preview with [`MovePreview::into_absolute`] to apply it from
+/// anywhere. The applied plan is recorded in the undo journal of `root`.
+pub fn apply_move_preview<D: AsRef<Path>>(preview: &MovePreview, root: D) -> Result<()> {
+    apply_preview(preview, root.as_ref())
+}
+
+fn to_path_pairs<P: AsRef<Path>, B: AsRef<Path>>(moves: &[(P, B)]) -> Vec<(PathBuf, PathBuf)> {
+    moves
+        .iter()
+        .map(|(source, dest)| (source.as_ref().to_path_buf(), dest.as_ref().to_path_buf()))
+        .collect()
 }
 
 // ============= Orchestration: preview =============
@@ -127,22 +356,30 @@ fn preview_regular_file_move(
         return preview_case_only_file_move(source, &case_only_dest, root, progress);
     }
 
-    let (resolved_dest, _source_canonical, _dest_canonical) =
-        match validate_move_paths(source, dest) {
-            Ok(paths) => paths,
-            Err(e) => {
-                if e.to_string().contains("resolve to the same file") {
-                    return Ok(build_move_preview(source, source, HashMap::new()));
-                }
-                return Err(e);
+    let (resolved_dest, source_canonical, dest_canonical) = match validate_move_paths(source, dest)
+    {
+        Ok(paths) => paths,
+        Err(e) => {
+            if e.to_string().contains("resolve to the same file") {
+                return Ok(build_move_preview(source, source, HashMap::new()));
             }
-        };
+            return Err(e);
+        }
+    };
 
     progress.set_message("Scanning references...");
     let references = find_references(source, root, progress)?;
-    let mut replacements_by_file = plan_external_replacements(&references, &resolved_dest)?;
+    let vault_move = VaultMove::new(root, &source_canonical, &dest_canonical);
+    let mut replacements_by_file =
+        plan_external_replacements(&references, &resolved_dest, &vault_move)?;
     replacements_by_file.remove(source);
-    let internal_replacements = plan_internal_replacements(source, source, &resolved_dest)?;
+    let internal_replacements = plan_internal_replacements(
+        source,
+        source,
+        &resolved_dest,
+        &vault_move,
+        &mut FileHashes::new(),
+    )?;
     add_destination_replacements(
         &mut replacements_by_file,
         &resolved_dest,
@@ -198,6 +435,7 @@ fn preview_directory_move(
         &dest_canonical,
         root,
         progress,
+        &mut FileHashes::new(),
     )?;
 
     Ok(build_move_preview(
@@ -213,31 +451,41 @@ fn mv_regular_file(
     source: &Path,
     dest: &Path,
     root: &Path,
+    git: Option<&GitWorkTree>,
     dry_run: bool,
     progress: &dyn ProgressReporter,
 ) -> Result<()> {
     if let Some(case_only_dest) = resolve_case_only_destination(source, dest)? {
-        return mv_case_only_file(source, &case_only_dest, root, dry_run, progress);
+        return mv_case_only_file(source, &case_only_dest, root, git, dry_run, progress);
     }
 
-    let (resolved_dest, _source_canonical, _dest_canonical) =
-        match validate_move_paths(source, dest) {
-            Ok(paths) => paths,
-            Err(e) => {
-                // Special case: source == destination is a no-op, not an error.
-                if e.to_string().contains("resolve to the same file") {
-                    return Ok(());
-                }
-                return Err(e);
+    let (resolved_dest, source_canonical, dest_canonical) = match validate_move_paths(source, dest)
+    {
+        Ok(paths) => paths,
+        Err(e) => {
+            // Special case: source == destination is a no-op, not an error.
+            if e.to_string().contains("resolve to the same file") {
+                return Ok(());
             }
-        };
+            return Err(e);
+        }
+    };
 
     // Phase 1: Plan — pure computation, no side effects.
     progress.set_message("Scanning references...");
-    let references = find_references(source, root, progress)?;
-    let mut replacements_by_file = plan_external_replacements(&references, &resolved_dest)?;
+    let mut scanned_hashes = FileHashes::new();
+    let references = find_references_with_hashes(source, root, progress, &mut scanned_hashes)?;
+    let vault_move = VaultMove::new(root, &source_canonical, &dest_canonical);
+    let mut replacements_by_file =
+        plan_external_replacements(&references, &resolved_dest, &vault_move)?;
     replacements_by_file.remove(source);
-    let internal_replacements = plan_internal_replacements(source, source, &resolved_dest)?;
+    let internal_replacements = plan_internal_replacements(
+        source,
+        source,
+        &resolved_dest,
+        &vault_move,
+        &mut scanned_hashes,
+    )?;
 
     if dry_run {
         add_destination_replacements(
@@ -261,33 +509,52 @@ fn mv_regular_file(
     if !internal_replacements.is_empty() && !replacements_by_file.contains_key(source) {
         transaction.snapshot_file(source)?;
     }
+    verify_snapshots(&transaction, &scanned_hashes)?;
+
+    add_destination_replacements(
+        &mut replacements_by_file,
+        &resolved_dest,
+        internal_replacements,
+    );
+    let moves = [(source.to_path_buf(), resolved_dest.clone())];
 
     // Ensure the parent directory of the destination exists.
     if let Some(parent) = resolved_dest.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let move_method = try_rename_regular_file(source, &resolved_dest)?;
+    let git_tracked = snapshot_git_index(git, source, &mut transaction)?;
+
+    // From here on every failure rolls back, which also removes the log.
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root,
+        "mv",
+        &moves,
+        &replacements_by_file,
+    )?);
+    rollback_on_error(&transaction, transaction.log_move(source, &resolved_dest))?;
+    let move_method = rollback_on_error(
+        &transaction,
+        match git_tracked {
+            Some(git) => git
+                .mv(source, &resolved_dest)
+                .map(|()| RegularFileMoveMethod::Renamed),
+            None => try_rename_regular_file(source, &resolved_dest).map_err(Into::into),
+        },
+    )?;
     match move_method {
-        RegularFileMoveMethod::Renamed => {
-            transaction.mark_renamed();
-            add_destination_replacements(
-                &mut replacements_by_file,
-                &resolved_dest,
-                internal_replacements,
-            );
-        }
+        RegularFileMoveMethod::Renamed => transaction.mark_renamed(),
         RegularFileMoveMethod::CopyAndDelete => {
-            fs::copy(source, &resolved_dest)?;
+            // Marked first so a partial copy is removed by the rollback.
             transaction.mark_copied();
-
-            if !internal_replacements.is_empty() {
-                transaction.snapshot_file(&resolved_dest)?;
-                add_destination_replacements(
-                    &mut replacements_by_file,
-                    &resolved_dest,
-                    internal_replacements,
-                );
+            rollback_on_error(
+                &transaction,
+                fs::copy(source, &resolved_dest).map_err(Into::into),
+            )?;
+
+            if replacements_by_file.contains_key(&resolved_dest) {
+                let snapshot = transaction.snapshot_file(&resolved_dest);
+                rollback_on_error(&transaction, snapshot.map_err(Into::into))?;
             }
         }
     }
@@ -316,6 +583,12 @@ fn mv_regular_file(
         transaction.mark_source_removed();
     }
 
+    if let Some(git) = git {
+        rollback_on_error(&transaction, git.stage(replacements_by_file.keys()))?;
+    }
+
+    transaction.commit()?;
+    record_operation(root, "mv", &moves, &transaction.file_snapshots);
     Ok(())
 }
 
@@ -323,11 +596,13 @@ fn mv_case_only_file(
     source: &Path,
     resolved_dest: &Path,
     root: &Path,
+    git: Option<&GitWorkTree>,
     dry_run: bool,
     progress: &dyn ProgressReporter,
 ) -> Result<()> {
     progress.set_message("Scanning references...");
-    let references = find_references(source, root, progress)?;
+    let mut scanned_hashes = FileHashes::new();
+    let references = find_references_with_hashes(source, root, progress, &mut scanned_hashes)?;
     let mut replacements_by_file =
         plan_case_only_external_replacements(&references, resolved_dest)?;
     move_source_replacements_to_destination(&mut replacements_by_file, source, resolved_dest);
@@ -348,8 +623,25 @@ fn mv_case_only_file(
     {
         transaction.snapshot_file(file_path)?;
     }
+    verify_snapshots(&transaction, &scanned_hashes)?;
+
+    let moves = [(source.to_path_buf(), resolved_dest.to_path_buf())];
+    let git_tracked = snapshot_git_index(git, source, &mut transaction)?;
 
-    fs::rename(source, resolved_dest)?;
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root,
+        "mv",
+        &moves,
+        &replacements_by_file,
+    )?);
+    rollback_on_error(&transaction, transaction.log_move(source, resolved_dest))?;
+    rollback_on_error(
+        &transaction,
+        match git_tracked {
+            Some(git) => git.mv(source, resolved_dest),
+            None => fs::rename(source, resolved_dest).map_err(Into::into),
+        },
+    )?;
     transaction.mark_renamed();
 
     execute_with_rollback(&transaction, || {
@@ -357,13 +649,22 @@ fn mv_case_only_file(
             apply_replacements(file_path, replacements)?;
         }
         Ok(())
-    })
+    })?;
+
+    if let Some(git) = git {
+        rollback_on_error(&transaction, git.stage(replacements_by_file.keys()))?;
+    }
+
+    transaction.commit()?;
+    record_operation(root, "mv", &moves, &transaction.file_snapshots);
+    Ok(())
 }
 
 fn mv_directory(
     source_dir: &Path,
     new_path: &Path,
     root: &Path,
+    git: Option<&GitWorkTree>,
     dry_run: bool,
     progress: &dyn ProgressReporter,
 ) -> Result<()> {
@@ -378,12 +679,14 @@ fn mv_directory(
             }
         };
 
+    let mut scanned_hashes = FileHashes::new();
     let (replacements_by_file, snapshot_paths) = plan_directory_replacements(
         source_dir,
         &source_canonical,
         &dest_canonical,
         root,
         progress,
+        &mut scanned_hashes,
     )?;
 
     if dry_run {
@@ -396,12 +699,33 @@ fn mv_directory(
     for snapshot_path in snapshot_paths {
         transaction.snapshot_file(&snapshot_path)?;
     }
+    verify_snapshots(&transaction, &scanned_hashes)?;
+
+    let moves = [(source_dir.to_path_buf(), resolved_dest.clone())];
 
     if let Some(parent) = resolved_dest.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(source_dir, &resolved_dest)?;
+    let git_tracked = snapshot_git_index(git, source_dir, &mut transaction)?;
+
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root,
+        "mv",
+        &moves,
+        &replacements_by_file,
+    )?);
+    rollback_on_error(
+        &transaction,
+        transaction.log_move(source_dir, &resolved_dest),
+    )?;
+    rollback_on_error(
+        &transaction,
+        match git_tracked {
+            Some(git) => git.mv(source_dir, &resolved_dest),
+            None => fs::rename(source_dir, &resolved_dest).map_err(Into::into),
+        },
+    )?;
     transaction.mark_renamed();
 
     execute_with_rollback(&transaction, || {
@@ -409,7 +733,29 @@ fn mv_directory(
             apply_replacements(file_path, replacements)?;
         }
         Ok(())
-    })
+    })?;
+
+    if let Some(git) = git {
+        rollback_on_error(&transaction, git.stage(replacements_by_file.keys()))?;
+    }
+
+    transaction.commit()?;
+    record_operation(root, "mv", &moves, &transaction.file_snapshots);
+    Ok(())
+}
+
+/// Snapshot the index of `git` before the first mutation, and return it
+/// when `source` should be moved with `git mv` because git tracks it.
+fn snapshot_git_index<'a>(
+    git: Option<&'a GitWorkTree>,
+    source: &Path,
+    transaction: &mut MoveTransaction,
+) -> Result<Option<&'a GitWorkTree>> {
+    let Some(git) = git else {
+        return Ok(None);
+    };
+    transaction.snapshot_git_index(git)?;
+    Ok(git.is_tracked(source)?.then_some(git))
 }
 
 #[cfg(test)]
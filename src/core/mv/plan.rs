@@ -8,9 +8,11 @@
 //! The module is internally organized into three sub-groups:
 //!
 //! - top-level planners: `plan_external_replacements`, `plan_internal_replacements`,
-//!   `plan_directory_replacements`
+//!   `plan_directory_replacements`, `plan_batch_replacements`, `plan_copy_replacements`,
+//!   `plan_replayed_replacements`
 //! - per-reference construction: `build_link_replacement`, `build_replacement`,
-//!   `build_reference_definition_replacement`, `split_link_and_anchor`
+//!   `build_reference_definition_replacement`, `build_wikilink_move_replacement`,
+//!   `split_link_and_anchor`
 //! - `ReplacementPlan` bookkeeping: `extend_unique_replacements`,
 //!   `move_source_replacements_to_destination`, `add_destination_replacements`
 
@@ -22,23 +24,29 @@ use std::{
 
 use walkdir::WalkDir;
 
+use super::validate::BatchMove;
 use crate::{
     LinkType, MdrefError, Reference, Result,
     core::{
-        find::find_references,
+        config::{Config, SpaceStyle, is_markdown},
+        editor::Overlay,
+        find::{
+            FileHashes, collect_local_links, find_links, find_links_with_hash,
+            find_references_matching,
+        },
         model::LinkReplacement,
         progress::ProgressReporter,
         util::{
-            collect_markdown_files, is_external_url, relative_path, strip_utf8_bom_prefix,
-            url_decode_link,
+            collect_markdown_files, is_external_url, post_move_path, pre_move_path, relative_path,
+            strip_utf8_bom_prefix, url_decode_link,
         },
+        wikilink::{VaultCopy, VaultMove, absolute, find_wikilink_target_span, normalize},
     },
-    find_links,
 };
 
-pub(super) type ReplacementPlan = HashMap<PathBuf, Vec<LinkReplacement>>;
+pub(crate) type ReplacementPlan = HashMap<PathBuf, Vec<LinkReplacement>>;
 pub(super) type SnapshotPaths = Vec<PathBuf>;
-pub(super) type LineCache = HashMap<PathBuf, Vec<String>>;
+pub(crate) type LineCache = HashMap<PathBuf, Vec<String>>;
 
 // ============= Top-level planners =============
 
@@ -46,11 +54,24 @@ pub(super) type LineCache = HashMap<PathBuf, Vec<String>>;
 pub(super) fn plan_external_replacements(
     references: &[Reference],
     resolved_dest: &Path,
+    vault_move: &VaultMove,
 ) -> Result<ReplacementPlan> {
     let mut replacements_by_file: ReplacementPlan = HashMap::new();
     let mut line_cache = LineCache::new();
 
     for reference in references {
+        if reference.link_type == LinkType::WikiLink {
+            if let Some(replacement) =
+                build_wikilink_move_replacement(reference, vault_move, &mut line_cache)?
+            {
+                replacements_by_file
+                    .entry(reference.path.clone())
+                    .or_default()
+                    .push(replacement);
+            }
+            continue;
+        }
+
         let (_link_path_only, anchor) = split_link_and_anchor(&reference.link_text);
         let new_link_path = relative_path(&reference.path, resolved_dest)?;
 
@@ -73,19 +94,29 @@ pub(super) fn plan_external_replacements(
 }
 
 /// Collect all link replacements needed for internal links within the moved file itself.
+///
+/// The content hash of `scan_path` is recorded in `hashes`.
 pub(super) fn plan_internal_replacements(
     scan_path: &Path,
     source: &Path,
     resolved_dest: &Path,
+    vault_move: &VaultMove,
+    hashes: &mut FileHashes,
 ) -> Result<Vec<LinkReplacement>> {
-    let links = find_links(scan_path)?;
+    if !is_markdown(scan_path) {
+        return Ok(Vec::new());
+    }
+    let links = find_links_with_hash(scan_path, hashes)?;
     let mut replacements = Vec::new();
     let mut line_cache = LineCache::new();
 
     for link in &links {
-        if let Some(replacement) =
+        let replacement = if link.link_type == LinkType::WikiLink {
+            build_wikilink_move_replacement(link, vault_move, &mut line_cache)?
+        } else {
             build_link_replacement(link, source, resolved_dest, &mut line_cache)?
-        {
+        };
+        if let Some(replacement) = replacement {
             replacements.push(replacement);
         }
     }
@@ -97,22 +128,90 @@ pub(super) fn plan_internal_replacements(
 ///
 /// Returns `(plan, snapshot_paths)`: the plan is keyed by the files'
 /// **post-move** paths, while `snapshot_paths` lists the **pre-move** paths
-/// that need to be snapshotted for rollback.
+/// that need to be snapshotted for rollback. The content hash of every
+/// scanned file is recorded in `hashes`, keyed by its pre-move path.
 pub(super) fn plan_directory_replacements(
     source_dir: &Path,
     source_canonical: &Path,
     dest_canonical: &Path,
     root: &Path,
     progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
 ) -> Result<(ReplacementPlan, SnapshotPaths)> {
-    let path_mappings =
-        build_directory_path_mappings(source_dir, source_canonical, dest_canonical)?;
+    let directory_move = BatchMove {
+        source: source_dir.to_path_buf(),
+        resolved_dest: dest_canonical.to_path_buf(),
+        source_canonical: source_canonical.to_path_buf(),
+        dest_canonical: dest_canonical.to_path_buf(),
+    };
+    plan_batch_replacements(
+        std::slice::from_ref(&directory_move),
+        root,
+        progress,
+        hashes,
+        &Overlay::new(),
+    )
+}
+
+/// Plan replacements for several files and directories moving at once, with
+/// a single scan of `root`.
+///
+/// Like [`plan_directory_replacements`], the plan is keyed by post-move paths
+/// and the snapshot paths are pre-move paths. Links whose source file and
+/// target both move are rewritten relative to their new locations. Files in
+/// `overlay` are planned from their overlaid contents instead of from disk.
+pub(super) fn plan_batch_replacements(
+    moves: &[BatchMove],
+    root: &Path,
+    progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
+    overlay: &Overlay,
+) -> Result<(ReplacementPlan, SnapshotPaths)> {
+    let mut path_mappings = HashMap::new();
+    for entry in moves {
+        path_mappings.extend(build_directory_path_mappings(
+            &entry.source,
+            &entry.source_canonical,
+            &entry.dest_canonical,
+        )?);
+    }
     let mut replacements_by_file: ReplacementPlan = HashMap::new();
     let mut snapshot_paths: HashSet<PathBuf> = HashSet::new();
     let mut line_cache = LineCache::new();
+    let vault_move = VaultMove::new_many(
+        root,
+        moves
+            .iter()
+            .map(|entry| (entry.source_canonical.clone(), entry.dest_canonical.clone()))
+            .collect(),
+    );
+    let is_moved = |path: &Path| {
+        moves
+            .iter()
+            .any(|entry| path.starts_with(&entry.source_canonical))
+    };
 
     progress.set_message("Scanning references...");
-    for reference in find_references(source_dir, root, progress)? {
+    let sources: Vec<PathBuf> = moves
+        .iter()
+        .map(|entry| entry.source_canonical.clone())
+        .collect();
+    for (reference, _) in find_references_matching(&sources, root, progress, hashes, overlay)? {
+        cache_overlaid_lines(&reference.path, overlay, &mut line_cache);
+        if reference.link_type == LinkType::WikiLink {
+            if let Some(replacement) =
+                build_wikilink_move_replacement(&reference, &vault_move, &mut line_cache)?
+            {
+                let file_after_move = remap_existing_path(&reference.path, moves, &path_mappings)?;
+                replacements_by_file
+                    .entry(file_after_move)
+                    .or_default()
+                    .push(replacement);
+                snapshot_paths.insert(reference.path);
+            }
+            continue;
+        }
+
         let (link_path_only, _) = split_link_and_anchor(&reference.link_text);
         let Some(old_target) = resolve_reference_target(&reference.path, link_path_only) else {
             continue;
@@ -121,8 +220,7 @@ pub(super) fn plan_directory_replacements(
             continue;
         };
 
-        let file_after_move =
-            remap_existing_path(&reference.path, source_canonical, &path_mappings)?;
+        let file_after_move = remap_existing_path(&reference.path, moves, &path_mappings)?;
         let replacement = build_replacement_for_target(
             &reference,
             &file_after_move,
@@ -137,36 +235,210 @@ pub(super) fn plan_directory_replacements(
         snapshot_paths.insert(reference.path);
     }
 
-    for markdown_file in collect_markdown_files(source_dir) {
-        let file_after_move =
-            remap_existing_path(&markdown_file, source_canonical, &path_mappings)?;
-        let links = find_links(&markdown_file)?;
+    for entry in moves {
+        for markdown_file in collect_markdown_files(&entry.source) {
+            let file_after_move = remap_existing_path(&markdown_file, moves, &path_mappings)?;
+            let links = match overlay.get(&absolute(&markdown_file)) {
+                Some(content) => collect_local_links(content, &markdown_file),
+                None => find_links_with_hash(&markdown_file, hashes)?,
+            };
+            cache_overlaid_lines(&markdown_file, overlay, &mut line_cache);
+
+            for link in links {
+                if link.link_type == LinkType::WikiLink {
+                    // Links to moved files were planned above.
+                    if let Some(replacement) =
+                        build_wikilink_move_replacement(&link, &vault_move, &mut line_cache)?
+                        && !replacements_by_file
+                            .get(&file_after_move)
+                            .is_some_and(|existing| existing.contains(&replacement))
+                    {
+                        replacements_by_file
+                            .entry(file_after_move.clone())
+                            .or_default()
+                            .push(replacement);
+                        snapshot_paths.insert(markdown_file.clone());
+                    }
+                    continue;
+                }
+
+                let (link_path_only, _) = split_link_and_anchor(&link.link_text);
+                let Some(target_path) = resolve_reference_target(&markdown_file, link_path_only)
+                else {
+                    continue;
+                };
+
+                if is_moved(&target_path) {
+                    continue;
+                }
+
+                let replacement = build_replacement_for_target(
+                    &link,
+                    &file_after_move,
+                    &target_path,
+                    &mut line_cache,
+                )?;
+                if replacement.old_pattern == replacement.new_pattern {
+                    continue;
+                }
+                replacements_by_file
+                    .entry(file_after_move.clone())
+                    .or_default()
+                    .push(replacement);
+                snapshot_paths.insert(markdown_file.clone());
+            }
+        }
+    }
+
+    Ok((replacements_by_file, snapshot_paths.into_iter().collect()))
+}
+
+/// Plan the link rewrites inside a copy of `source` placed at
+/// `dest_canonical`, without touching any other file.
+///
+/// The plan is keyed by the copies' paths under `dest_canonical`. Links to
+/// files outside the copied tree are rewritten relative to the copy's
+/// location; links between copied files point at the copy when
+/// `to_copy` is set and at the original otherwise. Wikilinks resolve through
+/// the vault under `root` and are only rewritten when the copy would change
+/// the file they resolve to.
+pub(crate) fn plan_copy_replacements(
+    source: &Path,
+    source_canonical: &Path,
+    dest_canonical: &Path,
+    root: &Path,
+    to_copy: bool,
+) -> Result<ReplacementPlan> {
+    let vault_copy = VaultCopy::new(root, source_canonical, dest_canonical);
+    let mut path_mappings =
+        HashMap::from([(source_canonical.to_path_buf(), dest_canonical.to_path_buf())]);
+    let markdown_files = if source.is_dir() {
+        path_mappings.extend(build_directory_path_mappings(
+            source,
+            source_canonical,
+            dest_canonical,
+        )?);
+        collect_markdown_files(source)
+    } else {
+        vec![source.to_path_buf()]
+    };
+
+    let mut replacements_by_file: ReplacementPlan = HashMap::new();
+    let mut line_cache = LineCache::new();
+    for markdown_file in markdown_files {
+        let canonical = markdown_file
+            .canonicalize()
+            .map_err(|e| MdrefError::PathValidation {
+                path: markdown_file.clone(),
+                details: format!("cannot canonicalize path: {e}"),
+            })?;
+        let Some(copy_path) = path_mappings.get(&canonical) else {
+            continue;
+        };
+
+        for link in find_links(&markdown_file)? {
+            let (link_path_only, anchor) = split_link_and_anchor(&link.link_text);
+            if link.link_type == LinkType::WikiLink {
+                let Some(new_target) = vault_copy.rewrite(&canonical, link_path_only, to_copy)
+                else {
+                    continue;
+                };
+                let new_target_with_anchor = match anchor {
+                    Some(a) => format!("{new_target}#{a}"),
+                    None => new_target,
+                };
+                replacements_by_file
+                    .entry(copy_path.clone())
+                    .or_default()
+                    .push(build_replacement(
+                        &link,
+                        &new_target_with_anchor,
+                        &mut line_cache,
+                    )?);
+                continue;
+            }
 
-        for link in links {
-            let (link_path_only, _) = split_link_and_anchor(&link.link_text);
             let Some(target_path) = resolve_reference_target(&markdown_file, link_path_only) else {
                 continue;
             };
+            let target_path = match path_mappings.get(&target_path) {
+                Some(copied_target) if to_copy => copied_target.clone(),
+                _ => target_path,
+            };
 
-            if target_path.starts_with(source_canonical) {
+            let replacement =
+                build_replacement_for_target(&link, copy_path, &target_path, &mut line_cache)?;
+            if replacement.old_pattern == replacement.new_pattern {
                 continue;
             }
+            replacements_by_file
+                .entry(copy_path.clone())
+                .or_default()
+                .push(replacement);
+        }
+    }
+
+    Ok(replacements_by_file)
+}
+
+/// Plan the link rewrites for `moves` that were already made on disk, such as
+/// renames done with `git mv`, as if `mv` had made them.
+///
+/// `moves` are absolute `(source, destination)` pairs. Every Markdown file
+/// under `root` is scanned where it is now, but its links are resolved from
+/// where it was before the moves. A link is rewritten when its target or the
+/// file itself moved, unless it already resolves to the new target or that
+/// target does not exist. The plan is keyed by the scanned paths, and the
+/// content hash of every scanned file is recorded in `hashes`.
+pub(crate) fn plan_replayed_replacements(
+    moves: &[(PathBuf, PathBuf)],
+    root: &Path,
+    progress: &dyn ProgressReporter,
+    hashes: &mut FileHashes,
+) -> Result<ReplacementPlan> {
+    let vault_move = VaultMove::already_moved(root, moves.to_vec());
+    let markdown_files = collect_markdown_files(root);
+    progress.set_total(markdown_files.len() as u64);
+
+    let mut replacements_by_file: ReplacementPlan = HashMap::new();
+    let mut line_cache = LineCache::new();
+    for markdown_file in markdown_files {
+        let current = absolute(&markdown_file);
+        let before = pre_move_path(moves, &current);
+
+        for link in find_links_with_hash(&markdown_file, hashes)? {
+            let (link_path_only, anchor) = split_link_and_anchor(&link.link_text);
+            let replacement = if link.link_type == LinkType::WikiLink {
+                let Some(new_target) = vault_move.rewrite(&before, link_path_only) else {
+                    continue;
+                };
+                let new_target_with_anchor = match anchor {
+                    Some(a) => format!("{new_target}#{a}"),
+                    None => new_target,
+                };
+                build_replacement(&link, &new_target_with_anchor, &mut line_cache)?
+            } else {
+                let Some(old_target) = lexical_target(&before, link_path_only) else {
+                    continue;
+                };
+                let new_target = post_move_path(moves, &old_target);
+                if !new_target.exists()
+                    || lexical_target(&current, link_path_only).as_ref() == Some(&new_target)
+                {
+                    continue;
+                }
+                build_replacement_for_target(&link, &current, &new_target, &mut line_cache)?
+            };
 
-            let replacement = build_replacement_for_target(
-                &link,
-                &file_after_move,
-                &target_path,
-                &mut line_cache,
-            )?;
             replacements_by_file
-                .entry(file_after_move.clone())
+                .entry(markdown_file.clone())
                 .or_default()
                 .push(replacement);
-            snapshot_paths.insert(markdown_file.clone());
         }
+        progress.inc(1);
     }
 
-    Ok((replacements_by_file, snapshot_paths.into_iter().collect()))
+    Ok(replacements_by_file)
 }
 
 // ============= Directory-move internals =============
@@ -225,9 +497,25 @@ fn resolve_reference_target(base_file: &Path, link_path_only: &str) -> Option<Pa
     resolved.canonicalize().ok()
 }
 
+/// Lexically resolve a local link written in `base_file`, whether or not its
+/// target exists. Returns `None` for external URLs and pure anchors.
+fn lexical_target(base_file: &Path, link_path_only: &str) -> Option<PathBuf> {
+    if link_path_only.is_empty() || is_external_url(link_path_only) {
+        return None;
+    }
+
+    let decoded_link = url_decode_link(link_path_only);
+    let decoded_path = Path::new(&decoded_link);
+    if decoded_path.is_absolute() {
+        Some(normalize(decoded_path))
+    } else {
+        Some(normalize(&base_file.parent()?.join(decoded_path)))
+    }
+}
+
 fn remap_existing_path(
     path: &Path,
-    source_canonical: &Path,
+    moves: &[BatchMove],
     path_mappings: &HashMap<PathBuf, PathBuf>,
 ) -> Result<PathBuf> {
     let canonical = path
@@ -237,7 +525,10 @@ fn remap_existing_path(
             details: format!("cannot canonicalize path: {e}"),
         })?;
 
-    if canonical.starts_with(source_canonical) {
+    if moves
+        .iter()
+        .any(|entry| canonical.starts_with(&entry.source_canonical))
+    {
         path_mappings
             .get(&canonical)
             .cloned()
@@ -275,7 +566,7 @@ fn build_replacement_for_target(
 ///   "file.md#section" -> ("file.md", Some("section"))
 ///   "file.md" -> ("file.md", None)
 ///   "#section" -> ("", Some("section"))  (pure anchor link)
-pub(super) fn split_link_and_anchor(link: &str) -> (&str, Option<&str>) {
+pub(crate) fn split_link_and_anchor(link: &str) -> (&str, Option<&str>) {
     match link.find('#') {
         Some(pos) => {
             let (path, anchor) = link.split_at(pos);
@@ -369,7 +660,7 @@ pub(super) fn build_link_replacement(
     )?))
 }
 
-pub(super) fn build_replacement(
+pub(crate) fn build_replacement(
     reference: &Reference,
     new_url: &str,
     line_cache: &mut LineCache,
@@ -379,14 +670,114 @@ pub(super) fn build_replacement(
             line: reference.line,
             column: reference.column,
             old_pattern: format!("]({})", reference.link_text),
-            new_pattern: format!("]({})", new_url),
+            new_pattern: format!("]({})", link_destination(&reference.path, new_url)),
         }),
         LinkType::ReferenceDefinition => {
             build_reference_definition_replacement(reference, new_url, line_cache)
         }
+        LinkType::WikiLink => build_wikilink_replacement(reference, new_url, line_cache),
+        LinkType::HtmlAttribute => build_html_attribute_replacement(reference, new_url, line_cache),
+        LinkType::Import => Ok(LinkReplacement {
+            line: reference.line,
+            column: reference.column,
+            old_pattern: reference.link_text.clone(),
+            new_pattern: import_specifier(new_url),
+        }),
     }
 }
 
+/// `url` as an import path. Bundlers read a path that does not start with
+/// `./` or `../` as a package name.
+fn import_specifier(url: &str) -> String {
+    if url.starts_with("./") || url.starts_with("../") {
+        url.to_string()
+    } else {
+        format!("./{url}")
+    }
+}
+
+/// Build the replacement for a wikilink affected by a move, keeping its
+/// `#fragment`. Returns `None` if the link still resolves after the move.
+fn build_wikilink_move_replacement(
+    reference: &Reference,
+    vault_move: &VaultMove,
+    line_cache: &mut LineCache,
+) -> Result<Option<LinkReplacement>> {
+    let (link_path_only, anchor) = split_link_and_anchor(&reference.link_text);
+    let Some(new_target) = vault_move.rewrite(&reference.path, link_path_only) else {
+        return Ok(None);
+    };
+
+    let new_target_with_anchor = match anchor {
+        Some(a) => format!("{new_target}#{a}"),
+        None => new_target,
+    };
+
+    Ok(Some(build_replacement(
+        reference,
+        &new_target_with_anchor,
+        line_cache,
+    )?))
+}
+
+fn build_wikilink_replacement(
+    reference: &Reference,
+    new_target: &str,
+    line_cache: &mut LineCache,
+) -> Result<LinkReplacement> {
+    let line = get_cached_line(&reference.path, reference.line, line_cache)?;
+    let (target_start, target_end) =
+        find_wikilink_target_span(line, reference.column).ok_or_else(|| {
+            MdrefError::PathValidation {
+                path: reference.path.clone(),
+                details: format!("could not parse wikilink in line {}", reference.line),
+            }
+        })?;
+
+    Ok(LinkReplacement {
+        line: reference.line,
+        column: target_start + 1,
+        old_pattern: line[target_start..target_end].to_string(),
+        new_pattern: new_target.to_string(),
+    })
+}
+
+/// Build the replacement for a URL in a raw HTML attribute.
+///
+/// The reference column points at the URL itself, so only the URL is replaced
+/// and the attribute keeps its quoting style. Characters that would end the
+/// value early (whitespace, the enclosing quote) are percent-encoded.
+fn build_html_attribute_replacement(
+    reference: &Reference,
+    new_url: &str,
+    line_cache: &mut LineCache,
+) -> Result<LinkReplacement> {
+    let line = get_cached_line(&reference.path, reference.line, line_cache)?;
+    let quote = line[..reference.column.saturating_sub(1).min(line.len())]
+        .trim_end_matches(|c: char| c != '"' && c != '\'' && c != '=')
+        .chars()
+        .next_back()
+        .filter(|c| *c != '=');
+
+    let mut escaped = String::with_capacity(new_url.len());
+    for c in new_url.chars() {
+        match c {
+            ' ' => escaped.push_str("%20"),
+            '"' if quote != Some('\'') => escaped.push_str("%22"),
+            '\'' if quote != Some('"') => escaped.push_str("%27"),
+            '>' if quote.is_none() => escaped.push_str("%3E"),
+            _ => escaped.push(c),
+        }
+    }
+
+    Ok(LinkReplacement {
+        line: reference.line,
+        column: reference.column,
+        old_pattern: reference.link_text.clone(),
+        new_pattern: escaped,
+    })
+}
+
 fn build_reference_definition_replacement(
     reference: &Reference,
     new_url: &str,
@@ -402,14 +793,42 @@ fn build_reference_definition_replacement(
             ),
         })?;
 
+    // Inside `<...>` a destination may contain spaces as they are.
+    let bracketed = line[..url_start].ends_with('<');
     Ok(LinkReplacement {
         line: reference.line,
         column: url_start + 1,
         old_pattern: line[url_start..url_end].to_string(),
-        new_pattern: new_url.to_string(),
+        new_pattern: if bracketed {
+            new_url.to_string()
+        } else {
+            link_destination(&reference.path, new_url)
+        },
     })
 }
 
+/// Write `url` as the destination of a Markdown link in `file`. A URL with
+/// spaces is escaped the way the `[links]` settings governing `file` prefer,
+/// since a bare space would end the destination.
+fn link_destination(file: &Path, url: &str) -> String {
+    if !url.contains(' ') {
+        return url.to_string();
+    }
+    match Config::for_path(file).1.links.spaces {
+        SpaceStyle::Percent => url.replace(' ', "%20"),
+        SpaceStyle::Angle => format!("<{url}>"),
+    }
+}
+
+/// Seed `line_cache` with the overlaid lines of `path`, if it is overlaid.
+fn cache_overlaid_lines(path: &Path, overlay: &Overlay, line_cache: &mut LineCache) {
+    if let Some(content) = overlay.get(&absolute(path)) {
+        line_cache
+            .entry(path.to_path_buf())
+            .or_insert_with(|| content.lines().map(|line| line.to_string()).collect());
+    }
+}
+
 fn get_cached_line<'a>(
     path: &Path,
     line_number: usize,
@@ -440,14 +859,16 @@ fn get_cached_line<'a>(
 /// Locate the URL span inside a Markdown reference definition line.
 ///
 /// Returns `(url_start, url_end)` as byte offsets into `line`, or `None` if the
-/// line is not a valid reference definition (more than 3 leading spaces, no
-/// `[label]:` prefix, empty URL, etc.). Angle-bracket-wrapped URLs have the
-/// brackets excluded from the span.
+/// line is not a valid reference definition (no `[label]:` prefix, empty URL,
+/// etc.). Angle-bracket-wrapped URLs have the brackets excluded from the span.
+///
+/// Indentation is not checked: the line comes from a definition the scan
+/// found, and MDX documents may indent definitions inside components.
 pub(super) fn find_reference_definition_url_span(line: &str) -> Option<(usize, usize)> {
     let (line_without_bom, bom_offset) = strip_utf8_bom_prefix(line);
     let trimmed = line_without_bom.trim_start();
     let leading_spaces = line_without_bom.len() - trimmed.len();
-    if leading_spaces > 3 || !trimmed.starts_with('[') {
+    if !trimmed.starts_with('[') {
         return None;
     }
 
@@ -2,14 +2,16 @@
 //!
 //! Pure transformations from a [`ReplacementPlan`] into user-facing shapes:
 //!
-//! - [`build_move_preview`]: assemble the structured [`MovePreview`] returned by
-//!   the public `preview_move` API.
-//! - [`print_dry_run_report`]: human-readable stdout report used when the CLI
-//!   runs with `--dry-run`.
+//! - [`build_move_preview`] / [`build_batch_move_preview`]: assemble the
+//!   structured [`MovePreview`] returned by `preview_move` and `preview_move_many`.
+//! - [`build_move_changes`]: sort a plan into [`MoveChange`]s, shared with
+//!   other in-place rewrites such as heading renames.
+//! - [`print_dry_run_report`] / [`print_move_changes`]: human-readable stdout
+//!   report used when the library runs with `dry_run`.
 
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
-use super::plan::ReplacementPlan;
+use super::{plan::ReplacementPlan, validate::BatchMove};
 use crate::core::model::{MoveChange, MoveChangeKind, MovePreview};
 
 pub(super) fn build_move_preview(
@@ -17,6 +19,63 @@ pub(super) fn build_move_preview(
     destination: &Path,
     replacements_by_file: ReplacementPlan,
 ) -> MovePreview {
+    let changes = build_move_changes(replacements_by_file, |path| {
+        if path == destination {
+            MoveChangeKind::MovedFileUpdate
+        } else {
+            MoveChangeKind::ReferenceUpdate
+        }
+    });
+
+    MovePreview {
+        source: source.to_path_buf(),
+        destination: destination.to_path_buf(),
+        moves: vec![(source.to_path_buf(), destination.to_path_buf())],
+        changes,
+        file_hashes: BTreeMap::new(),
+    }
+}
+
+/// Like [`build_move_preview`], for a batch. `source` and `destination` hold
+/// the first pair; files inside any destination are labelled as moved files.
+pub(super) fn build_batch_move_preview(
+    moves: &[BatchMove],
+    replacements_by_file: ReplacementPlan,
+) -> MovePreview {
+    let changes = build_move_changes(replacements_by_file, |path| {
+        if moves
+            .iter()
+            .any(|entry| path.starts_with(&entry.dest_canonical))
+        {
+            MoveChangeKind::MovedFileUpdate
+        } else {
+            MoveChangeKind::ReferenceUpdate
+        }
+    });
+    let pairs: Vec<_> = moves
+        .iter()
+        .map(|entry| (entry.source.clone(), entry.resolved_dest.clone()))
+        .collect();
+    let (source, destination) = pairs.first().cloned().unwrap_or_default();
+
+    MovePreview {
+        source,
+        destination,
+        moves: pairs,
+        changes,
+        file_hashes: BTreeMap::new(),
+    }
+}
+
+/// Turn a replacement plan into [`MoveChange`]s sorted by path, with each
+/// file's replacements sorted by position. `kind_for` labels each file.
+pub(crate) fn build_move_changes<F>(
+    replacements_by_file: ReplacementPlan,
+    kind_for: F,
+) -> Vec<MoveChange>
+where
+    F: Fn(&Path) -> MoveChangeKind,
+{
     let mut changes = replacements_by_file
         .into_iter()
         .map(|(path, mut replacements)| {
@@ -28,46 +87,44 @@ pub(super) fn build_move_preview(
                     .then(left.new_pattern.cmp(&right.new_pattern))
             });
 
-            let kind = if path == destination {
-                MoveChangeKind::MovedFileUpdate
-            } else {
-                MoveChangeKind::ReferenceUpdate
-            };
-
             MoveChange {
+                kind: kind_for(&path),
                 path,
-                kind,
                 replacements,
             }
         })
         .collect::<Vec<_>>();
 
     changes.sort_by(|left, right| left.path.cmp(&right.path));
-
-    MovePreview {
-        source: source.to_path_buf(),
-        destination: destination.to_path_buf(),
-        changes,
-    }
+    changes
 }
 
 /// Print a human-readable report of all changes that would be made during a move operation.
 pub(super) fn print_dry_run_report(preview: &MovePreview) {
-    println!(
-        "[dry-run] Would move: {} -> {}",
-        preview.source.display(),
-        preview.destination.display()
-    );
+    for (source, destination) in &preview.moves {
+        println!(
+            "[dry-run] Would move: {} -> {}",
+            source.display(),
+            destination.display()
+        );
+    }
+
+    print_move_changes(&preview.changes);
+}
 
-    if preview.changes.is_empty() {
+/// Print each planned file rewrite, one `Line N: old -> new` entry per replacement.
+pub(crate) fn print_move_changes(changes: &[MoveChange]) {
+    if changes.is_empty() {
         println!("[dry-run] No references to update.");
         return;
     }
 
-    for change in &preview.changes {
+    for change in changes {
         let label = match change.kind {
             MoveChangeKind::MovedFileUpdate => "Would update links in moved file",
             MoveChangeKind::ReferenceUpdate => "Would update reference in",
+            MoveChangeKind::HeadingUpdate => "Would update heading in",
+            MoveChangeKind::CopiedFileUpdate => "Would update links in copy",
         };
         println!("[dry-run] {} {}:", label, change.path.display());
         for replacement in &change.replacements {
@@ -0,0 +1,147 @@
+//! Execute a previously computed [`MovePreview`] exactly as planned.
+//!
+//! - [`record_file_hashes`]: fill [`MovePreview::file_hashes`] when a preview
+//!   is built for later application.
+//! - [`apply_preview`]: verify those hashes, then perform the recorded moves
+//!   and replacements under one rollback-protected transaction and record
+//!   them in the undo journal.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    apply::{apply_replacements, execute_with_rollback, move_paths, rollback_on_error},
+    plan::ReplacementPlan,
+};
+use crate::{
+    MdrefError, Result,
+    core::{
+        config::is_markdown,
+        journal::record_operation,
+        model::{MovePreview, MoveTransaction},
+        recover::WriteAheadLog,
+        util::{collect_markdown_files, content_hash, pre_move_path, resolve_path},
+        wikilink::absolute,
+    },
+};
+
+/// Hash every file the preview rewrites or carries along.
+pub(super) fn record_file_hashes(preview: &mut MovePreview) -> Result<()> {
+    let mut hashes = BTreeMap::new();
+    for path in touched_files(preview)? {
+        hashes.insert(path.clone(), hash_file(&path)?);
+    }
+    preview.file_hashes = hashes;
+    Ok(())
+}
+
+/// Apply `preview` after checking that no touched file changed since it was
+/// computed.
+pub(super) fn apply_preview(preview: &MovePreview, root: &Path) -> Result<()> {
+    let touched = touched_files(preview)?;
+    if let Some(missing) = touched.iter().find(|path| !path.exists()) {
+        return Err(MdrefError::PathValidation {
+            path: missing.clone(),
+            details: "file in the plan no longer exists".to_string(),
+        });
+    }
+    let changed: Vec<PathBuf> = touched
+        .into_iter()
+        .filter(|path| {
+            let actual = hash_file(path).ok();
+            actual.is_none() || preview.file_hashes.get(path) != actual.as_ref()
+        })
+        .collect();
+    if !changed.is_empty() {
+        return Err(MdrefError::ConcurrentModification { paths: changed });
+    }
+
+    let moves = resolved_moves(preview)?;
+    let mut transaction = MoveTransaction::for_batch();
+    for change in &preview.changes {
+        transaction.snapshot_file(&pre_move_path(&moves, &resolve(&change.path)?))?;
+    }
+    let plan: ReplacementPlan = preview
+        .changes
+        .iter()
+        .map(|change| (change.path.clone(), change.replacements.clone()))
+        .collect();
+    transaction.attach_log(WriteAheadLog::for_plan(root, "apply", &moves, &plan)?);
+
+    let moved = move_paths(&moves, &mut transaction);
+    rollback_on_error(&transaction, moved)?;
+
+    execute_with_rollback(&transaction, || {
+        for change in &preview.changes {
+            apply_replacements(&change.path, &change.replacements)?;
+        }
+        Ok(())
+    })?;
+
+    transaction.commit()?;
+    record_operation(root, "apply", &moves, &transaction.file_snapshots);
+    Ok(())
+}
+
+/// Absolute pre-move paths of every rewritten file and every moved Markdown
+/// file, sorted and deduplicated.
+fn touched_files(preview: &MovePreview) -> Result<Vec<PathBuf>> {
+    let moves = resolved_moves(preview)?;
+    let mut touched = Vec::new();
+
+    for (source, _) in &moves {
+        if source.is_dir() {
+            for path in collect_markdown_files(source) {
+                touched.push(resolve(&path)?);
+            }
+        } else if is_markdown(source) {
+            touched.push(source.clone());
+        }
+    }
+    for change in &preview.changes {
+        touched.push(pre_move_path(&moves, &resolve(&change.path)?));
+    }
+
+    touched.sort();
+    touched.dedup();
+    Ok(touched)
+}
+
+/// The preview's moves with both sides resolved to absolute paths; no-op
+/// moves are dropped.
+///
+/// Only the destination's parent is resolved, so the file name keeps the
+/// requested spelling for case-only renames.
+fn resolved_moves(preview: &MovePreview) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut moves = Vec::new();
+    for (source, destination) in &preview.moves {
+        if source == destination {
+            continue;
+        }
+        let source = resolve(source)?;
+        let destination = match (destination.parent(), destination.file_name()) {
+            (Some(parent), Some(name)) if parent.as_os_str().is_empty() => {
+                resolve(Path::new("."))?.join(name)
+            }
+            (Some(parent), Some(name)) => resolve(parent)?.join(name),
+            _ => resolve(destination)?,
+        };
+        moves.push((source, destination));
+    }
+    Ok(moves)
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(content_hash(&bytes))
+}
+
+fn resolve(path: &Path) -> Result<PathBuf> {
+    resolve_path(&absolute(path))
+}
@@ -4,9 +4,22 @@
 //! directory" case) and reject invalid moves early, before any planning or
 //! filesystem mutation happens.
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
-use crate::{MdrefError, Result};
+use super::case_only::resolve_case_only_destination;
+use crate::{MdrefError, Result, core::wikilink::absolute};
+
+/// One validated source → destination pair of a batch move.
+#[derive(Debug, Clone)]
+pub(super) struct BatchMove {
+    pub(super) source: PathBuf,
+    pub(super) resolved_dest: PathBuf,
+    pub(super) source_canonical: PathBuf,
+    pub(super) dest_canonical: PathBuf,
+}
 
 /// Resolve the destination path, handling the case where the destination is an existing directory.
 pub(super) fn resolve_destination(source: &Path, destination: &Path) -> Result<PathBuf> {
@@ -64,9 +77,26 @@ pub(super) fn canonicalize_destination(destination: &Path) -> Result<PathBuf> {
 
 /// Validate that the move operation is valid: source exists, destination doesn't collide, etc.
 /// Returns `(resolved_dest, source_canonical, dest_canonical)`.
-pub(super) fn validate_move_paths(
+pub(crate) fn validate_move_paths(
+    source: &Path,
+    destination: &Path,
+) -> Result<(PathBuf, PathBuf, PathBuf)> {
+    validate_paths(source, destination, "move")
+}
+
+/// Validate a copy the way [`validate_move_paths`] validates a move.
+pub(crate) fn validate_copy_paths(
+    source: &Path,
+    destination: &Path,
+) -> Result<(PathBuf, PathBuf, PathBuf)> {
+    validate_paths(source, destination, "copy")
+}
+
+/// The checks shared by moves and copies; `operation` names the one in errors.
+fn validate_paths(
     source: &Path,
     destination: &Path,
+    operation: &str,
 ) -> Result<(PathBuf, PathBuf, PathBuf)> {
     if !source.exists() {
         return Err(MdrefError::PathValidation {
@@ -102,9 +132,134 @@ pub(super) fn validate_move_paths(
     if source_canonical.is_dir() && dest_canonical.starts_with(&source_canonical) {
         return Err(MdrefError::PathValidation {
             path: source.to_path_buf(),
-            details: "cannot move directory into itself or one of its subdirectories".to_string(),
+            details: format!(
+                "cannot {operation} directory into itself or one of its subdirectories"
+            ),
         });
     }
 
     Ok((resolved_dest, source_canonical, dest_canonical))
 }
+
+/// Validate every pair of a batch move up front, before any planning.
+///
+/// Pairs whose source and destination are the same file are dropped. Chains
+/// (`a -> b`, `b -> c`) and swaps (`a -> b`, `b -> a`) are allowed because a
+/// destination may be the source of another pair; collisions, overlapping
+/// sources, and destinations inside moved directories or other destinations
+/// are rejected.
+pub(super) fn validate_batch_moves(pairs: &[(PathBuf, PathBuf)]) -> Result<Vec<BatchMove>> {
+    let mut moves: Vec<BatchMove> = Vec::new();
+
+    for (source, destination) in pairs {
+        if resolve_case_only_destination(source, destination)?.is_some() {
+            return Err(MdrefError::PathValidation {
+                path: source.clone(),
+                details: "case-only renames are not supported in batch moves".to_string(),
+            });
+        }
+        if !source.exists() {
+            return Err(MdrefError::PathValidation {
+                path: source.clone(),
+                details: "source path does not exist".to_string(),
+            });
+        }
+
+        let source_canonical = source
+            .canonicalize()
+            .map_err(|e| MdrefError::PathValidation {
+                path: source.clone(),
+                details: format!("cannot canonicalize source path: {e}"),
+            })?;
+        let resolved_dest = resolve_destination(source, destination)?;
+        let dest_canonical = absolute(&canonicalize_destination(&resolved_dest)?);
+
+        if source_canonical == dest_canonical {
+            continue;
+        }
+
+        moves.push(BatchMove {
+            source: source.clone(),
+            resolved_dest,
+            source_canonical,
+            dest_canonical,
+        });
+    }
+
+    let sources: HashSet<&Path> = moves
+        .iter()
+        .map(|entry| entry.source_canonical.as_path())
+        .collect();
+    let mut destinations = HashSet::new();
+    for entry in &moves {
+        if !destinations.insert(entry.dest_canonical.as_path()) {
+            return Err(MdrefError::PathValidation {
+                path: entry.resolved_dest.clone(),
+                details: "destination is used by more than one move".to_string(),
+            });
+        }
+    }
+
+    for entry in &moves {
+        if entry.resolved_dest.exists() && !sources.contains(entry.dest_canonical.as_path()) {
+            return Err(MdrefError::PathValidation {
+                path: entry.resolved_dest.clone(),
+                details: "destination path already exists".to_string(),
+            });
+        }
+
+        for other in &moves {
+            if std::ptr::eq(entry, other) {
+                continue;
+            }
+
+            if entry.source_canonical == other.source_canonical {
+                return Err(MdrefError::PathValidation {
+                    path: entry.source.clone(),
+                    details: "source is listed more than once".to_string(),
+                });
+            }
+            if entry.source_canonical.starts_with(&other.source_canonical) {
+                return Err(MdrefError::PathValidation {
+                    path: entry.source.clone(),
+                    details: format!(
+                        "source is inside '{}', which is also moved",
+                        other.source.display()
+                    ),
+                });
+            }
+            if entry.dest_canonical != other.source_canonical
+                && entry.dest_canonical.starts_with(&other.source_canonical)
+            {
+                return Err(MdrefError::PathValidation {
+                    path: entry.resolved_dest.clone(),
+                    details: format!(
+                        "destination is inside '{}', which is also moved",
+                        other.source.display()
+                    ),
+                });
+            }
+            if entry.dest_canonical.starts_with(&other.dest_canonical) {
+                return Err(MdrefError::PathValidation {
+                    path: entry.resolved_dest.clone(),
+                    details: format!(
+                        "destination is inside '{}', the destination of another move",
+                        other.resolved_dest.display()
+                    ),
+                });
+            }
+        }
+
+        if entry.source_canonical.is_dir()
+            && entry.dest_canonical.starts_with(&entry.source_canonical)
+        {
+            return Err(MdrefError::PathValidation {
+                path: entry.source.clone(),
+                details: "cannot move directory into itself or one of its subdirectories"
+                    .to_string(),
+            });
+        }
+    }
+
+    Ok(moves)
+}
@@ -0,0 +1,180 @@
+//! Report orphan pages, unreachable page groups, unused assets, and dead-end
+//! pages from the [`LinkGraph`] of a whole root.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    config::ScanConfig,
+    graph::{node_path, scan_link_graph},
+    model::{LinkGraph, OrphanReport},
+    progress::ProgressReporter,
+    util::collect_files,
+    wikilink::path_to_slash,
+};
+use crate::Result;
+
+/// The entrypoints used when none are given.
+pub const DEFAULT_ENTRYPOINTS: &[&str] = &["README.md", "index.md"];
+
+/// Scan `root` once and report the pages and assets nothing leads to.
+///
+/// Each entry of `entrypoints` names the pages reachability starts from: an
+/// entry containing `/` is a path relative to `root`, any other entry is a
+/// file name matched in every directory. See [`OrphanReport`] for what each
+/// list contains. Links count the way [`crate::build_link_graph`] resolves
+/// them; a page linking to itself is still an orphan and a dead end.
+///
+/// # Progress
+///
+/// Callers report progress through a [`ProgressReporter`] trait object. Pass
+/// [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+/// The reporter is called with [`ProgressReporter::set_total`] once before scanning,
+/// and with [`ProgressReporter::inc`] once per Markdown file as it is processed.
+pub fn find_orphans<P, S>(
+    root: P,
+    entrypoints: &[S],
+    progress: &dyn ProgressReporter,
+) -> Result<OrphanReport>
+where
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let root = root.as_ref();
+    let files = collect_files(root);
+    let scan = ScanConfig::for_path(root);
+    let is_markdown = |path: &Path| scan.is_markdown(path);
+    let graph = scan_link_graph(root, files.clone(), progress)?;
+    let files: BTreeSet<PathBuf> = files
+        .iter()
+        .filter_map(|file| node_path(&graph.root, file))
+        .collect();
+
+    let pages: BTreeSet<&Path> = files
+        .iter()
+        .map(PathBuf::as_path)
+        .filter(|file| is_markdown(file))
+        .collect();
+    let entrypoints: Vec<&Path> = pages
+        .iter()
+        .copied()
+        .filter(|page| is_entrypoint(page, entrypoints))
+        .collect();
+
+    let links = Links::new(&graph);
+    let orphans = pages
+        .iter()
+        .filter(|page| !links.inbound.contains(*page) && !entrypoints.contains(page))
+        .map(|page| page.to_path_buf())
+        .collect();
+    let dead_ends = pages
+        .iter()
+        .filter(|page| {
+            !links
+                .outbound
+                .get(*page)
+                .is_some_and(|targets| targets.iter().any(|target| is_markdown(target)))
+        })
+        .map(|page| page.to_path_buf())
+        .collect();
+    let unused_assets = files
+        .iter()
+        .filter(|file| !is_markdown(file) && !links.inbound.contains(file.as_path()))
+        .cloned()
+        .collect();
+
+    let reached = links.reachable_from(&entrypoints);
+    let unreachable: BTreeSet<&Path> = pages
+        .iter()
+        .copied()
+        .filter(|page| !reached.contains(page))
+        .collect();
+
+    Ok(OrphanReport {
+        entrypoints: entrypoints.iter().map(|page| page.to_path_buf()).collect(),
+        orphans,
+        unreachable: links.components(&unreachable),
+        unused_assets,
+        dead_ends,
+        root: graph.root,
+    })
+}
+
+fn is_entrypoint<S: AsRef<str>>(page: &Path, entrypoints: &[S]) -> bool {
+    let Some(relative) = path_to_slash(page) else {
+        return false;
+    };
+    let name = relative.rsplit('/').next().unwrap_or(&relative);
+    entrypoints.iter().map(AsRef::as_ref).any(|entry| {
+        if entry.contains('/') {
+            entry.trim_start_matches("./") == relative
+        } else {
+            entry == name
+        }
+    })
+}
+
+/// The edges of a [`LinkGraph`] between distinct files, by direction.
+struct Links<'a> {
+    outbound: HashMap<&'a Path, HashSet<&'a Path>>,
+    inbound: HashSet<&'a Path>,
+    neighbours: HashMap<&'a Path, HashSet<&'a Path>>,
+}
+
+impl<'a> Links<'a> {
+    fn new(graph: &'a LinkGraph) -> Self {
+        let mut links = Self {
+            outbound: HashMap::new(),
+            inbound: HashSet::new(),
+            neighbours: HashMap::new(),
+        };
+        for edge in graph.edges.iter().filter(|edge| edge.source != edge.target) {
+            let (source, target) = (edge.source.as_path(), edge.target.as_path());
+            links.outbound.entry(source).or_default().insert(target);
+            links.inbound.insert(target);
+            links.neighbours.entry(source).or_default().insert(target);
+            links.neighbours.entry(target).or_default().insert(source);
+        }
+        links
+    }
+
+    /// Every file reachable from `starts` by following links forwards.
+    fn reachable_from(&self, starts: &[&'a Path]) -> HashSet<&'a Path> {
+        let mut reached: HashSet<&Path> = starts.iter().copied().collect();
+        let mut stack = starts.to_vec();
+        while let Some(node) = stack.pop() {
+            for &next in self.outbound.get(node).into_iter().flatten() {
+                if reached.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Split `nodes` into groups connected by links in either direction,
+    /// ignoring links that leave `nodes`.
+    fn components(&self, nodes: &BTreeSet<&'a Path>) -> Vec<Vec<PathBuf>> {
+        let mut seen: HashSet<&Path> = HashSet::new();
+        let mut groups = Vec::new();
+        for &start in nodes {
+            if seen.contains(start) {
+                continue;
+            }
+            let mut group = BTreeSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &next in self.neighbours.get(node).into_iter().flatten() {
+                    if nodes.contains(next) && group.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            seen.extend(&group);
+            groups.push(group.into_iter().map(Path::to_path_buf).collect());
+        }
+        groups
+    }
+}
@@ -0,0 +1,439 @@
+//! Write-ahead log for mutating operations, stored at
+//! `<root>/.mdref/transaction`, and recovery after a crash.
+//!
+//! Before its first mutation, an operation writes a header naming every path
+//! it will move and, for each file it rewrites, the original and rewritten
+//! content. Every path move is appended to the log before it starts. The log
+//! is removed once the operation completes or has been rolled back, so a log
+//! found on disk means mdref was interrupted mid-operation. [`recover`] then
+//! rolls the operation back (undoing started moves in reverse and restoring
+//! original contents) or forward (rolling back, then replaying it in full).
+
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    index::{INDEX_DIR, create_state_dir},
+    journal::{forget_entry, record_operation},
+    model::{InterruptedOperation, MoveTransaction},
+    mv::{
+        apply::{execute_with_rollback, move_paths, rewrite_content, rollback_on_error},
+        plan::ReplacementPlan,
+    },
+    util::{pre_move_path, remove_path, resolve_keeping_name, sync_parent_dir, write_atomic},
+};
+use crate::{MdrefError, Result};
+
+const TRANSACTION_FILE: &str = "transaction";
+/// Bumped whenever the on-disk format changes.
+const LOG_VERSION: u32 = 1;
+
+/// How [`recover`] resolves an interrupted operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Restore the tree to its state before the operation.
+    RollBack,
+    /// Finish the operation as planned.
+    RollForward,
+}
+
+/// Return the location of the write-ahead log for `root`.
+pub fn transaction_log_path<P: AsRef<Path>>(root: P) -> PathBuf {
+    root.as_ref().join(INDEX_DIR).join(TRANSACTION_FILE)
+}
+
+/// Describe the operation left unfinished under `root`, if any.
+pub fn interrupted_operation<P: AsRef<Path>>(root: P) -> Result<Option<InterruptedOperation>> {
+    Ok(LoggedOperation::load(root.as_ref())?.map(|logged| logged.summary()))
+}
+
+/// Roll the operation left unfinished under `root` back or forward and remove
+/// its write-ahead log, returning what was recovered.
+///
+/// Rolling forward first rolls back, then replays the whole operation under a
+/// fresh log, so it is safe to run again if it is interrupted in turn.
+pub fn recover<P: AsRef<Path>>(root: P, action: RecoveryAction) -> Result<InterruptedOperation> {
+    let root = root.as_ref();
+    let Some(logged) = LoggedOperation::load(root)? else {
+        return Err(MdrefError::PathValidation {
+            path: transaction_log_path(root),
+            details: "no interrupted operation to recover".to_string(),
+        });
+    };
+
+    logged.roll_back()?;
+    remove_log(&transaction_log_path(root))?;
+
+    if action == RecoveryAction::RollForward {
+        logged.replay(root)?;
+    }
+
+    Ok(logged.summary())
+}
+
+/// The write-ahead log of one running operation.
+pub(crate) struct WriteAheadLog {
+    path: PathBuf,
+}
+
+impl WriteAheadLog {
+    /// Start logging an operation that moves `moves` and then applies `plan`,
+    /// whose keys are post-move paths. Must run before the first mutation:
+    /// original contents are read from the pre-move paths.
+    pub(crate) fn for_plan(
+        root: &Path,
+        operation: &str,
+        moves: &[(PathBuf, PathBuf)],
+        plan: &ReplacementPlan,
+    ) -> Result<Self> {
+        Self::begin(root, plan_header(operation, moves, plan)?)
+    }
+
+    /// Start logging a removal that parks each path with `moves`, applies
+    /// `plan`, and then deletes the parked paths.
+    pub(crate) fn for_removal(
+        root: &Path,
+        moves: &[(PathBuf, PathBuf)],
+        plan: &ReplacementPlan,
+    ) -> Result<Self> {
+        let header = LogHeader {
+            discard_moved: true,
+            ..plan_header("rm", moves, plan)?
+        };
+        Self::begin(root, header)
+    }
+
+    /// Start logging an undo of journal entry `undone_entry`. `moves` and
+    /// `files` must already be absolute.
+    pub(crate) fn for_undo(
+        root: &Path,
+        undone_entry: u64,
+        moves: Vec<(PathBuf, PathBuf)>,
+        files: Vec<LoggedFile>,
+    ) -> Result<Self> {
+        Self::begin(
+            root,
+            LogHeader {
+                version: LOG_VERSION,
+                operation: "undo".to_string(),
+                moves,
+                files,
+                undone_entry: Some(undone_entry),
+                discard_moved: false,
+            },
+        )
+    }
+
+    /// Write the header, refusing to start while another operation is
+    /// waiting to be recovered.
+    fn begin(root: &Path, header: LogHeader) -> Result<Self> {
+        if let Some(pending) = LoggedOperation::load(root)? {
+            return Err(MdrefError::InterruptedOperation {
+                path: root.to_path_buf(),
+                operation: pending.header.operation,
+            });
+        }
+
+        create_state_dir(root)?;
+        let path = transaction_log_path(root);
+        let mut line = serialize_line(&header)?;
+        line.push('\n');
+        write_atomic(&path, line.as_bytes()).map_err(|source| MdrefError::IoWrite {
+            path: path.clone(),
+            source,
+        })?;
+
+        Ok(Self { path })
+    }
+
+    /// Durably record that `from` is about to move to `to`.
+    pub(crate) fn log_move(&self, from: &Path, to: &Path) -> Result<()> {
+        let step = LoggedMove {
+            from: resolve_keeping_name(from)?,
+            to: resolve_keeping_name(to)?,
+        };
+        let mut line = serialize_line(&step)?;
+        line.push('\n');
+
+        let append = || -> io::Result<()> {
+            let mut file = OpenOptions::new().append(true).open(&self.path)?;
+            file.write_all(line.as_bytes())?;
+            file.sync_data()
+        };
+        append().map_err(|source| MdrefError::IoWrite {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    /// Remove the log once the operation completed or was rolled back.
+    pub(crate) fn remove(&self) -> Result<()> {
+        remove_log(&self.path)
+    }
+}
+
+/// Build the log header of an operation that moves `moves` and then applies
+/// `plan`, reading original contents from the pre-move paths.
+fn plan_header(
+    operation: &str,
+    moves: &[(PathBuf, PathBuf)],
+    plan: &ReplacementPlan,
+) -> Result<LogHeader> {
+    let mut resolved_moves = Vec::new();
+    for (source, destination) in moves {
+        if source != destination {
+            resolved_moves.push((
+                resolve_keeping_name(source)?,
+                resolve_keeping_name(destination)?,
+            ));
+        }
+    }
+
+    let mut files = Vec::new();
+    for (path, replacements) in plan {
+        let final_path = resolve_keeping_name(path)?;
+        let original_path = pre_move_path(&resolved_moves, &final_path);
+        let original = fs::read_to_string(&original_path).map_err(|e| MdrefError::IoRead {
+            path: original_path.clone(),
+            source: e,
+        })?;
+        let rewritten = rewrite_content(&original_path, &original, replacements)?;
+        files.push(LoggedFile {
+            original_path,
+            final_path,
+            original,
+            rewritten,
+        });
+    }
+    files.sort_by(|left, right| left.final_path.cmp(&right.final_path));
+
+    Ok(LogHeader {
+        version: LOG_VERSION,
+        operation: operation.to_string(),
+        moves: resolved_moves,
+        files,
+        undone_entry: None,
+        discard_moved: false,
+    })
+}
+
+/// A file an operation rewrites, with enough content to go either way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LoggedFile {
+    /// Where the file is before the operation.
+    pub(crate) original_path: PathBuf,
+    /// Where the file is once the operation completes.
+    pub(crate) final_path: PathBuf,
+    pub(crate) original: String,
+    pub(crate) rewritten: String,
+}
+
+/// First line of the log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LogHeader {
+    version: u32,
+    operation: String,
+    moves: Vec<(PathBuf, PathBuf)>,
+    files: Vec<LoggedFile>,
+    /// Journal entry an interrupted `undo` was reverting.
+    #[serde(default)]
+    undone_entry: Option<u64>,
+    /// The moves park paths that are deleted once the operation completes
+    /// (`rm`).
+    #[serde(default)]
+    discard_moved: bool,
+}
+
+/// Every further line: a path move that was about to start.
+#[derive(Debug, Serialize, Deserialize)]
+struct LoggedMove {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+/// A log read back from disk.
+struct LoggedOperation {
+    header: LogHeader,
+    started_moves: Vec<LoggedMove>,
+}
+
+impl LoggedOperation {
+    fn load(root: &Path) -> Result<Option<Self>> {
+        let path = transaction_log_path(root);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(MdrefError::IoRead { path, source }),
+        };
+
+        let mut lines = content.lines();
+        let header = lines
+            .next()
+            .and_then(|line| serde_json::from_str::<LogHeader>(line).ok())
+            .filter(|header| header.version == LOG_VERSION)
+            .ok_or_else(|| MdrefError::PathValidation {
+                path: path.clone(),
+                details: "unreadable transaction log".to_string(),
+            })?;
+        // A torn last line is a move whose intent never reached the disk, so
+        // the move itself never started.
+        let started_moves = lines
+            .map_while(|line| serde_json::from_str::<LoggedMove>(line).ok())
+            .collect();
+
+        Ok(Some(Self {
+            header,
+            started_moves,
+        }))
+    }
+
+    /// Undo every started move in reverse, then restore original contents.
+    fn roll_back(&self) -> Result<()> {
+        for step in self.started_moves.iter().rev() {
+            undo_move(&step.from, &step.to).map_err(|source| MdrefError::IoWrite {
+                path: step.from.clone(),
+                source,
+            })?;
+        }
+
+        for file in &self.header.files {
+            let current = fs::read_to_string(&file.original_path).ok();
+            if current.as_deref() != Some(file.original.as_str()) {
+                write_atomic(&file.original_path, file.original.as_bytes()).map_err(|source| {
+                    MdrefError::IoWrite {
+                        path: file.original_path.clone(),
+                        source,
+                    }
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run the logged operation again from its original state.
+    fn replay(&self, root: &Path) -> Result<()> {
+        let header = &self.header;
+        let log = WriteAheadLog::begin(root, header.clone())?;
+
+        let mut transaction = MoveTransaction::for_batch();
+        for file in &header.files {
+            transaction.snapshot_file(&file.original_path)?;
+        }
+        transaction.attach_log(log);
+
+        let moved = move_paths(&header.moves, &mut transaction);
+        rollback_on_error(&transaction, moved)?;
+
+        execute_with_rollback(&transaction, || {
+            for file in &header.files {
+                write_atomic(&file.final_path, file.rewritten.as_bytes()).map_err(|source| {
+                    MdrefError::IoWrite {
+                        path: file.final_path.clone(),
+                        source,
+                    }
+                })?;
+            }
+            Ok(())
+        })?;
+
+        if header.discard_moved {
+            let discarded = header.moves.iter().try_for_each(|(_, parked)| {
+                remove_path(parked).map_err(|source| MdrefError::IoWrite {
+                    path: parked.clone(),
+                    source,
+                })
+            });
+            rollback_on_error(&transaction, discarded)?;
+        }
+        if let Some(id) = header.undone_entry {
+            rollback_on_error(&transaction, forget_entry(root, id))?;
+        }
+        transaction.commit()?;
+
+        if header.undone_entry.is_none() && !header.discard_moved {
+            let snapshots: HashMap<PathBuf, String> = header
+                .files
+                .iter()
+                .map(|file| (file.original_path.clone(), file.original.clone()))
+                .collect();
+            record_operation(root, &header.operation, &header.moves, &snapshots);
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> InterruptedOperation {
+        InterruptedOperation {
+            operation: self.header.operation.clone(),
+            moves: self.header.moves.clone(),
+            started_moves: self.started_moves.len(),
+            files: self
+                .header
+                .files
+                .iter()
+                .map(|file| file.final_path.clone())
+                .collect(),
+        }
+    }
+}
+
+/// Reverse one logged move of `from` to `to`, whatever point it reached.
+///
+/// Every logged destination was free when its move started, so a destination
+/// that exists next to its source is an interrupted copy and is dropped.
+fn undo_move(from: &Path, to: &Path) -> io::Result<()> {
+    if !to.exists() {
+        return Ok(());
+    }
+
+    // On case-insensitive filesystems both spellings of a case-only rename
+    // name the same file; renaming back is harmless either way.
+    let case_only = from.as_os_str().eq_ignore_ascii_case(to.as_os_str());
+    if from.exists() && !case_only {
+        return if to.is_dir() {
+            Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("both {} and {} exist", from.display(), to.display()),
+            ))
+        } else {
+            fs::remove_file(to)
+        };
+    }
+
+    match fs::rename(to, from) {
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices && to.is_file() => {
+            fs::copy(to, from)?;
+            fs::remove_file(to)
+        }
+        result => result,
+    }
+}
+
+fn remove_log(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => {
+            return Err(MdrefError::IoWrite {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    }
+    sync_parent_dir(path).map_err(|source| MdrefError::IoWrite {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn serialize_line<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|error| MdrefError::SerializationFailed {
+        details: format!("failed to serialize transaction log: {error}"),
+    })
+}
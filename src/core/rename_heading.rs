@@ -0,0 +1,476 @@
+//! Rename a Markdown heading and rewrite every `#fragment` link that points to it.
+//!
+//! The heading text is replaced in place, the document's anchors are recomputed,
+//! and every anchor whose slug changed (including duplicate-suffix shifts such as
+//! `usage-1` becoming `usage`) is rewritten in inbound `file.md#slug` links and
+//! in the document's own `#slug` links. Wikilinks such as `[[file#Old Heading]]`
+//! name the heading by text and are rewritten to the new text. Planning and applying reuse the `mv`
+//! replacement and rollback machinery.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use super::{
+    anchor::{collect_headings, split_explicit_anchor},
+    find::{collect_local_links, find_references},
+    journal::record_operation,
+    model::{Heading, HeadingRenamePreview, LinkReplacement, MoveChangeKind, MoveTransaction},
+    mv::{
+        apply::{apply_replacements, execute_with_rollback},
+        plan::{LineCache, ReplacementPlan, build_replacement, split_link_and_anchor},
+        preview::{build_move_changes, print_move_changes},
+    },
+    progress::ProgressReporter,
+    recover::WriteAheadLog,
+    util::{strip_utf8_bom_prefix, url_decode_link},
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+/// Rename a heading in `file` and update every link to its anchor under `root`.
+///
+/// `old` may be the heading text (e.g. `"Getting Started"`) or its anchor
+/// (`"getting-started"` or `"#getting-started"`). `new` is the new heading
+/// text, written as Markdown.
+///
+/// Headings with an explicit `{#id}` attribute keep their id, so only the
+/// heading line changes. All file writes are rolled back if any of them fails,
+/// and a completed rename is recorded in the undo journal under `root`.
+///
+/// When `dry_run` is `true`, no files are modified. Instead, the function
+/// prints all changes that *would* be made.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn rename_heading<P, O, N, D>(
+    file: P,
+    old: O,
+    new: N,
+    root: D,
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
+where
+    P: AsRef<Path>,
+    O: AsRef<str>,
+    N: AsRef<str>,
+    D: AsRef<Path>,
+{
+    let file = file.as_ref();
+    let (preview, replacements_by_file) =
+        plan_heading_rename(file, old.as_ref(), new.as_ref(), root.as_ref(), progress)?;
+
+    if dry_run {
+        print_dry_run_report(&preview);
+        return Ok(());
+    }
+
+    let mut transaction = MoveTransaction::new(file.to_path_buf(), file.to_path_buf());
+    for file_path in replacements_by_file.keys() {
+        transaction.snapshot_file(file_path)?;
+    }
+    transaction.attach_log(WriteAheadLog::for_plan(
+        root.as_ref(),
+        "rename-heading",
+        &[],
+        &replacements_by_file,
+    )?);
+
+    execute_with_rollback(&transaction, || {
+        for (file_path, replacements) in &replacements_by_file {
+            apply_replacements(file_path, replacements)?;
+        }
+        Ok(())
+    })?;
+
+    transaction.commit()?;
+    record_operation(
+        root.as_ref(),
+        "rename-heading",
+        &[],
+        &transaction.file_snapshots,
+    );
+    Ok(())
+}
+
+/// Preview a heading rename without mutating the filesystem.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn preview_rename_heading<P, O, N, D>(
+    file: P,
+    old: O,
+    new: N,
+    root: D,
+    progress: &dyn ProgressReporter,
+) -> Result<HeadingRenamePreview>
+where
+    P: AsRef<Path>,
+    O: AsRef<str>,
+    N: AsRef<str>,
+    D: AsRef<Path>,
+{
+    let (preview, _replacements_by_file) = plan_heading_rename(
+        file.as_ref(),
+        old.as_ref(),
+        new.as_ref(),
+        root.as_ref(),
+        progress,
+    )?;
+    Ok(preview)
+}
+
+fn plan_heading_rename(
+    file: &Path,
+    old: &str,
+    new: &str,
+    root: &Path,
+    progress: &dyn ProgressReporter,
+) -> Result<(HeadingRenamePreview, ReplacementPlan)> {
+    let new = new.trim();
+    if new.is_empty() || new.contains(['\n', '\r']) {
+        return Err(MdrefError::PathValidation {
+            path: file.to_path_buf(),
+            details: "new heading must be a single non-empty line".to_string(),
+        });
+    }
+
+    let content = fs::read_to_string(file).map_err(|e| MdrefError::IoRead {
+        path: file.to_path_buf(),
+        source: e,
+    })?;
+    let headings = collect_headings(&content);
+    let index = select_heading(&headings, old, file)?;
+    let heading = &headings[index];
+
+    let heading_replacement = build_heading_replacement(&content, heading, new, file)?;
+    let new_headings = collect_headings(&replace_in_line(&content, &heading_replacement));
+    if new_headings.len() != headings.len() {
+        return Err(MdrefError::PathValidation {
+            path: file.to_path_buf(),
+            details: format!("new heading '{new}' would change the document's heading structure"),
+        });
+    }
+
+    let anchor_changes: HashMap<&str, &str> = headings
+        .iter()
+        .zip(&new_headings)
+        .filter(|(before, after)| before.anchor != after.anchor)
+        .map(|(before, after)| (before.anchor.as_str(), after.anchor.as_str()))
+        .collect();
+
+    let mut replacements_by_file: ReplacementPlan = HashMap::new();
+    let mut line_cache = LineCache::new();
+    replacements_by_file
+        .entry(file.to_path_buf())
+        .or_default()
+        .push(heading_replacement);
+
+    // Wikilinks name headings by text (`[[Page#Heading]]`), Markdown links by anchor.
+    let new_heading = new_headings[index].text.as_str();
+    let new_fragment = |link: &Reference, fragment: &str| -> Option<String> {
+        if link.link_type == LinkType::WikiLink {
+            (fragment.trim() == heading.text).then(|| new_heading.to_string())
+        } else {
+            anchor_changes
+                .get(url_decode_link(fragment).as_str())
+                .map(|anchor| anchor.to_string())
+        }
+    };
+
+    let file_canonical = file.canonicalize()?;
+
+    progress.set_message("Scanning references...");
+    for reference in find_references(file, root, progress)? {
+        let (link_path, Some(fragment)) = split_link_and_anchor(&reference.link_text) else {
+            continue;
+        };
+        let Some(new_fragment) = new_fragment(&reference, fragment) else {
+            continue;
+        };
+
+        let replacement = build_replacement(
+            &reference,
+            &format!("{link_path}#{new_fragment}"),
+            &mut line_cache,
+        )?;
+        let key = if reference.path.canonicalize().ok().as_ref() == Some(&file_canonical) {
+            file.to_path_buf()
+        } else {
+            reference.path.clone()
+        };
+        replacements_by_file
+            .entry(key)
+            .or_default()
+            .push(replacement);
+    }
+
+    for link in collect_local_links(&content, file) {
+        let Some(fragment) = link.link_text.strip_prefix('#') else {
+            continue;
+        };
+        let Some(new_fragment) = new_fragment(&link, fragment) else {
+            continue;
+        };
+
+        let replacement = build_replacement(&link, &format!("#{new_fragment}"), &mut line_cache)?;
+        replacements_by_file
+            .entry(file.to_path_buf())
+            .or_default()
+            .push(replacement);
+    }
+
+    let preview = HeadingRenamePreview {
+        path: file.to_path_buf(),
+        old_heading: heading.text.clone(),
+        new_heading: new_heading.to_string(),
+        old_anchor: heading.anchor.clone(),
+        new_anchor: new_headings[index].anchor.clone(),
+        changes: build_move_changes(replacements_by_file.clone(), |path| {
+            if path == file {
+                MoveChangeKind::HeadingUpdate
+            } else {
+                MoveChangeKind::ReferenceUpdate
+            }
+        }),
+    };
+
+    Ok((preview, replacements_by_file))
+}
+
+/// Pick the heading named by `old`, matching its anchor first and its text second.
+fn select_heading(headings: &[Heading], old: &str, file: &Path) -> Result<usize> {
+    let slug = old.strip_prefix('#').unwrap_or(old);
+    if let Some(index) = headings.iter().position(|heading| heading.anchor == slug) {
+        return Ok(index);
+    }
+
+    let matches: Vec<usize> = headings
+        .iter()
+        .enumerate()
+        .filter(|(_, heading)| heading.text == old.trim())
+        .map(|(index, _)| index)
+        .collect();
+
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(MdrefError::PathValidation {
+            path: file.to_path_buf(),
+            details: format!("no heading matches '{old}'"),
+        }),
+        _ => Err(MdrefError::PathValidation {
+            path: file.to_path_buf(),
+            details: format!(
+                "'{old}' matches {} headings; pass the anchor slug instead",
+                matches.len()
+            ),
+        }),
+    }
+}
+
+/// Build the replacement that swaps the heading's source text for `new`.
+///
+/// ATX markers, a closing `#` sequence, and an explicit `{#id}` are preserved.
+/// A setext heading whose text spans several lines is an error, since only
+/// one line can be replaced.
+fn build_heading_replacement(
+    content: &str,
+    heading: &Heading,
+    new: &str,
+    file: &Path,
+) -> Result<LinkReplacement> {
+    let mut lines = content.lines().skip(heading.line - 1);
+    let line = lines
+        .next()
+        .ok_or_else(|| MdrefError::InvalidLineReference {
+            path: file.to_path_buf(),
+            line: heading.line,
+            details: "heading line out of range".to_string(),
+        })?;
+    let node_start = heading.column.saturating_sub(1);
+    let is_atx = line
+        .get(node_start..)
+        .is_some_and(|rest| atx_marker_len(strip_utf8_bom_prefix(rest).0).is_some());
+    if !is_atx && !lines.next().is_some_and(is_setext_underline) {
+        return Err(MdrefError::PathValidation {
+            path: file.to_path_buf(),
+            details: format!(
+                "heading '{}' spans several lines; join it onto one line first",
+                heading.text
+            ),
+        });
+    }
+    let (start, end) = heading_text_span(line, heading.column).ok_or_else(|| {
+        MdrefError::InvalidLineReference {
+            path: file.to_path_buf(),
+            line: heading.line,
+            details: "could not locate heading text".to_string(),
+        }
+    })?;
+
+    Ok(LinkReplacement {
+        line: heading.line,
+        column: start + 1,
+        old_pattern: line[start..end].to_string(),
+        new_pattern: new.to_string(),
+    })
+}
+
+/// Locate the heading text inside its source line as a byte span.
+///
+/// `column` is the 1-based column where the heading node starts. For ATX
+/// headings the opening `#`s are skipped; the optional closing sequence and a
+/// trailing `{#id}` are excluded from the span.
+fn heading_text_span(line: &str, column: usize) -> Option<(usize, usize)> {
+    let (_, bom_offset) = strip_utf8_bom_prefix(line);
+    let node_start = (column.saturating_sub(1)).max(bom_offset);
+    let rest = line.get(node_start..)?;
+
+    let hashes = atx_marker_len(rest).unwrap_or(0);
+    let is_atx = hashes > 0;
+
+    let start = if is_atx {
+        let after_hashes = &rest[hashes..];
+        node_start + hashes + (after_hashes.len() - after_hashes.trim_start().len())
+    } else {
+        node_start
+    };
+
+    let mut text = line[start..].trim_end();
+    if is_atx {
+        let without_closing = text.trim_end_matches('#');
+        if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+            text = without_closing.trim_end();
+        }
+    }
+    let (text, _) = split_explicit_anchor(text);
+
+    Some((start, start + text.len()))
+}
+
+/// The length of the opening `#` sequence when `rest` starts an ATX heading.
+fn atx_marker_len(rest: &str) -> Option<usize> {
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    ((1..=6).contains(&hashes)
+        && rest[hashes..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == ' ' || c == '\t'))
+    .then_some(hashes)
+}
+
+/// Whether `line` is a setext underline, after any blockquote markers.
+fn is_setext_underline(line: &str) -> bool {
+    let underline = line.trim_start_matches(['>', ' ', '\t']).trim_end();
+    !underline.is_empty()
+        && (underline.chars().all(|c| c == '=') || underline.chars().all(|c| c == '-'))
+}
+
+fn replace_in_line(content: &str, replacement: &LinkReplacement) -> String {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if index + 1 == replacement.line {
+                let start = replacement.column - 1;
+                let end = start + replacement.old_pattern.len();
+                format!(
+                    "{}{}{}",
+                    &line[..start],
+                    replacement.new_pattern,
+                    &line[end..]
+                )
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Print a human-readable report of all changes a heading rename would make.
+fn print_dry_run_report(preview: &HeadingRenamePreview) {
+    println!(
+        "[dry-run] Would rename heading in {}: {} (#{}) -> {} (#{})",
+        preview.path.display(),
+        preview.old_heading,
+        preview.old_anchor,
+        preview.new_heading,
+        preview.new_anchor
+    );
+    print_move_changes(&preview.changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{core::progress::NoopProgress, test_utils::write_file};
+
+    #[rstest]
+    #[case::atx("## Getting Started", 1, "Getting Started")]
+    #[case::atx_closed("## Getting Started ##", 1, "Getting Started")]
+    #[case::explicit_id("## Getting Started {#start}", 1, "Getting Started")]
+    #[case::hash_in_text("# C# tips", 1, "C# tips")]
+    #[case::setext("Getting Started", 1, "Getting Started")]
+    #[case::blockquote("> ## Quoted", 3, "Quoted")]
+    fn test_heading_text_span_excludes_markers(
+        #[case] line: &str,
+        #[case] column: usize,
+        #[case] expected: &str,
+    ) {
+        let (start, end) = heading_text_span(line, column).unwrap();
+        assert_eq!(&line[start..end], expected);
+    }
+
+    #[test]
+    fn test_select_heading_prefers_anchor_then_unique_text() {
+        let headings = collect_headings("# Intro\n\n## Usage\n\n## Usage\n");
+        let file = Path::new("doc.md");
+
+        assert_eq!(select_heading(&headings, "#usage-1", file).unwrap(), 2);
+        assert_eq!(select_heading(&headings, "Intro", file).unwrap(), 0);
+        assert!(
+            select_heading(&headings, "Usage", file)
+                .unwrap_err()
+                .to_string()
+                .contains("matches 2 headings")
+        );
+        assert!(select_heading(&headings, "Missing", file).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_preview_rename_heading_shifts_duplicate_suffixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let doc = temp_dir.path().join("doc.md");
+        write_file(
+            &doc,
+            "## Usage\n\n## Usage\n\n[First](#usage) [Second](#usage-1)\n",
+        );
+
+        let preview =
+            preview_rename_heading(&doc, "usage", "Basics", temp_dir.path(), &NoopProgress)
+                .unwrap();
+
+        assert_eq!(preview.new_anchor, "basics");
+        assert_eq!(preview.changes.len(), 1);
+        let patterns: Vec<(&str, &str)> = preview.changes[0]
+            .replacements
+            .iter()
+            .map(|r| (r.old_pattern.as_str(), r.new_pattern.as_str()))
+            .collect();
+        assert_eq!(
+            patterns,
+            vec![
+                ("Usage", "Basics"),
+                ("](#usage)", "](#basics)"),
+                ("](#usage-1)", "](#usage)"),
+            ]
+        );
+    }
+}
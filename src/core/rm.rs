@@ -0,0 +1,370 @@
+//! Remove a file or directory without leaving dangling links behind.
+//!
+//! Removal first finds every inbound reference from files that stay. By
+//! default a path that is still referenced is not removed; the other
+//! [`RemoveMode`]s unlink those references, redirect them to another page, or
+//! leave them dangling. The removal runs as a transaction: the path is parked
+//! at a hidden sibling, references are rewritten, and the parked path is
+//! deleted last, so a failure puts everything back. Removals are logged for
+//! `mdref recover` but are not recorded in the undo journal.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    find::{FileHashes, find_link_spans, find_references_with_hashes, link_matches_target},
+    model::{LinkReplacement, MoveChangeKind, MoveTransaction, RemovePreview},
+    mv::{
+        apply::{
+            apply_replacements, execute_with_rollback, move_paths, parking_path, rollback_on_error,
+            verify_snapshots,
+        },
+        plan::{LineCache, ReplacementPlan, build_replacement, split_link_and_anchor},
+        preview::{build_move_changes, print_move_changes},
+    },
+    progress::ProgressReporter,
+    recover::WriteAheadLog,
+    util::{relative_path, remove_path},
+    wikilink::{WikiVault, absolute, find_wikilink_span},
+};
+use crate::{LinkType, MdrefError, Reference, Result};
+
+/// What [`rm`] does with links that still point at the removed path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RemoveMode {
+    /// Refuse to remove a path that is still referenced.
+    #[default]
+    Refuse,
+    /// Replace each link with its text: `[text](gone.md)` becomes `text`.
+    Unlink,
+    /// Point each link at this page instead.
+    RedirectTo(PathBuf),
+    /// Remove the path anyway and leave the links dangling.
+    Force,
+}
+
+/// Remove the file or directory at `path` and deal with the links under
+/// `root` that point at it according to `mode`.
+///
+/// Links from files inside a removed directory do not count. With
+/// [`RemoveMode::Refuse`], a path that is still referenced is left alone and
+/// [`MdrefError::StillReferenced`] is returned; [`preview_remove`] lists the
+/// references. Unlinking replaces inline links, images and wikilinks with
+/// their text, reference-style links with their text and their definitions
+/// with an empty line; links in raw HTML cannot be unlinked and make the
+/// removal fail before anything changes. Redirected links drop their
+/// `#fragment`, which named a heading of the removed page.
+///
+/// When `dry_run` is `true`, no files are removed or modified. Instead, the
+/// function prints all changes that *would* be made.
+///
+/// # Progress
+///
+/// Callers pass a [`ProgressReporter`] trait object to receive scanning progress.
+/// Pass [`crate::NoopProgress`] (as `&NoopProgress`) when progress updates are not needed.
+pub fn rm<P, B>(
+    path: P,
+    root: B,
+    mode: &RemoveMode,
+    dry_run: bool,
+    progress: &dyn ProgressReporter,
+) -> Result<()>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let RemovePlan {
+        preview,
+        path_canonical,
+        replacements_by_file,
+        scanned_hashes,
+    } = plan_remove(path.as_ref(), root, mode, progress)?;
+
+    if *mode == RemoveMode::Refuse && !preview.references.is_empty() {
+        return Err(MdrefError::StillReferenced {
+            path: preview.path,
+            count: preview.references.len(),
+        });
+    }
+
+    if dry_run {
+        println!("[dry-run] Would remove: {}", preview.path.display());
+        if *mode == RemoveMode::Force {
+            for reference in &preview.references {
+                println!("[dry-run] Would leave dangling: {reference}");
+            }
+        }
+        print_move_changes(&preview.changes);
+        return Ok(());
+    }
+
+    let mut transaction = MoveTransaction::for_batch();
+    for file_path in replacements_by_file.keys() {
+        transaction.snapshot_file(file_path)?;
+    }
+    verify_snapshots(&transaction, &scanned_hashes)?;
+
+    let parked = parking_path(&path_canonical);
+    let moves = [(path_canonical, parked.clone())];
+    transaction.attach_log(WriteAheadLog::for_removal(
+        root,
+        &moves,
+        &replacements_by_file,
+    )?);
+
+    let moved = move_paths(&moves, &mut transaction);
+    rollback_on_error(&transaction, moved)?;
+
+    execute_with_rollback(&transaction, || {
+        for (file_path, replacements) in &replacements_by_file {
+            apply_replacements(file_path, replacements)?;
+        }
+        remove_path(&parked).map_err(|source| MdrefError::IoWrite {
+            path: parked.clone(),
+            source,
+        })
+    })?;
+
+    transaction.commit()
+}
+
+/// Preview a removal without touching the filesystem.
+///
+/// Unlike [`rm`], this succeeds with [`RemoveMode::Refuse`] even when the
+/// path is still referenced, so callers can list the references.
+pub fn preview_remove<P, B>(
+    path: P,
+    root: B,
+    mode: &RemoveMode,
+    progress: &dyn ProgressReporter,
+) -> Result<RemovePreview>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    Ok(plan_remove(path.as_ref(), root.as_ref(), mode, progress)?.preview)
+}
+
+/// A removal with its inbound references and the rewrites `mode` asks for.
+struct RemovePlan {
+    preview: RemovePreview,
+    path_canonical: PathBuf,
+    replacements_by_file: ReplacementPlan,
+    scanned_hashes: FileHashes,
+}
+
+fn plan_remove(
+    path: &Path,
+    root: &Path,
+    mode: &RemoveMode,
+    progress: &dyn ProgressReporter,
+) -> Result<RemovePlan> {
+    let path_canonical = path.canonicalize().map_err(|e| MdrefError::IoRead {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    if root.canonicalize()?.starts_with(&path_canonical) {
+        return Err(MdrefError::PathValidation {
+            path: path.to_path_buf(),
+            details: "cannot remove the root directory or one of its parents".to_string(),
+        });
+    }
+
+    progress.set_message("Scanning references...");
+    let mut scanned_hashes = FileHashes::new();
+    let references: Vec<Reference> =
+        find_references_with_hashes(path, root, progress, &mut scanned_hashes)?
+            .into_iter()
+            .filter(|reference| {
+                !reference
+                    .path
+                    .canonicalize()
+                    .is_ok_and(|referrer| referrer.starts_with(&path_canonical))
+            })
+            .collect();
+
+    let replacements_by_file = match mode {
+        RemoveMode::Refuse | RemoveMode::Force => ReplacementPlan::new(),
+        RemoveMode::Unlink => plan_unlink_replacements(&references, &path_canonical)?,
+        RemoveMode::RedirectTo(page) => {
+            let redirect = page.canonicalize().map_err(|e| MdrefError::IoRead {
+                path: page.clone(),
+                source: e,
+            })?;
+            if redirect.starts_with(&path_canonical) {
+                return Err(MdrefError::PathValidation {
+                    path: page.clone(),
+                    details: "redirect target is inside the removed path".to_string(),
+                });
+            }
+            plan_redirect_replacements(&references, &redirect, root)?
+        }
+    };
+    let changes = build_move_changes(replacements_by_file.clone(), |_| {
+        MoveChangeKind::ReferenceUpdate
+    });
+
+    Ok(RemovePlan {
+        preview: RemovePreview {
+            path: path.to_path_buf(),
+            references,
+            changes,
+        },
+        path_canonical,
+        replacements_by_file,
+        scanned_hashes,
+    })
+}
+
+/// Plan rewrites that replace every reference to `target` with plain text.
+fn plan_unlink_replacements(references: &[Reference], target: &Path) -> Result<ReplacementPlan> {
+    let mut references_by_file: BTreeMap<&Path, Vec<&Reference>> = BTreeMap::new();
+    for reference in references {
+        references_by_file
+            .entry(&reference.path)
+            .or_default()
+            .push(reference);
+    }
+
+    let mut replacements_by_file = ReplacementPlan::new();
+    for (file_path, references) in references_by_file {
+        let content = fs::read_to_string(file_path).map_err(|e| MdrefError::IoRead {
+            path: file_path.to_path_buf(),
+            source: e,
+        })?;
+        let lines: Vec<&str> = content.lines().collect();
+        let unlink_error = |line: usize, reason: &str| MdrefError::PathValidation {
+            path: file_path.to_path_buf(),
+            details: format!("cannot unlink the link in line {line}: {reason}"),
+        };
+        let mut replacements = Vec::new();
+
+        // Inline links and the uses of reference definitions are both link
+        // nodes, so one pass over the document covers them.
+        if references.iter().any(|reference| {
+            matches!(
+                reference.link_type,
+                LinkType::Inline | LinkType::ReferenceDefinition
+            )
+        }) {
+            let accept = |url: &str| {
+                link_matches_target(file_path, Some(target), None, url, &LinkType::Inline)
+            };
+            for (line, start, end) in find_link_spans(&content, file_path, &accept) {
+                if start == end {
+                    return Err(unlink_error(line, "it spans several lines"));
+                }
+                let span = &lines[line - 1][start..end];
+                replacements.push(LinkReplacement {
+                    line,
+                    column: start + 1,
+                    old_pattern: span.to_string(),
+                    new_pattern: displayed_text(span).to_string(),
+                });
+            }
+        }
+
+        for reference in references {
+            let line = lines[reference.line - 1];
+            match reference.link_type {
+                LinkType::Inline => {}
+                LinkType::ReferenceDefinition => replacements.push(LinkReplacement {
+                    line: reference.line,
+                    column: reference.column,
+                    old_pattern: line[reference.column - 1..].to_string(),
+                    new_pattern: String::new(),
+                }),
+                LinkType::WikiLink => {
+                    let (start, end, text) = find_wikilink_span(line, reference.column)
+                        .ok_or_else(|| unlink_error(reference.line, "unparsable wikilink"))?;
+                    replacements.push(LinkReplacement {
+                        line: reference.line,
+                        column: start + 1,
+                        old_pattern: line[start..end].to_string(),
+                        new_pattern: text.to_string(),
+                    });
+                }
+                LinkType::HtmlAttribute => {
+                    return Err(unlink_error(
+                        reference.line,
+                        "links in raw HTML must be redirected or edited by hand",
+                    ));
+                }
+                LinkType::Import => {
+                    return Err(unlink_error(
+                        reference.line,
+                        "imports must be redirected or edited by hand",
+                    ));
+                }
+            }
+        }
+
+        replacements_by_file.insert(file_path.to_path_buf(), replacements);
+    }
+
+    Ok(replacements_by_file)
+}
+
+/// The text a Markdown link or image displays, e.g. `text` for
+/// `[text](url)`, `![text](url)` and `[text][label]`.
+fn displayed_text(span: &str) -> &str {
+    let inner = span.strip_prefix('!').unwrap_or(span);
+    let inner = inner.strip_prefix('[').unwrap_or(inner);
+    match inner.find("](").or_else(|| inner.find("][")) {
+        Some(end) => &inner[..end],
+        None => inner.strip_suffix(']').unwrap_or(inner),
+    }
+}
+
+/// Plan rewrites that point every reference at `redirect` (a canonical path).
+fn plan_redirect_replacements(
+    references: &[Reference],
+    redirect: &Path,
+    root: &Path,
+) -> Result<ReplacementPlan> {
+    let vault = WikiVault::new(root);
+    let mut replacements_by_file = ReplacementPlan::new();
+    let mut line_cache = LineCache::new();
+
+    for reference in references {
+        let new_target = if reference.link_type == LinkType::WikiLink {
+            let (link_path_only, _anchor) = split_link_and_anchor(&reference.link_text);
+            vault
+                .retarget(&absolute(&reference.path), link_path_only, redirect)
+                .unwrap_or_else(|| link_path_only.to_string())
+        } else {
+            relative_path(&absolute(&reference.path), redirect)?
+                .display()
+                .to_string()
+        };
+
+        replacements_by_file
+            .entry(reference.path.clone())
+            .or_default()
+            .push(build_replacement(reference, &new_target, &mut line_cache)?);
+    }
+
+    Ok(replacements_by_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::inline("[Guide](gone.md)", "Guide")]
+    #[case::image("![Logo](gone.png)", "Logo")]
+    #[case::reference("[Guide][gone]", "Guide")]
+    #[case::collapsed("[gone][]", "gone")]
+    #[case::shortcut("[gone]", "gone")]
+    #[case::nested_brackets("[see [this]](gone.md)", "see [this]")]
+    fn test_displayed_text(#[case] span: &str, #[case] expected: &str) {
+        assert_eq!(displayed_text(span), expected);
+    }
+}
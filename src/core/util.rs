@@ -1,17 +1,60 @@
-use std::path::{Path, PathBuf};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 use ignore::WalkBuilder;
 
-use crate::{MdrefError, Result, core::pathdiff::diff_paths};
+use crate::{
+    MdrefError, Result,
+    core::{config::ScanFilter, pathdiff::diff_paths},
+};
 
 /// Collect markdown files while respecting ignore files such as `.gitignore`.
 ///
 /// Returned paths are normalised: a leading `./` prefix (produced by
 /// `WalkBuilder` when `root` is `"."`) is stripped so that path shapes
 /// match user-supplied relative paths.
+///
+/// The `[scan]` settings of the `.mdref.toml` governing `root` decide which
+/// extensions count as Markdown, see [`crate::core::config`].
 pub fn collect_markdown_files(root: &Path) -> Vec<PathBuf> {
+    let filter = ScanFilter::for_root(root);
+    walk_files(root, &filter)
+        .into_iter()
+        .filter(|path| filter.is_markdown(path))
+        .collect()
+}
+
+/// Collect every regular file under `root`, with the same ignore handling and
+/// path normalisation as [`collect_markdown_files`].
+///
+/// The include and exclude patterns and the ignore-file setting of the
+/// `.mdref.toml` governing `root` apply.
+pub fn collect_files(root: &Path) -> Vec<PathBuf> {
+    walk_files(root, &ScanFilter::for_root(root))
+}
+
+fn walk_files(root: &Path, filter: &ScanFilter) -> Vec<PathBuf> {
     let mut builder = WalkBuilder::new(root);
     builder.standard_filters(true).require_git(false);
+    if !filter.scan.ignore_files {
+        builder
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .ignore(false)
+            .parents(false);
+    }
+    // Excluded directories are pruned instead of walked.
+    let prune = filter.clone();
+    builder.filter_entry(move |entry| {
+        let is_dir = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir());
+        !prune.is_excluded(entry.path(), is_dir)
+    });
 
     builder
         .build()
@@ -22,12 +65,12 @@ pub fn collect_markdown_files(root: &Path) -> Vec<PathBuf> {
                 .is_some_and(|file_type| file_type.is_file())
         })
         .map(|entry| entry.into_path())
+        .filter(|path| filter.is_included(path))
         .map(|path| {
             path.strip_prefix("./")
                 .map(|stripped| stripped.to_path_buf())
                 .unwrap_or(path)
         })
-        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("md"))
         .collect()
 }
 
@@ -102,6 +145,7 @@ pub fn url_decode_link(link: &str) -> String {
 /// Returns `true` for:
 /// - URLs with scheme containing `://` (http://, https://, ftp://, etc.) except `file://`
 /// - Common URI schemes without `://` (mailto:, tel:, data:, javascript:)
+/// - Protocol-relative URLs (`//host/path`)
 ///
 /// Returns `false` for:
 /// - Local file paths (relative or absolute)
@@ -143,6 +187,11 @@ pub fn is_external_url(link: &str) -> bool {
         }
     }
 
+    // Protocol-relative URLs (`//cdn.example.com/logo.png`), common in raw HTML
+    if link.starts_with("//") {
+        return true;
+    }
+
     // file:// is a local file, not external
     if link_lower.starts_with("file://") {
         return false;
@@ -165,6 +214,20 @@ pub fn is_external_url(link: &str) -> bool {
     false
 }
 
+/// Hash file contents with 64-bit FNV-1a, rendered as 16 hex digits.
+///
+/// The hash is stable across platforms and releases, so it can be persisted
+/// to detect whether a file changed.
+pub fn content_hash(bytes: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{hash:016x}")
+}
+
 /// Strip the anchor (fragment) from a link URL.
 /// For example, "file.md#section" becomes "file.md", and "#section" returns None.
 pub fn strip_anchor(link: &str) -> Option<&str> {
@@ -239,6 +302,122 @@ pub fn resolve_parent(dir: &Path) -> Result<PathBuf> {
     }
 }
 
+/// Resolve `path` to an absolute path with a canonical parent. The file name
+/// keeps its spelling, so both sides of a case-only rename stay distinct.
+pub fn resolve_keeping_name(path: &Path) -> Result<PathBuf> {
+    let path = crate::core::wikilink::normalize(&std::path::absolute(path)?);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => Ok(resolve_path(parent)?.join(name)),
+        _ => resolve_path(&path),
+    }
+}
+
+/// Map a path from before a set of `(source, destination)` moves to where the
+/// moves leave it.
+pub fn post_move_path(moves: &[(PathBuf, PathBuf)], path: &Path) -> PathBuf {
+    remap(
+        moves
+            .iter()
+            .map(|(source, destination)| (source, destination)),
+        path,
+    )
+}
+
+/// Map a path from after a set of `(source, destination)` moves back to
+/// where it was before them.
+pub fn pre_move_path(moves: &[(PathBuf, PathBuf)], path: &Path) -> PathBuf {
+    remap(
+        moves
+            .iter()
+            .map(|(source, destination)| (destination, source)),
+        path,
+    )
+}
+
+fn remap<'a>(mut pairs: impl Iterator<Item = (&'a PathBuf, &'a PathBuf)>, path: &Path) -> PathBuf {
+    pairs
+        .find_map(|(from, to)| {
+            let rest = path.strip_prefix(from).ok()?;
+            // `join("")` would append a trailing separator.
+            Some(if rest.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                to.join(rest)
+            })
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Remove a file, or a directory with everything in it.
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Replace the content of `path` without ever leaving it truncated.
+///
+/// The content goes to a hidden sibling temp file that is flushed to disk and
+/// then renamed over `path`, so after a crash the file holds either its old or
+/// its new content. Permissions carry over, a symlink is followed so the link
+/// itself stays in place, and a read-only file is refused like a plain write.
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let target = if path.is_symlink() {
+        fs::canonicalize(path)?
+    } else {
+        path.to_path_buf()
+    };
+    let permissions = match fs::metadata(&target) {
+        Ok(metadata) if metadata.permissions().readonly() => {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "file is read-only",
+            ));
+        }
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error),
+    };
+
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = target.with_file_name(format!(".{name}.mdref-write"));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp_path, &target)?;
+        sync_parent_dir(&target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Flush the directory entry of `path` to disk so a rename or removal inside
+/// it survives a crash. A no-op where directories cannot be opened (Windows).
+pub fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -257,6 +436,14 @@ mod tests {
         assert!(is_external_url("ftp://files.example.com/doc.md"));
         assert!(is_external_url("sftp://secure.example.com/file"));
         assert!(is_external_url("git://github.com/user/repo.git"));
+        assert!(is_external_url("//cdn.example.com/logo.png"));
+    }
+
+    #[test]
+    fn test_content_hash_is_stable_fnv1a() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash(b"# A\n"), content_hash(b"# B\n"));
     }
 
     #[test]
@@ -514,4 +701,63 @@ mod tests {
     ) {
         assert_eq!(url_decode_link(input), expected);
     }
+
+    #[test]
+    fn test_post_and_pre_move_path_map_files_inside_moved_directory() {
+        let moves = vec![(PathBuf::from("/r/docs"), PathBuf::from("/r/manual"))];
+
+        assert_eq!(
+            post_move_path(&moves, Path::new("/r/docs/guide/a.md")),
+            PathBuf::from("/r/manual/guide/a.md")
+        );
+        assert_eq!(
+            post_move_path(&moves, Path::new("/r/docs")),
+            PathBuf::from("/r/manual")
+        );
+        assert_eq!(
+            post_move_path(&moves, Path::new("/r/docsets/a.md")),
+            PathBuf::from("/r/docsets/a.md")
+        );
+        assert_eq!(
+            pre_move_path(&moves, Path::new("/r/manual/a.md")),
+            PathBuf::from("/r/docs/a.md")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[allow(clippy::unwrap_used)]
+    fn test_write_atomic_replaces_content_and_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.md");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o640
+        );
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[allow(clippy::unwrap_used)]
+    fn test_write_atomic_writes_through_symlink() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("target.md");
+        let link = temp_dir.path().join("link.md");
+        fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, b"new").unwrap();
+
+        assert!(link.is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    }
 }
@@ -0,0 +1,755 @@
+//! Obsidian-style wikilinks: `[[Page]]`, `[[folder/Page|alias]]`,
+//! `[[Page#Heading]]`, and embeds such as `![[diagram.png]]`.
+//!
+//! Wikilinks are scanned from raw text rather than the comrak AST, because
+//! comrak does not recognise embeds; code blocks and code spans are skipped.
+//! Targets resolve with vault semantics (see [`WikiVault`]), so a bare
+//! `[[Page]]` keeps working wherever `Page.md` lives under the root.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use comrak::nodes::Sourcepos;
+
+use super::{config::ScanConfig, pathdiff::diff_paths, util::collect_files};
+
+/// A wikilink or embed found in a document's source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WikiLinkMatch {
+    /// 1-based line number.
+    pub(crate) line: usize,
+    /// 1-based byte column of the opening `[[`, or of the `!` for embeds.
+    pub(crate) column: usize,
+    /// The link target without its `|alias`, e.g. `folder/Page#Heading`.
+    pub(crate) target: String,
+}
+
+/// Scan `content` for wikilinks, skipping `ignored_lines` (code blocks) and
+/// any link that starts inside one of `code_spans`.
+pub(crate) fn scan_wikilinks(
+    content: &str,
+    ignored_lines: &HashSet<usize>,
+    code_spans: &[Sourcepos],
+) -> Vec<WikiLinkMatch> {
+    let mut links = Vec::new();
+
+    for (line_index, line) in content.lines().enumerate() {
+        let line_number = line_index + 1;
+        if ignored_lines.contains(&line_number) {
+            continue;
+        }
+
+        let mut search_from = 0;
+        while let Some(offset) = line[search_from..].find("[[") {
+            let open = search_from + offset;
+            let escaped = line[..open].ends_with('\\');
+            let Some((start, end, close_end)) = parse_wikilink_at(line, open).filter(|_| !escaped)
+            else {
+                search_from = open + 2;
+                continue;
+            };
+            search_from = close_end;
+
+            if in_code_span(code_spans, line_number, open + 1) {
+                continue;
+            }
+
+            let column = if line[..open].ends_with('!') {
+                open
+            } else {
+                open + 1
+            };
+            links.push(WikiLinkMatch {
+                line: line_number,
+                column,
+                target: line[start..end].to_string(),
+            });
+        }
+    }
+
+    links
+}
+
+/// Locate the target span of the wikilink starting at `column` in `line`.
+///
+/// `column` is the 1-based column reported by [`scan_wikilinks`]. Returns
+/// `(start, end)` as byte offsets into `line`; the `|alias` is excluded.
+pub(crate) fn find_wikilink_target_span(line: &str, column: usize) -> Option<(usize, usize)> {
+    let start = column.checked_sub(1)?;
+    let rest = line.get(start..)?;
+    let open = if rest.starts_with('!') {
+        start + 1
+    } else {
+        start
+    };
+    if !line[open..].starts_with("[[") {
+        return None;
+    }
+
+    parse_wikilink_at(line, open).map(|(start, end, _)| (start, end))
+}
+
+/// Locate the whole wikilink starting at `column` in `line`, including the
+/// `!` of an embed, and the text it displays.
+///
+/// Returns `(start, end, text)` with byte offsets into `line`; `text` is the
+/// alias, or the target without its `#fragment` when there is none. An
+/// embed's `|` part is a size or caption rather than an alias, so an embed
+/// always displays its target.
+pub(crate) fn find_wikilink_span(line: &str, column: usize) -> Option<(usize, usize, &str)> {
+    let start = column.checked_sub(1)?;
+    let open = if line.get(start..)?.starts_with('!') {
+        start + 1
+    } else {
+        start
+    };
+    if !line[open..].starts_with("[[") {
+        return None;
+    }
+
+    let (target_start, target_end, end) = parse_wikilink_at(line, open)?;
+    let inner = &line[open + 2..end - 2];
+    let alias = inner
+        .find('|')
+        .filter(|_| open == start)
+        .map(|pipe| inner[pipe + 1..].trim())
+        .filter(|alias| !alias.is_empty());
+    let text = alias.unwrap_or_else(|| {
+        let target = &line[target_start..target_end];
+        target.split('#').next().unwrap_or(target).trim_end()
+    });
+    Some((start, end, text))
+}
+
+/// Parse the wikilink whose `[[` starts at byte `open`.
+///
+/// Returns the trimmed target span and the byte offset just past `]]`. An
+/// escaped pipe (`\|`, as used inside tables) ends the target too. A target
+/// with `[` or `]` cannot name a note, so nested brackets in prose such as
+/// `[[1, 2], [3, 4]]` are not a wikilink.
+fn parse_wikilink_at(line: &str, open: usize) -> Option<(usize, usize, usize)> {
+    let inner_start = open + 2;
+    let close = inner_start + line[inner_start..].find("]]")?;
+    let inner = &line[inner_start..close];
+    if inner.contains("[[") {
+        return None;
+    }
+
+    let mut target_end = inner.find('|').map_or(close, |pipe| inner_start + pipe);
+    if line[..target_end].ends_with('\\') {
+        target_end -= 1;
+    }
+
+    let raw = &line[inner_start..target_end];
+    if raw.contains(['[', ']']) {
+        return None;
+    }
+    let start = inner_start + (raw.len() - raw.trim_start().len());
+    let end = inner_start + raw.trim_end().len();
+    (start < end).then_some((start, end, close + 2))
+}
+
+fn in_code_span(code_spans: &[Sourcepos], line: usize, column: usize) -> bool {
+    code_spans.iter().any(|span| {
+        (span.start.line, span.start.column) <= (line, column)
+            && (line, column) <= (span.end.line, span.end.column)
+    })
+}
+
+/// An index of every file under a root, used to resolve wikilink targets.
+///
+/// Resolution follows Obsidian's rules:
+///
+/// - `./` and `../` targets are relative to the linking file
+/// - targets containing `/` match a path from the root first, then a path suffix
+/// - bare names match any file with that name; when several do, the one in the
+///   linking file's directory wins, then the one closest to the root
+///
+/// Matching is case-insensitive and a Markdown extension may be omitted; a
+/// target without one tries each of the root's `scan.markdown_extensions`
+/// in order.
+#[derive(Debug, Clone)]
+pub struct WikiVault {
+    root: PathBuf,
+    /// The Markdown extensions of the root, lowercased and without a dot.
+    extensions: Vec<String>,
+    /// `/`-separated paths relative to `root`, sorted.
+    entries: Vec<String>,
+    /// Lowercased `entries`, used for matching.
+    keys: Vec<String>,
+    by_path: HashMap<String, usize>,
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl WikiVault {
+    /// Index every file under `root`, respecting ignore files.
+    pub fn new(root: &Path) -> Self {
+        Self::from_files(root, &collect_files(root))
+    }
+
+    /// Index `files`, which were collected under `root`.
+    pub(crate) fn from_files(root: &Path, files: &[PathBuf]) -> Self {
+        let root_relative = normalize(root);
+        let entries = files
+            .iter()
+            .filter_map(|file| {
+                let normalized = normalize(file);
+                path_to_slash(normalized.strip_prefix(&root_relative).ok()?)
+            })
+            .collect();
+
+        let extensions = ScanConfig::for_path(root)
+            .markdown_extensions
+            .iter()
+            .map(|extension| extension.trim_start_matches('.').to_lowercase())
+            .collect();
+
+        Self::from_entries(absolute(root), extensions, entries)
+    }
+
+    fn from_entries(root: PathBuf, extensions: Vec<String>, mut entries: Vec<String>) -> Self {
+        entries.sort();
+        entries.dedup();
+
+        let keys: Vec<String> = entries.iter().map(|entry| entry.to_lowercase()).collect();
+        let mut by_path = HashMap::new();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            by_path.insert(key.clone(), index);
+            let name = key.rsplit('/').next().unwrap_or(key);
+            by_name.entry(name.to_string()).or_default().push(index);
+        }
+
+        Self {
+            root,
+            extensions,
+            entries,
+            keys,
+            by_path,
+            by_name,
+        }
+    }
+
+    /// Resolve the wikilink `target` (without its `#fragment`) written in
+    /// `base_file` to an absolute path under the root.
+    pub fn resolve(&self, base_file: &Path, target: &str) -> Option<PathBuf> {
+        let target = target.trim();
+        if target.is_empty() {
+            return None;
+        }
+
+        let base_file = absolute(base_file);
+        let base_dir = self
+            .relative(&base_file)
+            .map(|path| parent_of(&path).to_lowercase());
+
+        let index = self
+            .candidate_names(target)
+            .iter()
+            .find_map(|candidate| self.lookup(&base_file, base_dir.as_deref(), candidate))?;
+        Some(self.root.join(&self.entries[index]))
+    }
+
+    /// Return the link text that makes a wikilink in `base_file` point at
+    /// `target`, or `None` when `original` already resolves there.
+    ///
+    /// The style of `original` is kept: relative targets stay relative, paths
+    /// stay paths, bare names stay bare while the file name is unique, and a
+    /// Markdown extension is only written if `original` wrote one.
+    pub(crate) fn retarget(
+        &self,
+        base_file: &Path,
+        original: &str,
+        target: &Path,
+    ) -> Option<String> {
+        if self.resolve(base_file, original).as_deref() == Some(target) {
+            return None;
+        }
+
+        let relative = relative_text(base_file, target);
+        let preferred = match self.relative(target) {
+            Some(vault_path) if !is_relative_target(original) => {
+                let name = vault_path.rsplit('/').next().unwrap_or(&vault_path);
+                let unique = self
+                    .by_name
+                    .get(&name.to_lowercase())
+                    .is_some_and(|matches| matches.len() == 1);
+                if unique && !original.contains('/') {
+                    name.to_string()
+                } else {
+                    vault_path
+                }
+            }
+            _ => relative.clone(),
+        };
+
+        let preferred = self.apply_extension_style(preferred, target, original);
+        if self.resolve(base_file, &preferred).as_deref() == Some(target) {
+            Some(preferred)
+        } else {
+            Some(self.apply_extension_style(relative, target, original))
+        }
+    }
+
+    /// The vault as it will look after every `(from, to)` move (of a file or
+    /// directory) in `moves`.
+    fn moved(&self, moves: &[(PathBuf, PathBuf)]) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let moved = remap_all(&self.root.join(entry), moves);
+                path_to_slash(moved.strip_prefix(&self.root).ok()?)
+            })
+            .collect();
+
+        Self::from_entries(self.root.clone(), self.extensions.clone(), entries)
+    }
+
+    /// The paths `target` may name: with each Markdown extension appended,
+    /// then as written, unless it already has a Markdown extension.
+    fn candidate_names(&self, target: &str) -> Vec<String> {
+        if self.markdown_extension(target).is_some() {
+            return vec![target.to_string()];
+        }
+        self.extensions
+            .iter()
+            .map(|extension| format!("{target}.{extension}"))
+            .chain([target.to_string()])
+            .collect()
+    }
+
+    /// The Markdown extension `path` ends with, if any.
+    fn markdown_extension(&self, path: &str) -> Option<&str> {
+        let (_, extension) = path.rsplit_once('.')?;
+        self.extensions
+            .iter()
+            .find(|known| known.eq_ignore_ascii_case(extension))
+            .map(String::as_str)
+    }
+
+    /// Drop the Markdown extension from `text`, a link to `target`, unless
+    /// `original` wrote one.
+    fn apply_extension_style(&self, text: String, target: &Path, original: &str) -> String {
+        let target_is_markdown = target
+            .to_str()
+            .is_some_and(|target| self.markdown_extension(target).is_some());
+        match self.markdown_extension(&text) {
+            Some(extension)
+                if target_is_markdown && self.markdown_extension(original.trim()).is_none() =>
+            {
+                text[..text.len() - extension.len() - 1].to_string()
+            }
+            _ => text,
+        }
+    }
+
+    fn lookup(&self, base_file: &Path, base_dir: Option<&str>, candidate: &str) -> Option<usize> {
+        if is_relative_target(candidate) {
+            let resolved = normalize(&base_file.parent()?.join(candidate));
+            let key = path_to_slash(resolved.strip_prefix(&self.root).ok()?)?;
+            return self.by_path.get(&key.to_lowercase()).copied();
+        }
+
+        let key = candidate.trim_start_matches('/').to_lowercase();
+        let matches: Vec<usize> = if key.contains('/') {
+            if let Some(&index) = self.by_path.get(&key) {
+                return Some(index);
+            }
+            let suffix = format!("/{key}");
+            self.keys
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.ends_with(&suffix))
+                .map(|(index, _)| index)
+                .collect()
+        } else {
+            self.by_name.get(&key).cloned().unwrap_or_default()
+        };
+
+        matches.into_iter().min_by_key(|&index| {
+            let key = &self.keys[index];
+            (
+                Some(parent_of(key)) != base_dir,
+                key.matches('/').count(),
+                index,
+            )
+        })
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        path_to_slash(path.strip_prefix(&self.root).ok()?)
+    }
+}
+
+/// Plans wikilink rewrites for moving `from` to `to` (both canonical paths),
+/// or for several such moves at once.
+pub(crate) struct VaultMove {
+    before: WikiVault,
+    after: WikiVault,
+    moves: Vec<(PathBuf, PathBuf)>,
+}
+
+impl VaultMove {
+    pub(crate) fn new(root: &Path, from: &Path, to: &Path) -> Self {
+        Self::new_many(root, vec![(from.to_path_buf(), to.to_path_buf())])
+    }
+
+    /// Plan for a batch of non-overlapping `(from, to)` moves applied together.
+    pub(crate) fn new_many(root: &Path, moves: Vec<(PathBuf, PathBuf)>) -> Self {
+        let before = WikiVault::new(root);
+        let after = before.moved(&moves);
+        Self {
+            before,
+            after,
+            moves,
+        }
+    }
+
+    /// Plan for `moves` that were already made on disk, e.g. with `git mv`:
+    /// the vault before them is reconstructed from the one on disk.
+    pub(crate) fn already_moved(root: &Path, moves: Vec<(PathBuf, PathBuf)>) -> Self {
+        let after = WikiVault::new(root);
+        let reversed: Vec<(PathBuf, PathBuf)> = moves
+            .iter()
+            .map(|(from, to)| (to.clone(), from.clone()))
+            .collect();
+        let before = after.moved(&reversed);
+        Self {
+            before,
+            after,
+            moves,
+        }
+    }
+
+    /// Return the new target for the wikilink `target` written in `base_file`
+    /// (a pre-move path), or `None` if the link needs no change.
+    pub(crate) fn rewrite(&self, base_file: &Path, target: &str) -> Option<String> {
+        let base_before = absolute(base_file);
+        let target_before = self.before.resolve(&base_before, target)?;
+        self.after.retarget(
+            &remap_all(&base_before, &self.moves),
+            target,
+            &remap_all(&target_before, &self.moves),
+        )
+    }
+}
+
+/// Plans wikilink rewrites inside a copy of `from` at `to` (both canonical
+/// paths), whose original stays where it is.
+pub(crate) struct VaultCopy {
+    before: WikiVault,
+    after: WikiVault,
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl VaultCopy {
+    pub(crate) fn new(root: &Path, from: &Path, to: &Path) -> Self {
+        let before = WikiVault::new(root);
+        let copied = before.moved(&[(from.to_path_buf(), to.to_path_buf())]);
+        let mut entries = before.entries.clone();
+        entries.extend(copied.entries);
+        let after =
+            WikiVault::from_entries(before.root.clone(), before.extensions.clone(), entries);
+        Self {
+            before,
+            after,
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        }
+    }
+
+    /// Return the new target for the wikilink `target` written in the
+    /// original `base_file`, as it should read in the copy of that file, or
+    /// `None` if the link needs no change.
+    ///
+    /// A target inside the copied tree points at its copy when `to_copy` is
+    /// set and at the original otherwise; any other target is kept.
+    pub(crate) fn rewrite(&self, base_file: &Path, target: &str, to_copy: bool) -> Option<String> {
+        let base_before = absolute(base_file);
+        let target_before = self.before.resolve(&base_before, target)?;
+        let target_after = if to_copy {
+            remap(&target_before, &self.from, &self.to)
+        } else {
+            target_before
+        };
+        self.after.retarget(
+            &remap(&base_before, &self.from, &self.to),
+            target,
+            &target_after,
+        )
+    }
+}
+
+fn is_relative_target(target: &str) -> bool {
+    target.starts_with("./") || target.starts_with("../")
+}
+
+fn relative_text(base_file: &Path, target: &Path) -> String {
+    let relative = base_file
+        .parent()
+        .and_then(|base_dir| diff_paths(target, base_dir))
+        .and_then(|path| path_to_slash(&path))
+        .unwrap_or_default();
+    if relative.starts_with("../") {
+        relative
+    } else {
+        format!("./{relative}")
+    }
+}
+
+/// Apply the first move in `moves` whose source is `path` or one of its ancestors.
+fn remap_all(path: &Path, moves: &[(PathBuf, PathBuf)]) -> PathBuf {
+    moves
+        .iter()
+        .find(|(from, _)| path.starts_with(from))
+        .map_or_else(|| path.to_path_buf(), |(from, to)| remap(path, from, to))
+}
+
+fn remap(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) if rest.as_os_str().is_empty() => to.to_path_buf(),
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn parent_of(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// Join the components of `path` with `/`, or `None` for non-UTF-8 paths.
+pub(crate) fn path_to_slash(path: &Path) -> Option<String> {
+    let parts = path
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+/// Canonicalize `path`, falling back to a lexically normalized absolute path
+/// when it does not exist.
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::path::absolute(path)
+            .map(|path| normalize(&path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+/// Lexically resolve `.` and `..` components.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::test_utils::write_file;
+
+    fn targets(content: &str) -> Vec<(usize, usize, String)> {
+        scan_wikilinks(content, &HashSet::new(), &[])
+            .into_iter()
+            .map(|link| (link.line, link.column, link.target))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_wikilinks_reads_targets_aliases_and_embeds() {
+        let content = "See [[Some Note]] and ![[diagram.png]]\n[[folder/Note|alias]] [[Note#Heading]]\n| [[Table\\|cell]] |\n";
+
+        assert_eq!(
+            targets(content),
+            vec![
+                (1, 5, "Some Note".to_string()),
+                (1, 23, "diagram.png".to_string()),
+                (2, 1, "folder/Note".to_string()),
+                (2, 23, "Note#Heading".to_string()),
+                (3, 3, "Table".to_string()),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::empty("[[]] [[ |alias]]")]
+    #[case::unclosed("[[Note")]
+    #[case::escaped("\\[[Note]]")]
+    #[case::nested_brackets("[[1, 2], [3, 4]] and [[a]b]]")]
+    fn test_scan_wikilinks_ignores_invalid_links(#[case] content: &str) {
+        assert!(targets(content).is_empty());
+    }
+
+    #[test]
+    fn test_scan_wikilinks_skips_ignored_lines_and_code_spans() {
+        let mut ignored_lines = HashSet::new();
+        ignored_lines.insert(2);
+        let code_span = Sourcepos::from((1, 1, 1, 10));
+
+        let links = scan_wikilinks(
+            "`[[code]]` [[Real]]\n[[Fenced]]\n",
+            &ignored_lines,
+            &[code_span],
+        );
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, "Real");
+    }
+
+    #[rstest]
+    #[case::plain("a [[Note]] b", 3, "Note")]
+    #[case::embed("![[img.png|200]]", 1, "img.png")]
+    #[case::padded("[[ Spaced | alias ]]", 1, "Spaced")]
+    #[allow(clippy::unwrap_used)]
+    fn test_find_wikilink_target_span(
+        #[case] line: &str,
+        #[case] column: usize,
+        #[case] expected: &str,
+    ) {
+        let (start, end) = find_wikilink_target_span(line, column).unwrap();
+        assert_eq!(&line[start..end], expected);
+    }
+
+    #[rstest]
+    #[case::plain("a [[Note]] b", 3, "[[Note]]", "Note")]
+    #[case::fragment("[[Note#Heading]]", 1, "[[Note#Heading]]", "Note")]
+    #[case::alias("[[ Spaced | alias ]]", 1, "[[ Spaced | alias ]]", "alias")]
+    #[case::embed("x ![[img.png|200]]", 3, "![[img.png|200]]", "img.png")]
+    #[allow(clippy::unwrap_used)]
+    fn test_find_wikilink_span(
+        #[case] line: &str,
+        #[case] column: usize,
+        #[case] expected_span: &str,
+        #[case] expected_text: &str,
+    ) {
+        let (start, end, text) = find_wikilink_span(line, column).unwrap();
+        assert_eq!(&line[start..end], expected_span);
+        assert_eq!(text, expected_text);
+    }
+
+    #[allow(clippy::unwrap_used)]
+    fn vault_with(files: &[&str]) -> (TempDir, WikiVault) {
+        let temp_dir = TempDir::new().unwrap();
+        for file in files {
+            write_file(temp_dir.path().join(file), "");
+        }
+        let vault = WikiVault::new(temp_dir.path());
+        (temp_dir, vault)
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_wiki_vault_resolves_names_paths_and_relative_targets() {
+        let (temp_dir, vault) = vault_with(&[
+            "index.md",
+            "notes/Topic.md",
+            "notes/deep/Other.md",
+            "assets/diagram.png",
+        ]);
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+
+        assert_eq!(
+            vault.resolve(&index, "topic"),
+            Some(root.join("notes/Topic.md"))
+        );
+        assert_eq!(
+            vault.resolve(&index, "deep/Other"),
+            Some(root.join("notes/deep/Other.md"))
+        );
+        assert_eq!(
+            vault.resolve(&index, "notes/Topic.md"),
+            Some(root.join("notes/Topic.md"))
+        );
+        assert_eq!(
+            vault.resolve(&index, "diagram.png"),
+            Some(root.join("assets/diagram.png"))
+        );
+        assert_eq!(
+            vault.resolve(&root.join("notes/Topic.md"), "./deep/Other"),
+            Some(root.join("notes/deep/Other.md"))
+        );
+        assert_eq!(vault.resolve(&index, "Missing"), None);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_wiki_vault_prefers_same_directory_then_shallowest() {
+        let (temp_dir, vault) = vault_with(&["Note.md", "a/Note.md", "a/b/Note.md", "a/b/x.md"]);
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        assert_eq!(
+            vault.resolve(&root.join("a/b/x.md"), "Note"),
+            Some(root.join("a/b/Note.md"))
+        );
+        assert_eq!(
+            vault.resolve(&root.join("elsewhere.md"), "Note"),
+            Some(root.join("Note.md"))
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_wiki_vault_tries_markdown_extensions_in_configured_order() {
+        let (temp_dir, vault) = vault_with(&["page.md", "page.markdown", "only.MDX"]);
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+
+        assert_eq!(vault.resolve(&index, "page"), Some(root.join("page.md")));
+        assert_eq!(vault.resolve(&index, "only"), Some(root.join("only.MDX")));
+
+        write_file(
+            root.join(".mdref.toml"),
+            "[scan]\nmarkdown_extensions = [\"markdown\"]\n",
+        );
+        let vault = WikiVault::new(&root);
+
+        assert_eq!(
+            vault.resolve(&index, "page"),
+            Some(root.join("page.markdown"))
+        );
+        assert_eq!(vault.resolve(&index, "only"), None);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_vault_move_rewrites_only_links_that_stop_resolving() {
+        let (temp_dir, _vault) = vault_with(&["index.md", "Note.md", "other/Note.md"]);
+        let root = temp_dir.path().canonicalize().unwrap();
+        let index = root.join("index.md");
+
+        let unchanged = VaultMove::new(&root, &root.join("index.md"), &root.join("home.md"));
+        assert_eq!(unchanged.rewrite(&index, "Note"), None);
+
+        let shadowed = VaultMove::new(&root, &root.join("Note.md"), &root.join("x/deep/Note.md"));
+        assert_eq!(
+            shadowed.rewrite(&index, "Note"),
+            Some("x/deep/Note".to_string())
+        );
+
+        let renamed = VaultMove::new(&root, &root.join("Note.md"), &root.join("Renamed.md"));
+        assert_eq!(renamed.rewrite(&index, "Note"), Some("Renamed".to_string()));
+        assert_eq!(
+            renamed.rewrite(&index, "Note.md"),
+            Some("Renamed.md".to_string())
+        );
+        assert_eq!(renamed.rewrite(&index, "other/Note"), None);
+    }
+}
@@ -29,9 +29,45 @@ pub enum MdrefError {
         details: String,
     },
 
+    #[error("Invalid configuration '{path}': {details}")]
+    Config { path: PathBuf, details: String },
+
     #[error("Serialization failed: {details}")]
     SerializationFailed { details: String },
 
+    #[error("Language server error: {details}")]
+    LanguageServer { details: String },
+
+    #[error("{operation} changes files only when called with `confirm: true`")]
+    ConfirmationRequired { operation: String },
+
+    #[error("Failed to watch '{path}': {details}")]
+    Watch { path: PathBuf, details: String },
+
+    #[error("Found {count} broken link(s)")]
+    BrokenLinks { count: usize },
+
+    #[error("'{path}' is still referenced by {count} link(s)")]
+    StillReferenced { path: PathBuf, count: usize },
+
+    #[error(
+        "{} file(s) changed since they were scanned: {}",
+        paths.len(),
+        join_paths(paths)
+    )]
+    ConcurrentModification { paths: Vec<PathBuf> },
+
+    #[error("git failed in '{path}': {details}")]
+    Git { path: PathBuf, details: String },
+
+    #[error("'{path}' has uncommitted changes; commit or stash them, or force the move")]
+    UncommittedChanges { path: PathBuf },
+
+    #[error(
+        "An interrupted {operation} was found in '{path}'; run `mdref recover` to roll it forward or back"
+    )]
+    InterruptedOperation { path: PathBuf, operation: String },
+
     #[error("Operation failed and rollback also failed: {original_error}; rollback errors: {}", rollback_errors.join("; "))]
     RollbackFailed {
         original_error: String,
@@ -40,3 +76,11 @@ pub enum MdrefError {
 }
 
 pub type Result<T> = std::result::Result<T, MdrefError>;
+
+fn join_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -5,12 +5,26 @@ mod error;
 pub mod test_utils;
 
 pub use core::{
-    find::{find_links, find_references},
-    model::{LinkType, Reference},
-    mv::{mv, preview_move},
+    check::check_links,
+    cp::{IntraCopyLinks, cp, preview_copy},
+    find::{find_links, find_references, find_references_many},
+    fix::{fix, fix_from_git, fix_moved, preview_fix, preview_fix_from_git, preview_fix_moved},
+    graph::build_link_graph,
+    index::{build_index, clear_index, index_status},
+    journal::{history, undo},
+    model::{
+        BrokenLink, BrokenLinkKind, CopyPreview, FixMatch, FixPreview, GraphEdge, HistoryEntry,
+        IndexStatus, InterruptedOperation, LinkFix, LinkGraph, LinkType, MovePreview, OrphanReport,
+        Reference, RemovePreview,
+    },
+    mv::{GitMode, apply_move_preview, mv, mv_many, mv_with_git, preview_move, preview_move_many},
+    orphans::{DEFAULT_ENTRYPOINTS, find_orphans},
     pathdiff::diff_paths,
     progress::{NoopProgress, ProgressReporter},
+    recover::{RecoveryAction, interrupted_operation, recover, transaction_log_path},
     rename::rename,
+    rename_heading::{preview_rename_heading, rename_heading},
+    rm::{RemoveMode, preview_remove, rm},
 };
 
 pub use error::{MdrefError, Result};
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use serde::Serialize;
 
 mod commands;
@@ -58,10 +58,24 @@ fn emit_error(context: &commands::CommandOutputContext, error: &mdref::MdrefErro
 }
 
 fn main() {
-    let cli = Cli::parse();
+    // An unreadable configuration must not break `--help` or `--version`, so
+    // it is only reported once a command is about to run.
+    let (command, config_error) = match commands::config::discover()
+        .and_then(|config| commands::config::apply_defaults(Cli::command(), config.as_ref()))
+    {
+        Ok(command) => (command, None),
+        Err(e) => (Cli::command(), Some(e)),
+    };
+    let matches = command.get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     let output_context = cli.command.output_context();
 
+    if let Some(e) = config_error {
+        emit_error(&output_context, &e);
+        std::process::exit(1);
+    }
+
     if let Err(e) = commands::handle_command(cli.command, cli.progress) {
         emit_error(&output_context, &e);
         std::process::exit(1);
@@ -1,6 +1,6 @@
 mod common;
 
-use common::{read_file, run_cli, temp_dir, write_file};
+use common::{git, read_file, run_cli, run_cli_in, spawn_cli, temp_dir, write_file};
 use serde_json::Value;
 
 // CLI tests only cover process-level contracts: argument wiring, exit codes,
@@ -240,6 +240,46 @@ fn test_cli_mv_runtime_error_reports_exit_code_and_stderr() {
     assert!(stderr.contains("Error:"));
 }
 
+// ============= check command tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_check_clean_root_exits_successfully() {
+    let temp_dir = temp_dir();
+    write_file(temp_dir.path().join("index.md"), "[Guide](guide.md)");
+    write_file(temp_dir.path().join("guide.md"), "# Guide");
+
+    let output = run_cli(&["check", "--root", temp_dir.path().to_str().unwrap()]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No broken links found"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_check_broken_links_report_nonzero_exit_status() {
+    let temp_dir = temp_dir();
+    write_file(temp_dir.path().join("index.md"), "[Gone](gone.md)");
+
+    let output = run_cli(&[
+        "check",
+        "--root",
+        temp_dir.path().to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+
+    assert!(!output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["operation"], "check");
+    assert_eq!(payload["broken_links"].as_array().unwrap().len(), 1);
+
+    let error: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["operation"], "check");
+    assert!(error["error"].as_str().unwrap().contains("broken link"));
+}
+
 // ============= version and help =============
 
 #[test]
@@ -327,6 +367,557 @@ fn test_cli_rename_updates_references_e2e() {
     assert!(!ref_content.contains("old_doc.md"));
 }
 
+// ============= End-to-end: rename-heading with anchor update =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_rename_heading_updates_anchor_links_e2e() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let ref_file = temp_dir.path().join("index.md");
+    write_file(&guide, "# Guide\n\n## Getting Started\n");
+    write_file(&ref_file, "See [start](guide.md#getting-started).");
+
+    let output = run_cli(&[
+        "rename-heading",
+        guide.to_str().unwrap(),
+        "Getting Started",
+        "Quick Start",
+        "--root",
+        temp_dir.path().to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(read_file(&guide).contains("## Quick Start"));
+    assert_eq!(read_file(&ref_file), "See [start](guide.md#quick-start).");
+}
+
+// ============= End-to-end: cp with link rewrite =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_cp_rewrites_links_in_copy_e2e() {
+    let temp_dir = temp_dir();
+    let source = temp_dir.path().join("docs/v1");
+    write_file(
+        source.join("index.md"),
+        "[Setup](setup.md)\n[Home](../../README.md)\n",
+    );
+    write_file(source.join("setup.md"), "[Back](index.md)\n");
+    write_file(
+        temp_dir.path().join("README.md"),
+        "[Docs](docs/v1/index.md)\n",
+    );
+
+    let output = run_cli(&[
+        "cp",
+        source.to_str().unwrap(),
+        temp_dir.path().join("archive/v1").to_str().unwrap(),
+        "--to-copy",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        read_file(temp_dir.path().join("archive/v1/index.md")),
+        "[Setup](setup.md)\n[Home](../../README.md)\n"
+    );
+    assert_eq!(
+        read_file(temp_dir.path().join("README.md")),
+        "[Docs](docs/v1/index.md)\n"
+    );
+}
+
+// ============= End-to-end: rm refuses, then redirects =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_rm_refuses_then_redirects_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let gone = temp_dir.path().join("gone.md");
+    let index = temp_dir.path().join("index.md");
+    write_file(&gone, "# Gone\n");
+    write_file(temp_dir.path().join("new.md"), "# New\n");
+    write_file(&index, "[Gone](gone.md)\n");
+
+    let output = run_cli(&["rm", gone.to_str().unwrap(), "--root", root]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is still referenced by:"));
+    assert!(gone.exists());
+
+    let output = run_cli(&[
+        "rm",
+        gone.to_str().unwrap(),
+        "--root",
+        root,
+        "--redirect-to",
+        temp_dir.path().join("new.md").to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(!gone.exists());
+    assert_eq!(read_file(&index), "[Gone](new.md)\n");
+}
+
+// ============= End-to-end: fix after a plain move =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_fix_apply_repairs_link_after_plain_move_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let index = temp_dir.path().join("index.md");
+    write_file(temp_dir.path().join("guide.md"), "# Guide\n");
+    write_file(&index, "[Guide](guide.md)\n");
+    std::fs::create_dir(temp_dir.path().join("docs")).unwrap();
+    std::fs::rename(
+        temp_dir.path().join("guide.md"),
+        temp_dir.path().join("docs/guide.md"),
+    )
+    .unwrap();
+
+    let output = run_cli(&["fix", "--root", root, "--apply"]);
+
+    assert!(output.status.success());
+    assert_eq!(read_file(&index), "[Guide](docs/guide.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_fix_from_git_replays_git_mv_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    git(root, &["init", "--quiet"]);
+    git(root, &["add", "--all"]);
+    git(root, &["commit", "--quiet", "--message", "initial"]);
+    git(root, &["mv", "guide.md", "handbook.md"]);
+
+    let output = run_cli(&[
+        "fix",
+        "--root",
+        root.to_str().unwrap(),
+        "--from-git",
+        "HEAD",
+        "--apply",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(read_file(root.join("index.md")), "[Guide](handbook.md)\n");
+}
+
+// ============= End-to-end: graph around one file =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_graph_mermaid_around_file_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "[B](b.md)");
+    write_file(root.join("b.md"), "[C](c.md)");
+    write_file(root.join("c.md"), "# C");
+
+    let output = run_cli(&[
+        "graph",
+        "--root",
+        root.to_str().unwrap(),
+        "--around",
+        root.join("a.md").to_str().unwrap(),
+        "--format",
+        "mermaid",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "flowchart LR\n    n0[\"a.md\"]\n    n1[\"b.md\"]\n    n0 -->|\"inline 1:1\"| n1\n"
+    );
+}
+
+// ============= End-to-end: orphans report =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_orphans_json_lists_unlinked_page_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("README.md"), "[Guide](guide.md)");
+    write_file(root.join("guide.md"), "[Home](README.md)");
+    write_file(root.join("draft.md"), "[Guide](guide.md)");
+
+    let output = run_cli(&[
+        "orphans",
+        "--root",
+        root.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["orphans"], serde_json::json!(["draft.md"]));
+    assert_eq!(payload["unreachable"], serde_json::json!([["draft.md"]]));
+    assert_eq!(payload["dead_ends"], serde_json::json!([]));
+}
+
+// ============= End-to-end: find --all-assets =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_find_all_assets_lists_unused_assets_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    let assets = root.join("assets");
+    write_file(root.join("index.md"), "![Logo](assets/logo.png)");
+    write_file(assets.join("logo.png"), "png");
+    write_file(assets.join("old.png"), "png");
+
+    let output = run_cli(&[
+        "find",
+        "--all-assets",
+        assets.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let unused = stdout.split("Unused assets in").nth(1).unwrap();
+    assert!(unused.contains("old.png"));
+    assert!(!unused.contains("logo.png"));
+}
+
+// ============= End-to-end: lsp =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_lsp_answers_references_over_stdio_e2e() {
+    use std::io::BufReader;
+
+    use lsp_server::{Message, Notification, Request, RequestId};
+    use lsp_types::Url;
+
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Guide\n");
+    let guide_uri = Url::from_file_path(root.join("guide.md")).unwrap();
+
+    let mut child = spawn_cli(&["lsp", "--root", root.to_str().unwrap()]);
+    let mut stdin = child.stdin.take().unwrap();
+    let messages: Vec<Message> = vec![
+        Request::new(
+            RequestId::from(1),
+            "initialize".to_string(),
+            serde_json::json!({ "capabilities": {} }),
+        )
+        .into(),
+        Notification::new("initialized".to_string(), serde_json::json!({})).into(),
+        Request::new(
+            RequestId::from(2),
+            "textDocument/references".to_string(),
+            serde_json::json!({
+                "textDocument": { "uri": guide_uri },
+                "position": { "line": 0, "character": 0 },
+                "context": { "includeDeclaration": false },
+            }),
+        )
+        .into(),
+        Request::new(RequestId::from(3), "shutdown".to_string(), Value::Null).into(),
+        Notification::new("exit".to_string(), Value::Null).into(),
+    ];
+    for message in messages {
+        message.write(&mut stdin).unwrap();
+    }
+    drop(stdin);
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut references = None;
+    while let Some(message) = Message::read(&mut stdout).unwrap() {
+        if let Message::Response(response) = message
+            && response.id == RequestId::from(2)
+        {
+            references = response.result;
+        }
+    }
+
+    assert!(child.wait().unwrap().success());
+    let references = references.unwrap();
+    assert_eq!(references.as_array().unwrap().len(), 1);
+    assert_eq!(
+        references[0]["uri"],
+        Url::from_file_path(root.join("index.md")).unwrap().as_str()
+    );
+    assert_eq!(references[0]["range"]["start"]["character"], 8);
+}
+
+// ============= End-to-end: mcp =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_mcp_answers_tool_calls_over_stdio_e2e() {
+    use std::io::{BufRead, BufReader, Write};
+
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    let mut child = spawn_cli(&["mcp", "--root", root.to_str().unwrap()]);
+    let mut stdin = child.stdin.take().unwrap();
+    let messages = [
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "protocolVersion": "2025-06-18", "capabilities": {} },
+        }),
+        serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "find_references",
+                "arguments": { "path": root.join("guide.md") },
+            },
+        }),
+    ];
+    for message in messages {
+        writeln!(stdin, "{message}").unwrap();
+    }
+    drop(stdin);
+
+    let replies: Vec<Value> = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect();
+
+    assert!(child.wait().unwrap().success());
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["result"]["serverInfo"]["name"], "mdref");
+    let references = &replies[1]["result"]["structuredContent"]["references"];
+    assert_eq!(references.as_array().unwrap().len(), 1);
+    assert_eq!(references[0]["link_text"], "guide.md");
+}
+
+// ============= End-to-end: watch =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_watch_fixes_links_after_external_rename_e2e() {
+    use std::{
+        fs,
+        io::{BufRead, BufReader},
+        sync::mpsc,
+        time::Duration,
+    };
+
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    let mut child = spawn_cli(&["watch", root.to_str().unwrap(), "--debounce", "50"]);
+    let (sender, lines) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    std::thread::spawn(move || {
+        for line in stdout.lines() {
+            if sender.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let next_line = || lines.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    assert!(next_line().starts_with("Watching"));
+    fs::rename(root.join("guide.md"), root.join("manual.md")).unwrap();
+    let log = [next_line(), next_line(), next_line()];
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(
+        log,
+        [
+            "Moved guide.md -> manual.md",
+            "Updated links in index.md:",
+            "  Line 1: ](guide.md) -> ](manual.md)",
+        ]
+    );
+    assert_eq!(read_file(root.join("index.md")), "[Guide](manual.md)\n");
+}
+
+// ============= End-to-end: config =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_config_supplies_defaults_that_flags_override_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join(".mdref.toml"),
+        "root = \"docs\"\nformat = \"json\"\n\n[scan]\nexclude = [\"drafts/\"]\n",
+    );
+    write_file(root.join("docs/index.md"), "[Gone](gone.md)\n");
+    write_file(root.join("docs/drafts/wip.md"), "[Missing](missing.md)\n");
+    write_file(root.join("notes/index.md"), "[Lost](lost.md)\n");
+    let nested = root.join("docs/drafts");
+
+    let output = run_cli_in(&nested, &["check"]);
+    assert!(!output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let broken = payload["broken_links"].as_array().unwrap();
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0]["link_text"], "gone.md");
+
+    let output = run_cli_in(&root, &["check", "--root", "notes", "--format", "human"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("lost.md"));
+    assert!(!stdout.contains("gone.md"));
+
+    let output = run_cli_in(&nested, &["config", "show"]);
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["operation"], "config");
+    assert_eq!(payload["config"]["root"], "..");
+    assert_eq!(payload["config"]["scan"]["exclude"][0], "drafts/");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_config_invalid_file_fails_commands_but_not_help() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join(".mdref.toml"), "format = [\n");
+
+    let output = run_cli_in(root, &["check"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid configuration"));
+
+    for args in [&["--help"][..], &["--version"], &["check", "--help"]] {
+        let output = run_cli_in(root, args);
+        assert!(output.status.success(), "mdref {args:?} failed");
+        assert!(!output.stdout.is_empty());
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_config_unknown_format_fails_commands() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join(".mdref.toml"), "format = \"yaml\"\n");
+    write_file(root.join("index.md"), "# Index\n");
+
+    let output = run_cli_in(root, &["check"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid configuration"));
+    assert!(stderr.contains("unknown format `yaml`"));
+}
+
+// ============= End-to-end: mv --git =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_mv_git_refuses_dirty_source_until_forced_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    git(root, &["init", "--quiet"]);
+    git(root, &["add", "--all"]);
+    git(root, &["commit", "--quiet", "--message", "initial"]);
+    write_file(root.join("guide.md"), "# Guide\n\nDraft.\n");
+    let (source, dest) = (root.join("guide.md"), root.join("handbook.md"));
+    let args = [
+        "mv",
+        "--git=auto",
+        source.to_str().unwrap(),
+        dest.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+    ];
+
+    let output = run_cli(&args);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("uncommitted changes"));
+
+    let output = run_cli(&[args.as_slice(), &["--force"]].concat());
+    assert!(output.status.success());
+    assert_eq!(read_file(root.join("index.md")), "[Guide](handbook.md)\n");
+    assert!(root.join("handbook.md").exists());
+}
+
+// ============= End-to-end: index build then mv =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_index_build_then_mv_uses_index_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let source = temp_dir.path().join("a.md");
+    let ref_file = temp_dir.path().join("index.md");
+    write_file(&source, "# A");
+    write_file(&ref_file, "[A](a.md)");
+
+    let output = run_cli(&["index", "build", "--root", root]);
+    assert!(output.status.success());
+    assert!(temp_dir.path().join(".mdref/index").exists());
+
+    let dest = temp_dir.path().join("docs/a.md");
+    let output = run_cli(&[
+        "mv",
+        source.to_str().unwrap(),
+        dest.to_str().unwrap(),
+        "--root",
+        root,
+    ]);
+    assert!(output.status.success());
+    assert_eq!(read_file(&ref_file), "[A](docs/a.md)");
+
+    let output = run_cli(&["index", "status", "--root", root]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is stale"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_mv_from_csv_manifest_moves_all_pairs_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let a = temp_dir.path().join("a.md");
+    let b = temp_dir.path().join("b.md");
+    let ref_file = temp_dir.path().join("index.md");
+    write_file(&a, "# A");
+    write_file(&b, "# B");
+    write_file(&ref_file, "[A](a.md) [B](b.md)");
+    let manifest = temp_dir.path().join("moves.csv");
+    write_file(
+        &manifest,
+        &format!(
+            "source,destination\n{},{}\n{},{}\n",
+            a.display(),
+            b.display(),
+            b.display(),
+            a.display()
+        ),
+    );
+
+    let output = run_cli(&["mv", "--from", manifest.to_str().unwrap(), "--root", root]);
+
+    assert!(output.status.success());
+    assert_eq!(read_file(&a), "# B");
+    assert_eq!(read_file(&b), "# A");
+    assert_eq!(read_file(&ref_file), "[A](b.md) [B](a.md)");
+}
+
 // ============= dry-run CLI tests =============
 
 #[test]
@@ -411,3 +1002,174 @@ fn test_cli_rename_dry_run_does_not_rename() {
         stdout
     );
 }
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_rename_dry_run_diff_format_prints_rename_headers() {
+    let temp_dir = temp_dir();
+    let source = temp_dir.path().join("old.md");
+    write_file(&source, "# Old");
+    write_file(temp_dir.path().join("ref.md"), "[Old](old.md)\n");
+
+    let output = run_cli(&[
+        "rename",
+        source.to_str().unwrap(),
+        "new.md",
+        "--root",
+        temp_dir.path().to_str().unwrap(),
+        "--dry-run",
+        "--format",
+        "diff",
+    ]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("diff --git a/old.md b/new.md\nrename from old.md\nrename to new.md\n")
+    );
+    assert!(stdout.contains("-[Old](old.md)\n+[Old](new.md)\n"));
+    assert!(source.exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_plan_then_apply_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let source = temp_dir.path().join("a.md");
+    let ref_file = temp_dir.path().join("index.md");
+    let plan = temp_dir.path().join("plan.json");
+    write_file(&source, "# A");
+    write_file(&ref_file, "[A](a.md)");
+
+    let output = run_cli(&[
+        "plan",
+        source.to_str().unwrap(),
+        temp_dir.path().join("docs/a.md").to_str().unwrap(),
+        "--root",
+        root,
+        "-o",
+        plan.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert!(source.exists());
+
+    let output = run_cli(&["apply", plan.to_str().unwrap(), "--root", root]);
+    assert!(output.status.success());
+    assert!(!source.exists());
+    assert_eq!(read_file(&ref_file), "[A](docs/a.md)");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_plan_with_relative_paths_applies_from_another_directory_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("index.md"), "[A](a.md)");
+    write_file(root.join("notes/todo.md"), "# Todo");
+
+    let output = run_cli_in(&root, &["plan", "a.md", "docs/a.md", "-o", "plan.json"]);
+    assert!(output.status.success());
+
+    let output = run_cli_in(
+        &root.join("notes"),
+        &["apply", "../plan.json", "--root", ".."],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!root.join("a.md").exists());
+    assert!(root.join("docs/a.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[A](docs/a.md)");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_mv_then_history_and_undo_e2e() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().to_str().unwrap();
+    let source = temp_dir.path().join("a.md");
+    let ref_file = temp_dir.path().join("index.md");
+    write_file(&source, "# A");
+    write_file(&ref_file, "[A](a.md)");
+
+    let output = run_cli(&[
+        "mv",
+        source.to_str().unwrap(),
+        temp_dir.path().join("docs/a.md").to_str().unwrap(),
+        "--root",
+        root,
+    ]);
+    assert!(output.status.success());
+
+    let output = run_cli(&["history", "--root", root]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("#1 mv (just now): moved 1 path(s), rewrote 1 file(s)\n"));
+    assert!(stdout.contains("a.md -> docs/a.md"));
+
+    let output = run_cli(&["undo", "--root", root]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Undid #1 mv"));
+    assert!(source.exists());
+    assert_eq!(read_file(&ref_file), "[A](a.md)");
+
+    let output = run_cli(&["undo", "--root", root]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no operation to undo"));
+}
+
+// ============= recover command tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cli_recover_interrupted_mv_e2e() {
+    let temp_dir = temp_dir();
+    let root_path = temp_dir.path().canonicalize().unwrap();
+    let root = root_path.to_str().unwrap();
+    write_file(root_path.join("docs/a.md"), "# A\n");
+    write_file(root_path.join("index.md"), "[A](a.md)\n");
+    let header = serde_json::json!({
+        "version": 1,
+        "operation": "mv",
+        "moves": [[root_path.join("a.md"), root_path.join("docs/a.md")]],
+        "files": [{
+            "original_path": root_path.join("index.md"),
+            "final_path": root_path.join("index.md"),
+            "original": "[A](a.md)\n",
+            "rewritten": "[A](docs/a.md)\n",
+        }],
+    });
+    let step = serde_json::json!({
+        "from": root_path.join("a.md"),
+        "to": root_path.join("docs/a.md"),
+    });
+    write_file(
+        root_path.join(".mdref/transaction"),
+        &format!("{header}\n{step}\n"),
+    );
+
+    let output = run_cli(&["check", "--root", root]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("run `mdref recover`"));
+
+    let output = run_cli(&["recover", "--root", root]);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .starts_with("Interrupted mv: 1 of 1 move(s) started, 1 file(s) to rewrite\n")
+    );
+
+    let output = run_cli(&["recover", "forward", "--root", root, "--format", "json"]);
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["action"], "forward");
+    assert_eq!(payload["interrupted"]["operation"], "mv");
+    assert_eq!(read_file(root_path.join("index.md")), "[A](docs/a.md)\n");
+
+    let output = run_cli(&["recover", "--root", root]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("No interrupted operation"));
+}
@@ -5,7 +5,8 @@ use std::{
     fs,
     io::Write,
     path::{Path, PathBuf},
-    process::{Command, Output},
+    process::{Child, Command, Output, Stdio},
+    sync::{LazyLock, Mutex},
 };
 
 use tempfile::TempDir;
@@ -18,10 +19,91 @@ pub fn run_cli(args: &[&str]) -> Output {
     Command::new(binary_path()).args(args).output().unwrap()
 }
 
+/// Run the CLI from `dir`, for behavior that depends on the current directory.
+pub fn run_cli_in(dir: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+static CURRENT_DIR_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+struct CurrentDirGuard {
+    original_dir: PathBuf,
+}
+
+impl CurrentDirGuard {
+    fn enter(path: &Path) -> Self {
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(path).unwrap();
+        Self { original_dir }
+    }
+}
+
+impl Drop for CurrentDirGuard {
+    fn drop(&mut self) {
+        std::env::set_current_dir(&self.original_dir).unwrap();
+    }
+}
+
+/// Run `operation` with `path` as the current directory, one test at a time.
+pub fn with_current_dir<T>(path: &Path, operation: impl FnOnce() -> T) -> T {
+    let _lock = CURRENT_DIR_LOCK.lock().unwrap();
+    let _guard = CurrentDirGuard::enter(path);
+    operation()
+}
+
+/// Start the CLI with piped stdin and stdout, for commands that talk a protocol.
+pub fn spawn_cli(args: &[&str]) -> Child {
+    Command::new(binary_path())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Run `git` in `root` with a throwaway identity, panicking on failure.
+pub fn git(root: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "user.name=mdref",
+            "-c",
+            "user.email=mdref@example.com",
+            "-c",
+            "commit.gpgsign=false",
+        ])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Turn `root` into a git repository whose first commit holds every file.
+pub fn commit_all(root: &Path) {
+    git(root, &["init", "--quiet"]);
+    git(root, &["add", "--all"]);
+    git(root, &["commit", "--quiet", "--message", "initial"]);
+}
+
 pub fn temp_dir() -> TempDir {
     TempDir::new().unwrap()
 }
 
+/// Convert string paths into `PathBuf`s, for comparing path lists.
+pub fn paths(items: &[&str]) -> Vec<PathBuf> {
+    items.iter().map(PathBuf::from).collect()
+}
+
 pub fn read_file<P: AsRef<Path>>(path: P) -> String {
     fs::read_to_string(path).unwrap()
 }
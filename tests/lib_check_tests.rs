@@ -0,0 +1,175 @@
+use mdref::{BrokenLinkKind, LinkType, NoopProgress, check_links};
+use tempfile::TempDir;
+
+mod common;
+
+use common::{fixture_directory_move, write_file};
+
+// Library tests for `check_links` cover link resolution across a whole root.
+// Output formatting and exit status belong to CLI tests.
+
+/// A tree whose links all resolve should produce no broken links.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_valid_tree_returns_empty() {
+    let fixture = fixture_directory_move();
+
+    let broken = check_links(&fixture.root, false, &NoopProgress).unwrap();
+
+    assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+}
+
+/// Nested files should resolve links relative to their own directory.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_resolves_relative_to_referencing_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("shared.md"), "# Shared");
+    write_file(
+        root.join("docs").join("nested").join("page.md"),
+        "[Shared](../../shared.md)\n[Wrong](../shared.md)",
+    );
+
+    let broken = check_links(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_text, "../shared.md");
+    assert_eq!(broken[0].line, 2);
+    assert!(broken[0].path.ends_with("page.md"));
+}
+
+/// URL-encoded links and anchored links should resolve to the decoded file path.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_decodes_url_encoding_and_strips_anchor() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("my file.md"), "# Mine");
+    write_file(
+        root.join("index.md"),
+        "[Mine](my%20file.md#intro)\n\n[ref]: <my file.md>",
+    );
+
+    let broken = check_links(root, false, &NoopProgress).unwrap();
+
+    assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+}
+
+/// Links inside code blocks are not links and must not be reported.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_ignores_code_blocks() {
+    let temp_dir = TempDir::new().unwrap();
+    write_file(
+        temp_dir.path().join("index.md"),
+        "```md\n[Gone](gone.md)\n\n[ref]: ./gone.md\n```\n",
+    );
+
+    let broken = check_links(temp_dir.path(), false, &NoopProgress).unwrap();
+
+    assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_ignores_nested_brackets_in_prose() {
+    let temp_dir = TempDir::new().unwrap();
+    write_file(
+        temp_dir.path().join("a.md"),
+        "# Matrix\n\nThe identity is [[1, 0], [0, 1]].\n",
+    );
+
+    let broken = check_links(temp_dir.path(), false, &NoopProgress).unwrap();
+
+    assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+}
+
+/// Broken reference definitions should be reported with their link type.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_reports_reference_definition_type() {
+    let temp_dir = TempDir::new().unwrap();
+    write_file(
+        temp_dir.path().join("index.md"),
+        "[text][ref]\n\n[ref]: ./missing.md",
+    );
+
+    let broken = check_links(temp_dir.path(), false, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_type, LinkType::ReferenceDefinition);
+    assert_eq!(broken[0].line, 3);
+}
+
+// ============= anchor checking tests =============
+
+/// Fragments must match a heading slug in the target document.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_with_anchors_reports_misspelled_fragment() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n\n## Installation\n");
+    write_file(
+        root.join("index.md"),
+        "[Ok](guide.md#installation)\n[Typo](guide.md#instalation)\n",
+    );
+
+    let broken = check_links(root, true, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_text, "guide.md#instalation");
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingAnchor);
+}
+
+/// Without anchor checking, only missing targets are reported.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_without_anchors_ignores_fragments() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide");
+    write_file(
+        root.join("index.md"),
+        "[Typo](guide.md#instalation)\n[Self](#nope)",
+    );
+
+    let broken = check_links(root, false, &NoopProgress).unwrap();
+
+    assert!(broken.is_empty(), "unexpected broken links: {broken:?}");
+}
+
+/// Duplicate headings, explicit ids, and HTML anchors all count as valid fragments.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_with_anchors_accepts_all_anchor_forms() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(
+        root.join("guide.md"),
+        "## Usage\n\n## Usage\n\n## Setup {#install}\n\n<a id=\"faq\"></a>\n",
+    );
+    write_file(
+        root.join("index.md"),
+        "[A](guide.md#usage) [B](guide.md#usage-1) [C](guide.md#install) [D](guide.md#faq)\n\n[E](guide.md#setup)\n",
+    );
+
+    let broken = check_links(root, true, &NoopProgress).unwrap();
+
+    let link_texts: Vec<&str> = broken.iter().map(|b| b.link_text.as_str()).collect();
+    assert_eq!(link_texts, vec!["guide.md#setup"]);
+}
+
+/// A missing file is reported as a missing target even when it has a fragment.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_with_anchors_prefers_missing_target() {
+    let temp_dir = TempDir::new().unwrap();
+    write_file(temp_dir.path().join("index.md"), "[Gone](gone.md#intro)");
+
+    let broken = check_links(temp_dir.path(), true, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingTarget);
+}
@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use mdref::{
+    NoopProgress, check_links,
+    core::util::{collect_files, collect_markdown_files},
+    mv,
+};
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for `.mdref.toml` cover how its `[scan]` settings shape the
+// files a root contains and how its `[links]` settings shape rewritten links.
+// Command-line defaults are tested through the CLI.
+
+fn relative_files(root: &std::path::Path, files: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = files
+        .into_iter()
+        .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+        .collect();
+    files.sort();
+    files
+}
+
+// ============= Scan settings =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_collect_files_applies_include_and_exclude_patterns() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join(".mdref.toml"),
+        "[scan]\ninclude = [\"docs/\", \"README.md\"]\nexclude = [\"drafts/\"]\n",
+    );
+    write_file(root.join("README.md"), "# Readme\n");
+    write_file(root.join("docs/guide.md"), "# Guide\n");
+    write_file(root.join("docs/logo.png"), "png");
+    write_file(root.join("docs/drafts/wip.md"), "# WIP\n");
+    write_file(root.join("vendor/notes.md"), "# Notes\n");
+
+    let files = relative_files(&root, collect_files(&root));
+
+    assert_eq!(
+        files,
+        [
+            PathBuf::from("README.md"),
+            PathBuf::from("docs/guide.md"),
+            PathBuf::from("docs/logo.png"),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_collect_files_can_ignore_gitignore() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join(".gitignore"), "build/\n");
+    write_file(root.join("build/out.md"), "# Out\n");
+    write_file(root.join("index.md"), "# Index\n");
+
+    assert_eq!(
+        relative_files(&root, collect_files(&root)),
+        [PathBuf::from("index.md")]
+    );
+
+    write_file(root.join(".mdref.toml"), "[scan]\nignore_files = false\n");
+
+    assert_eq!(
+        relative_files(&root, collect_files(&root)),
+        [PathBuf::from("build/out.md"), PathBuf::from("index.md")]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_collect_markdown_files_uses_configured_extensions() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join(".mdref.toml"),
+        "[scan]\nmarkdown_extensions = [\"md\", \"markdown\"]\n",
+    );
+    write_file(root.join("a.md"), "# A\n");
+    write_file(root.join("b.markdown"), "# B\n");
+    write_file(root.join("c.txt"), "C\n");
+
+    assert_eq!(
+        relative_files(&root, collect_markdown_files(&root)),
+        [PathBuf::from("a.md"), PathBuf::from("b.markdown")]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_skips_excluded_files() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join(".mdref.toml"),
+        "[scan]\nexclude = [\"drafts/\"]\n",
+    );
+    write_file(root.join("index.md"), "[Gone](gone.md)\n");
+    write_file(root.join("drafts/wip.md"), "[Missing](missing.md)\n");
+
+    let broken = check_links(&root, false, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_text, "gone.md");
+}
+
+// ============= Link settings =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_percent_encodes_spaces_by_default() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    mv(
+        root.join("guide.md"),
+        root.join("user guide.md"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](user%20guide.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_writes_angle_brackets_when_configured() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join(".mdref.toml"), "[links]\nspaces = \"angle\"\n");
+    write_file(
+        root.join("index.md"),
+        "[Guide](guide.md)\n[Ref][guide]\n\n[guide]: guide.md\n",
+    );
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    mv(
+        root.join("guide.md"),
+        root.join("user guide.md"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](<user guide.md>)\n[Ref][guide]\n\n[guide]: <user guide.md>\n"
+    );
+}
@@ -0,0 +1,299 @@
+use mdref::{IntraCopyLinks, MdrefError, core::model::MoveChangeKind, cp, preview_copy};
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for `cp` cover link rewrites inside copies, links between
+// copied files, and the preview. CLI tests only verify the end-to-end process
+// behavior.
+
+// ============= File copy tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_file_to_different_depth_rewrites_copy_only() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    let guide = root.join("guide.md");
+    let index = root.join("index.md");
+    write_file(&guide, "[Index](index.md)\n![Logo](images/logo.png)\n");
+    write_file(&index, "[Guide](guide.md)\n");
+    write_file(root.join("images/logo.png"), "png");
+
+    cp(
+        &guide,
+        root.join("archive/2024/guide.md"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/2024/guide.md")),
+        "[Index](../../index.md)\n![Logo](../../images/logo.png)\n"
+    );
+    assert_eq!(
+        read_file(&guide),
+        "[Index](index.md)\n![Logo](images/logo.png)\n"
+    );
+    assert_eq!(read_file(&index), "[Guide](guide.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_file_into_existing_directory_keeps_name() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "[Index](index.md)\n");
+    write_file(root.join("index.md"), "# Index\n");
+    std::fs::create_dir(root.join("archive")).unwrap();
+
+    cp(
+        root.join("guide.md"),
+        root.join("archive"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/guide.md")),
+        "[Index](../index.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_non_markdown_file_copies_bytes() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("images/logo.png"), "png");
+
+    cp(
+        root.join("images/logo.png"),
+        root.join("assets/logo.png"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(read_file(root.join("assets/logo.png")), "png");
+    assert_eq!(read_file(root.join("images/logo.png")), "png");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_existing_destination_returns_error() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "[Index](index.md)\n");
+    write_file(root.join("copy.md"), "keep me\n");
+
+    let result = cp(
+        root.join("guide.md"),
+        root.join("copy.md"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    );
+
+    assert!(matches!(result, Err(MdrefError::PathValidation { .. })));
+    assert_eq!(read_file(root.join("copy.md")), "keep me\n");
+}
+
+// ============= Directory copy tests =============
+
+fn write_versioned_docs(root: &std::path::Path) {
+    write_file(
+        root.join("docs/v1/index.md"),
+        "[Setup](setup.md)\n[Home](../../README.md)\n",
+    );
+    write_file(root.join("docs/v1/setup.md"), "[Back](index.md)\n");
+    write_file(root.join("README.md"), "[Docs](docs/v1/index.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_directory_to_original_points_intra_links_at_originals() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_versioned_docs(root);
+
+    cp(
+        root.join("docs/v1"),
+        root.join("archive/v1"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/v1/index.md")),
+        "[Setup](../../docs/v1/setup.md)\n[Home](../../README.md)\n"
+    );
+    assert_eq!(
+        read_file(root.join("archive/v1/setup.md")),
+        "[Back](../../docs/v1/index.md)\n"
+    );
+    assert_eq!(
+        read_file(root.join("README.md")),
+        "[Docs](docs/v1/index.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_directory_to_copy_keeps_intra_links_inside_copy() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_versioned_docs(root);
+
+    cp(
+        root.join("docs/v1"),
+        root.join("archive/v1"),
+        IntraCopyLinks::ToCopy,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/v1/index.md")),
+        "[Setup](setup.md)\n[Home](../../README.md)\n"
+    );
+    assert_eq!(
+        read_file(root.join("archive/v1/setup.md")),
+        "[Back](index.md)\n"
+    );
+    assert_eq!(
+        read_file(root.join("docs/v1/index.md")),
+        "[Setup](setup.md)\n[Home](../../README.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_directory_to_original_keeps_wikilinks_on_originals() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(
+        root.join("docs/v1/index.md"),
+        "[[setup]]\n[[setup#Install|Install]]\n[[README]]\n",
+    );
+    write_file(root.join("docs/v1/setup.md"), "# Install\n");
+    write_file(root.join("README.md"), "# Readme\n");
+
+    cp(
+        root.join("docs/v1"),
+        root.join("archive/v1"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/v1/index.md")),
+        "[[docs/v1/setup]]\n[[docs/v1/setup#Install|Install]]\n[[README]]\n"
+    );
+    assert_eq!(
+        read_file(root.join("docs/v1/index.md")),
+        "[[setup]]\n[[setup#Install|Install]]\n[[README]]\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_directory_to_copy_points_wikilink_paths_at_copies() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(
+        root.join("docs/v1/index.md"),
+        "[[setup]]\n[[docs/v1/setup]]\n",
+    );
+    write_file(root.join("docs/v1/setup.md"), "# Setup\n");
+
+    cp(
+        root.join("docs/v1"),
+        root.join("archive/v1"),
+        IntraCopyLinks::ToCopy,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("archive/v1/index.md")),
+        "[[setup]]\n[[archive/v1/setup]]\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_directory_into_itself_returns_copy_error() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_versioned_docs(root);
+
+    let result = cp(
+        root.join("docs"),
+        root.join("docs/v1/docs"),
+        IntraCopyLinks::ToOriginal,
+        false,
+    );
+
+    match result {
+        Err(MdrefError::PathValidation { details, .. }) => assert_eq!(
+            details,
+            "cannot copy directory into itself or one of its subdirectories"
+        ),
+        other => panic!("expected a path validation error, got {other:?}"),
+    }
+    assert!(!root.join("docs/v1/docs").exists());
+}
+
+// ============= Preview tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_copy_lists_copy_rewrites_without_writing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_versioned_docs(root);
+
+    let preview = preview_copy(
+        root.join("docs/v1"),
+        root.join("docs/v2"),
+        IntraCopyLinks::ToOriginal,
+    )
+    .unwrap();
+
+    assert!(!root.join("docs/v2").exists());
+    assert_eq!(preview.changes.len(), 2);
+    assert!(
+        preview
+            .changes
+            .iter()
+            .all(|change| change.kind == MoveChangeKind::CopiedFileUpdate
+                && change.path.starts_with(root.join("docs/v2")))
+    );
+    assert_eq!(
+        preview.changes[0].replacements[0].new_pattern,
+        "](../v1/setup.md)"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_cp_dry_run_writes_nothing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_versioned_docs(root);
+
+    cp(
+        root.join("docs/v1"),
+        root.join("docs/v2"),
+        IntraCopyLinks::ToCopy,
+        true,
+    )
+    .unwrap();
+
+    assert!(!root.join("docs/v2").exists());
+}
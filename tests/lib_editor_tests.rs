@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use mdref::{
+    BrokenLinkKind, NoopProgress, build_index,
+    core::{
+        editor::{
+            Overlay, check_document, find_references_in_overlay, link_at, link_span,
+            preview_move_in_overlay, resolve_link_target,
+        },
+        model::MoveChangeKind,
+    },
+};
+
+mod common;
+
+use common::{temp_dir, write_file};
+
+// Library tests for `core::editor` cover how overlaid contents take the place
+// of the files on disk. The language server built on them is tested through
+// `mdref lsp` in the CLI tests.
+
+fn overlay(entries: &[(&Path, &str)]) -> Overlay {
+    entries
+        .iter()
+        .map(|(path, content)| (path.canonicalize().unwrap(), content.to_string()))
+        .collect()
+}
+
+fn referrers(references: &[mdref::Reference]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = references
+        .iter()
+        .map(|reference| PathBuf::from(reference.path.file_name().unwrap()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+// ============= References =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_in_overlay_reads_overlaid_files() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("target.md"), "# Target\n");
+    write_file(root.join("added.md"), "# Added\n");
+    write_file(root.join("removed.md"), "[Target](target.md)\n");
+    write_file(root.join("kept.md"), "[Target](target.md)\n");
+    let overlay = overlay(&[
+        (&root.join("added.md"), "# Added\n\n[Target](target.md)\n"),
+        (&root.join("removed.md"), "# Removed\n"),
+    ]);
+
+    let references =
+        find_references_in_overlay(root.join("target.md"), root, &overlay, &NoopProgress).unwrap();
+
+    assert_eq!(
+        referrers(&references),
+        [PathBuf::from("added.md"), PathBuf::from("kept.md")]
+    );
+    let added = references
+        .iter()
+        .find(|reference| reference.path.ends_with("added.md"))
+        .unwrap();
+    assert_eq!(added.line, 3);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_in_overlay_overrides_link_index() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("target.md"), "# Target\n");
+    write_file(root.join("a.md"), "[Target](target.md)\n");
+    write_file(root.join("b.md"), "# B\n");
+    build_index(root, &NoopProgress).unwrap();
+    let overlay = overlay(&[
+        (&root.join("a.md"), "# A\n"),
+        (&root.join("b.md"), "[[target]]\n"),
+    ]);
+
+    let references =
+        find_references_in_overlay(root.join("target.md"), root, &overlay, &NoopProgress).unwrap();
+
+    assert_eq!(referrers(&references), [PathBuf::from("b.md")]);
+}
+
+// ============= Diagnostics =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_document_checks_overlaid_contents_and_anchors() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Old\n");
+    let overlay = overlay(&[
+        (
+            &root.join("index.md"),
+            "[Guide](guide.md#new)\n[Gone](gone.md)\n[Old](guide.md#old)\n",
+        ),
+        (&root.join("guide.md"), "# New\n"),
+    ]);
+
+    let broken = check_document(root.join("index.md"), root, true, &overlay).unwrap();
+
+    let found: Vec<(usize, &str, BrokenLinkKind)> = broken
+        .iter()
+        .map(|link| (link.line, link.link_text.as_str(), link.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (2, "gone.md", BrokenLinkKind::MissingTarget),
+            (3, "guide.md#old", BrokenLinkKind::MissingAnchor),
+        ]
+    );
+}
+
+// ============= Links under the cursor =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_link_at_covers_link_text_through_url() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    let path = root.join("index.md");
+    let content = "See [Guide](docs/guide.md) and [[Note|alias]].\n";
+
+    assert!(link_at(&path, content, 1, 4).is_none());
+    let link = link_at(&path, content, 1, 5).unwrap();
+    assert_eq!(link.link_text, "docs/guide.md");
+    assert_eq!(
+        link_at(&path, content, 1, 26).unwrap().link_text,
+        "docs/guide.md"
+    );
+    assert!(link_at(&path, content, 1, 28).is_none());
+    assert_eq!(link_at(&path, content, 1, 34).unwrap().link_text, "Note");
+
+    assert_eq!(link_span(content, &link), Some((12, 25)));
+    let wikilink = link_at(&path, content, 1, 34).unwrap();
+    assert_eq!(link_span(content, &wikilink), Some((33, 37)));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_resolve_link_target_finds_heading_in_overlay() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "");
+    write_file(root.join("guide.md"), "# Guide\n");
+    let overlay = overlay(&[(&root.join("guide.md"), "# Guide\n\n## Set up\n")]);
+    let content = "[Setup](guide.md#set-up) [[guide#Set up]] [Top](#intro)\n";
+    let index = root.join("index.md");
+
+    let link = link_at(&index, content, 1, 2).unwrap();
+    let (target, heading) = resolve_link_target(&link, root, &overlay).unwrap();
+    assert_eq!(
+        target.canonicalize().unwrap(),
+        root.join("guide.md").canonicalize().unwrap()
+    );
+    assert_eq!(heading.unwrap().line, 3);
+
+    let link = link_at(&index, content, 1, 27).unwrap();
+    let (_, heading) = resolve_link_target(&link, root, &overlay).unwrap();
+    assert_eq!(heading.unwrap().text, "Set up");
+
+    let link = link_at(&index, content, 1, 44).unwrap();
+    let (target, heading) = resolve_link_target(&link, root, &overlay).unwrap();
+    assert_eq!(target, index);
+    assert!(heading.is_none());
+}
+
+// ============= Move previews =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_move_in_overlay_locates_replacements_in_buffers() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "[Home](index.md)\n");
+    let overlay = overlay(&[
+        (
+            &root.join("index.md"),
+            "# Home\n\n[Guide](guide.md) and [again](guide.md)\n",
+        ),
+        (&root.join("guide.md"), "# Guide\n[Home](index.md)\n"),
+    ]);
+
+    let preview = preview_move_in_overlay(
+        &[(root.join("guide.md"), root.join("docs/guide.md"))],
+        &root,
+        &overlay,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    let index = preview
+        .changes
+        .iter()
+        .find(|change| change.kind == MoveChangeKind::ReferenceUpdate)
+        .unwrap();
+    let positions: Vec<(usize, usize, &str)> = index
+        .replacements
+        .iter()
+        .map(|replacement| {
+            (
+                replacement.line,
+                replacement.column,
+                replacement.new_pattern.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        positions,
+        [(3, 1, "](docs/guide.md)"), (3, 23, "](docs/guide.md)")]
+    );
+
+    let moved = preview
+        .changes
+        .iter()
+        .find(|change| change.kind == MoveChangeKind::MovedFileUpdate)
+        .unwrap();
+    assert_eq!(moved.replacements.len(), 1);
+    assert_eq!(moved.replacements[0].line, 2);
+    assert_eq!(moved.replacements[0].new_pattern, "](../index.md)");
+}
@@ -1,6 +1,9 @@
 use std::{fs, io::Write, path::Path};
 
-use mdref::{MdrefError, NoopProgress, Reference, find_links, find_references};
+use mdref::{
+    LinkType, MdrefError, NoopProgress, Reference, build_index, find_links, find_references,
+    find_references_many, index_status,
+};
 use rstest::rstest;
 use tempfile::TempDir;
 
@@ -363,6 +366,117 @@ fn test_find_references_only_returns_markdown_files() {
     }
 }
 
+// ============= find_references_many tests =============
+
+/// find_references_many should group the references per target, in target order.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_many_groups_references_per_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(
+        root.join("index.md"),
+        "![Logo](img/logo.png) [Guide](guide.md)",
+    );
+    write_file(root.join("guide.md"), "![Logo](img/logo.png)");
+    write_file(root.join("img/logo.png"), "png");
+    write_file(root.join("img/unused.png"), "png");
+
+    let targets = [root.join("img/unused.png"), root.join("img/logo.png")];
+    let result = find_references_many(&targets, root, &NoopProgress).unwrap();
+
+    assert_eq!(result.len(), 2);
+    assert!(result[0].is_empty(), "unused.png should have no referrers");
+    let mut referrers: Vec<_> = result[1]
+        .iter()
+        .map(|reference| reference.path.file_name().unwrap().to_owned())
+        .collect();
+    referrers.sort();
+    assert_eq!(referrers, ["guide.md", "index.md"]);
+}
+
+/// A reference into a directory target is listed under the directory and
+/// under the file when both are targets.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_many_lists_overlapping_targets_under_each() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "![Logo](img/logo.png)");
+    write_file(root.join("img/logo.png"), "png");
+
+    let targets = [root.join("img"), root.join("img/logo.png")];
+    let result = find_references_many(&targets, root, &NoopProgress).unwrap();
+
+    assert_eq!(result[0].len(), 1);
+    assert_eq!(result[1].len(), 1);
+    assert_eq!(result[0][0].link_text, result[1][0].link_text);
+}
+
+/// With a link index, each reference is still listed once under every
+/// target it refers to, including a target given twice.
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_many_with_index_matches_like_a_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(
+        root.join("docs/index.md"),
+        "![Logo](../img/logo.png) [[guide]] [Icon](../img/icons/a.svg)",
+    );
+    write_file(root.join("docs/guide.md"), "# Guide");
+    write_file(root.join("img/logo.png"), "png");
+    write_file(root.join("img/icons/a.svg"), "svg");
+    let targets = [
+        root.join("img"),
+        root.join("img/logo.png"),
+        root.join("docs/guide.md"),
+        root.join("img"),
+    ];
+    let scanned = find_references_many(&targets, root, &NoopProgress).unwrap();
+
+    build_index(root, &NoopProgress).unwrap();
+    let indexed = find_references_many(&targets, root, &NoopProgress).unwrap();
+
+    let link_texts = |references: &[Vec<Reference>]| -> Vec<Vec<String>> {
+        references
+            .iter()
+            .map(|list| {
+                let mut texts: Vec<String> = list
+                    .iter()
+                    .map(|reference| reference.link_text.clone())
+                    .collect();
+                texts.sort();
+                texts
+            })
+            .collect()
+    };
+    let expected = [
+        vec!["../img/icons/a.svg", "../img/logo.png"],
+        vec!["../img/logo.png"],
+        vec!["guide"],
+        vec!["../img/icons/a.svg", "../img/logo.png"],
+    ];
+    assert_eq!(link_texts(&scanned), expected);
+    assert_eq!(link_texts(&indexed), expected);
+}
+
+/// find_references_many should fail when any target does not exist.
+#[test]
+fn test_find_references_many_returns_error_for_missing_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A");
+
+    let targets = [root.join("a.md"), root.join("ghost.png")];
+    let result = find_references_many(&targets, root, &NoopProgress);
+
+    match result {
+        Err(MdrefError::IoRead { path, .. }) => assert!(path.ends_with("ghost.png")),
+        other => panic!("expected io read error for missing target, got {other:?}"),
+    }
+}
+
 // ============= find_references with nested directories =============
 
 /// find_references should find references from nested directories.
@@ -485,6 +599,119 @@ fn test_find_references_ignores_external_urls() {
     assert_eq!(result[0].link_text, "target.md");
 }
 
+// ============= Wikilink tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_matches_wikilinks_with_vault_resolution() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let target = root.join("notes/Topic.md");
+    write_file(&target, "# Topic");
+    write_file(
+        root.join("index.md"),
+        "[[Topic]] [[notes/Topic#Intro|alias]] [[topic.md]] [[Other]]\n",
+    );
+    write_file(root.join("journal/day.md"), "![[Topic]]\n");
+
+    let mut references = find_references(&target, root, &NoopProgress).unwrap();
+    references.sort_by(|a, b| a.path.cmp(&b.path).then(a.column.cmp(&b.column)));
+
+    let found: Vec<(String, usize, &str)> = references
+        .iter()
+        .map(|r| {
+            (
+                r.path.strip_prefix(root).unwrap().display().to_string(),
+                r.column,
+                r.link_text.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("index.md".to_string(), 1, "Topic"),
+            ("index.md".to_string(), 11, "notes/Topic#Intro"),
+            ("index.md".to_string(), 39, "topic.md"),
+            ("journal/day.md".to_string(), 1, "Topic"),
+        ]
+    );
+    assert!(references.iter().all(|r| r.link_type == LinkType::WikiLink));
+}
+
+// ============= Link index tests =============
+
+fn summarize(references: &[Reference], root: &Path) -> Vec<(String, usize, usize, String)> {
+    let mut summary: Vec<_> = references
+        .iter()
+        .map(|r| {
+            (
+                r.path
+                    .strip_prefix(root)
+                    .unwrap_or(&r.path)
+                    .display()
+                    .to_string(),
+                r.line,
+                r.column,
+                r.link_text.clone(),
+            )
+        })
+        .collect();
+    summary.sort();
+    summary
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_with_index_matches_full_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let target = root.join("docs/guide.md");
+    write_file(&target, "# Guide");
+    write_file(
+        root.join("index.md"),
+        "[Guide](docs/guide.md#intro)\n[[guide]]\n<a href=\"docs/guide.md\">x</a>\n\n[ref]: ./docs/guide.md\n",
+    );
+    write_file(
+        root.join("docs/other.md"),
+        "[Guide](guide.md) [Else](else.md)\n",
+    );
+    write_file(root.join("unrelated.md"), "[Other](docs/other.md)\n");
+
+    let without_index = find_references(&target, root, &NoopProgress).unwrap();
+    build_index(root, &NoopProgress).unwrap();
+    let with_index = find_references(&target, root, &NoopProgress).unwrap();
+    let directory = find_references(root.join("docs"), root, &NoopProgress).unwrap();
+
+    assert_eq!(
+        summarize(&with_index, root),
+        summarize(&without_index, root)
+    );
+    assert_eq!(with_index.len(), 5);
+    assert_eq!(directory.len(), 6);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_with_index_sees_edits_and_new_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let target = root.join("guide.md");
+    write_file(&target, "# Guide");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    build_index(root, &NoopProgress).unwrap();
+
+    write_file(root.join("index.md"), "Nothing here\n");
+    write_file(root.join("new.md"), "See [Guide](./guide.md)\n");
+    let references = find_references(&target, root, &NoopProgress).unwrap();
+
+    assert_eq!(
+        summarize(&references, root),
+        vec![("new.md".to_string(), 1, 5, "./guide.md".to_string())]
+    );
+    assert!(index_status(root).unwrap().is_fresh());
+}
+
 // ============= Unicode tests =============
 
 /// Test find_links with Unicode filenames (Chinese, Japanese, Korean, emoji).
@@ -0,0 +1,301 @@
+use std::fs;
+
+use mdref::{
+    FixMatch, MdrefError, NoopProgress, build_index, core::model::MoveChangeKind, fix,
+    fix_from_git, fix_moved, preview_fix, preview_fix_from_git, preview_fix_moved, undo,
+};
+
+mod common;
+
+use common::{commit_all, git, read_file, temp_dir, with_current_dir, write_file};
+
+// Library tests for `fix` cover each way of finding a candidate, the links
+// left for manual review, undo, and replaying renames from git history or
+// from moves already made. CLI
+// tests only verify the end-to-end process behavior.
+
+// ============= Candidate tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_unique_filename_rewrites_every_link_form() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide/setup.md"), "# Setup\n");
+    write_file(root.join("assets/logo.png"), "png");
+    write_file(
+        root.join("notes/index.md"),
+        "[Setup](setup.md#install) ![Logo](logo.png)\n[ref]\n\n[ref]: ../setup.md\n",
+    );
+
+    fix(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(
+        read_file(root.join("notes/index.md")),
+        "[Setup](../docs/guide/setup.md#install) ![Logo](../assets/logo.png)\n[ref]\n\n\
+         [ref]: ../docs/guide/setup.md\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_with_default_root_keeps_root_level_links_relative() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("docs/new/guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[g](guide.md#g)\n");
+
+    with_current_dir(&root, || fix(".", false, &NoopProgress)).unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[g](docs/new/guide.md#g)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_fix_prefers_most_similar_path() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("archive/guide/setup.md"), "# Old\n");
+    write_file(root.join("other/setup.md"), "# Other\n");
+    write_file(root.join("index.md"), "[Setup](guide/setup.md)\n");
+
+    let preview = preview_fix(root, &NoopProgress).unwrap();
+
+    assert_eq!(preview.fixes.len(), 1);
+    assert_eq!(preview.fixes[0].matched_by, Some(FixMatch::PathSimilarity));
+    assert_eq!(
+        preview.fixes[0].target(),
+        Some(root.join("archive/guide/setup.md").as_path())
+    );
+    assert_eq!(
+        preview.changes[0].replacements[0].new_pattern,
+        "](archive/guide/setup.md)"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_uses_index_content_hash_for_renamed_file() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n\nUnique content.\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    build_index(root, &NoopProgress).unwrap();
+    fs::create_dir(root.join("manual")).unwrap();
+    fs::rename(root.join("guide.md"), root.join("manual/handbook.md")).unwrap();
+
+    let preview = preview_fix(root, &NoopProgress).unwrap();
+    assert_eq!(preview.fixes[0].matched_by, Some(FixMatch::ContentHash));
+
+    fix(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](manual/handbook.md)\n"
+    );
+}
+
+// ============= Manual review tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_leaves_ambiguous_and_unmatched_links_alone() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a/setup.md"), "# A\n");
+    write_file(root.join("b/setup.md"), "# B\n");
+    let content = "[Setup](setup.md)\n[Gone](gone.md)\n[[Missing Note]]\n";
+    write_file(root.join("index.md"), content);
+
+    let preview = preview_fix(root, &NoopProgress).unwrap();
+    assert_eq!(preview.fixes.len(), 3);
+    assert!(preview.fixes.iter().all(|fix| fix.target().is_none()));
+    assert_eq!(preview.fixes[0].candidates.len(), 2);
+    assert!(preview.fixes[1].candidates.is_empty());
+    assert!(preview.changes.is_empty());
+
+    fix(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(read_file(root.join("index.md")), content);
+}
+
+// ============= Dry-run and undo tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_dry_run_changes_nothing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+
+    fix(root, true, &NoopProgress).unwrap();
+
+    assert_eq!(read_file(root.join("index.md")), "[Guide](guide.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_is_recorded_for_undo() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+
+    fix(root, false, &NoopProgress).unwrap();
+    assert_eq!(read_file(root.join("index.md")), "[Guide](docs/guide.md)\n");
+
+    let entry = undo(root).unwrap();
+
+    assert_eq!(entry.operation, "fix");
+    assert_eq!(read_file(root.join("index.md")), "[Guide](guide.md)\n");
+}
+
+// ============= Git history tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_from_git_replays_staged_rename() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(
+        root.join("guide.md"),
+        "[Home](index.md) ![Logo](images/logo.png) [Intro](#intro)\n",
+    );
+    write_file(root.join("images/logo.png"), "png");
+    write_file(root.join("index.md"), "[Guide](guide.md#setup) [[guide]]\n");
+    write_file(root.join("notes/page.md"), "[Guide](../guide.md)\n");
+    commit_all(root);
+    std::fs::create_dir(root.join("docs")).unwrap();
+    git(root, &["mv", "guide.md", "docs/guide.md"]);
+
+    fix_from_git(root, "HEAD", false, &NoopProgress).unwrap();
+
+    assert_eq!(
+        read_file(root.join("docs/guide.md")),
+        "[Home](../index.md) ![Logo](../images/logo.png) [Intro](#intro)\n"
+    );
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](docs/guide.md#setup) [[guide]]\n"
+    );
+    assert_eq!(
+        read_file(root.join("notes/page.md")),
+        "[Guide](../docs/guide.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_fix_from_git_lists_committed_rename_without_writing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md) and [[guide]]\n");
+    commit_all(root);
+    git(root, &["mv", "guide.md", "handbook.md"]);
+    git(root, &["commit", "--quiet", "--message", "rename"]);
+
+    let preview = preview_fix_from_git(root, "HEAD~1", &NoopProgress).unwrap();
+
+    assert_eq!(
+        preview.renames,
+        vec![(
+            root.canonicalize().unwrap().join("guide.md"),
+            root.canonicalize().unwrap().join("handbook.md")
+        )]
+    );
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.changes[0].kind, MoveChangeKind::ReferenceUpdate);
+    assert_eq!(
+        preview.changes[0]
+            .replacements
+            .iter()
+            .map(|replacement| replacement.new_pattern.as_str())
+            .collect::<Vec<_>>(),
+        vec!["](handbook.md)", "handbook"]
+    );
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](guide.md) and [[guide]]\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_from_git_outside_repository_returns_error() {
+    let temp_dir = temp_dir();
+    write_file(temp_dir.path().join("index.md"), "# Index\n");
+
+    let result = fix_from_git(temp_dir.path(), "HEAD", false, &NoopProgress);
+
+    assert!(matches!(result, Err(MdrefError::Git { .. })));
+}
+
+// ============= Known move tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_moved_replays_directory_move() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join("index.md"),
+        "[Setup](docs/setup.md) ![Logo](docs/logo.png)\n",
+    );
+    write_file(
+        root.join("docs/setup.md"),
+        "[Home](../index.md) [Logo](logo.png)\n",
+    );
+    write_file(root.join("docs/logo.png"), "png");
+    fs::create_dir(root.join("manual")).unwrap();
+    fs::rename(root.join("docs"), root.join("manual/guide")).unwrap();
+    let moves = [(root.join("docs"), root.join("manual/guide"))];
+
+    let preview = preview_fix_moved(&root, &moves, &NoopProgress).unwrap();
+    assert_eq!(preview.renames, moves);
+    let kinds: Vec<MoveChangeKind> = preview.changes.iter().map(|change| change.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            MoveChangeKind::ReferenceUpdate,
+            MoveChangeKind::MovedFileUpdate
+        ]
+    );
+
+    fix_moved(&root, &moves, false, &NoopProgress).unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Setup](manual/guide/setup.md) ![Logo](manual/guide/logo.png)\n"
+    );
+    assert_eq!(
+        read_file(root.join("manual/guide/setup.md")),
+        "[Home](../../index.md) [Logo](logo.png)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_fix_moved_ignores_moves_not_on_disk() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    let preview = preview_fix_moved(
+        root,
+        &[
+            (root.join("guide.md"), root.join("index.md")),
+            (root.join("gone.md"), root.join("missing.md")),
+        ],
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(preview.renames.is_empty());
+    assert!(preview.changes.is_empty());
+}
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use mdref::{LinkType, MdrefError, NoopProgress, build_link_graph};
+
+mod common;
+
+use common::{paths, temp_dir, write_file};
+
+// Library tests for `build_link_graph` cover which links become edges and the
+// subtree and neighbourhood filters. Rendering is unit-tested in
+// `core::graph`, and CLI tests only verify the end-to-end process behavior.
+
+// ============= Build tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_build_link_graph_includes_every_local_link_type() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(
+        root.join("index.md"),
+        "[Guide](docs/guide.md#setup)\n[ref]: docs/guide.md\n<img src=\"logo.png\">\n",
+    );
+    write_file(root.join("docs/guide.md"), "[[index]]\n");
+    write_file(root.join("logo.png"), "png");
+
+    let graph = build_link_graph(root, &NoopProgress).unwrap();
+
+    assert_eq!(graph.root, root.canonicalize().unwrap());
+    assert_eq!(
+        graph.nodes,
+        paths(&["docs/guide.md", "index.md", "logo.png"])
+    );
+    let edges: Vec<(&str, &str, usize, LinkType)> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.source.to_str().unwrap(),
+                edge.target.to_str().unwrap(),
+                edge.line,
+                edge.link_type.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("docs/guide.md", "index.md", 1, LinkType::WikiLink),
+            ("index.md", "docs/guide.md", 1, LinkType::Inline),
+            (
+                "index.md",
+                "docs/guide.md",
+                2,
+                LinkType::ReferenceDefinition
+            ),
+            ("index.md", "logo.png", 3, LinkType::HtmlAttribute),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_build_link_graph_skips_external_fragment_and_broken_links() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().join("docs");
+    write_file(
+        root.join("index.md"),
+        "[Web](https://example.com) [Top](#top) [Gone](gone.md) [Up](../README.md)\n",
+    );
+    write_file(temp_dir.path().join("README.md"), "# Readme\n");
+
+    let graph = build_link_graph(&root, &NoopProgress).unwrap();
+
+    assert_eq!(graph.nodes, paths(&["../README.md", "index.md"]));
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(graph.edges[0].link_text, "../README.md");
+}
+
+// ============= Filter tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_link_graph_subtree_keeps_edges_inside_directory() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "[Guide](docs/guide.md)\n");
+    write_file(
+        root.join("docs/guide.md"),
+        "[Setup](setup.md) [Home](../index.md)\n",
+    );
+    write_file(root.join("docs/setup.md"), "# Setup\n");
+
+    let graph = build_link_graph(root, &NoopProgress)
+        .unwrap()
+        .subtree(root.join("docs"))
+        .unwrap();
+
+    assert_eq!(graph.nodes, paths(&["docs/guide.md", "docs/setup.md"]));
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(graph.edges[0].target, PathBuf::from("docs/setup.md"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_link_graph_neighborhood_follows_links_both_ways_up_to_depth() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "[B](b.md)\n");
+    write_file(root.join("b.md"), "[C](c.md)\n");
+    write_file(root.join("c.md"), "[D](d.md)\n");
+    write_file(root.join("d.md"), "# D\n");
+    write_file(root.join("e.md"), "[C](c.md)\n");
+    let graph = build_link_graph(root, &NoopProgress).unwrap();
+
+    let around = graph.neighborhood(root.join("c.md"), 1).unwrap();
+    assert_eq!(around.nodes, paths(&["b.md", "c.md", "d.md", "e.md"]));
+    assert_eq!(around.edges.len(), 3);
+
+    let around = graph.neighborhood(root.join("c.md"), 2).unwrap();
+    assert_eq!(
+        around.nodes,
+        paths(&["a.md", "b.md", "c.md", "d.md", "e.md"])
+    );
+
+    let around = graph.neighborhood(root.join("c.md"), 0).unwrap();
+    assert_eq!(around.nodes, paths(&["c.md"]));
+    assert!(around.edges.is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_link_graph_filter_outside_root_returns_error() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().join("docs");
+    write_file(root.join("index.md"), "# Index\n");
+    write_file(temp_dir.path().join("other.md"), "# Other\n");
+    let graph = build_link_graph(&root, &NoopProgress).unwrap();
+
+    let result = graph.neighborhood(temp_dir.path().join("other.md"), 1);
+
+    assert!(matches!(result, Err(MdrefError::PathValidation { .. })));
+}
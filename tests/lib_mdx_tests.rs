@@ -0,0 +1,303 @@
+use std::path::PathBuf;
+
+use mdref::{
+    BrokenLinkKind, LinkType, NoopProgress, RemoveMode, check_links, find_links, find_references,
+    mv, rm,
+};
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for MDX documents and the other Markdown extensions cover
+// which links are found around JSX and ESM, and how moves rewrite them.
+
+const GUIDE_MDX: &str = "\
+import Tabs from '@theme/Tabs';
+import Intro from './intro.mdx';
+
+# Guide
+
+<Tabs groupId=\"os\" values={[{ label: 'Linux', value: 'linux' }]}>
+    <TabItem value=\"linux\">
+        See [setup](setup.md) and <Badge>[FAQ](faq.markdown)</Badge>.
+    </TabItem>
+</Tabs>
+
+```jsx
+<Link to=\"./missing.mdx\" />
+```
+";
+
+// ============= Finding links =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_links_in_mdx_skips_jsx_and_finds_imports() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.mdx"), GUIDE_MDX);
+
+    let links = find_links(root.join("guide.mdx")).unwrap();
+
+    let found: Vec<(usize, usize, &str, &LinkType)> = links
+        .iter()
+        .map(|link| {
+            (
+                link.line,
+                link.column,
+                link.link_text.as_str(),
+                &link.link_type,
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (8, 13, "setup.md", &LinkType::Inline),
+            (8, 42, "faq.markdown", &LinkType::Inline),
+            (2, 20, "./intro.mdx", &LinkType::Import),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_references_scans_other_markdown_extensions() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("target.md"), "# Target\n");
+    write_file(root.join("a.markdown"), "[Target](target.md)\n");
+    write_file(root.join("b.mdown"), "[Target](target.md)\n");
+    write_file(
+        root.join("c.mdx"),
+        "<Note>\n  [Target](target.md)\n</Note>\n",
+    );
+    write_file(root.join("d.txt"), "[Target](target.md)\n");
+
+    let references = find_references(root.join("target.md"), root, &NoopProgress).unwrap();
+
+    let mut referrers: Vec<PathBuf> = references
+        .iter()
+        .map(|reference| PathBuf::from(reference.path.file_name().unwrap()))
+        .collect();
+    referrers.sort();
+    assert_eq!(
+        referrers,
+        [
+            PathBuf::from("a.markdown"),
+            PathBuf::from("b.mdown"),
+            PathBuf::from("c.mdx"),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_reports_missing_mdx_imports() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.mdx"), GUIDE_MDX);
+    write_file(root.join("setup.md"), "# Setup\n");
+    write_file(root.join("faq.markdown"), "# FAQ\n");
+
+    let broken = check_links(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_text, "./intro.mdx");
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingTarget);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_check_links_resolves_wikilinks_to_other_markdown_extensions() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("page.markdown"), "# Page\n");
+    write_file(root.join("guide.mdx"), "# Guide\n");
+    write_file(
+        root.join("index.md"),
+        "[[page]] [[guide#Guide]] [[missing]]\n",
+    );
+
+    let broken = check_links(root, false, &NoopProgress).unwrap();
+
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].link_text, "missing");
+}
+
+// ============= Moves =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_links_and_imports_in_mdx() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("guide.mdx"), GUIDE_MDX);
+    write_file(root.join("intro.mdx"), "# Intro\n");
+    write_file(root.join("setup.md"), "# Setup\n");
+    write_file(root.join("faq.markdown"), "# FAQ\n");
+
+    mv(
+        root.join("intro.mdx"),
+        root.join("parts/intro.mdx"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    mv(
+        root.join("guide.mdx"),
+        root.join("docs/guide.mdx"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    let guide = read_file(root.join("docs/guide.mdx"));
+    assert!(guide.contains("import Intro from '../parts/intro.mdx';"));
+    assert!(guide.contains("import Tabs from '@theme/Tabs';"));
+    assert!(
+        guide.contains(
+            "        See [setup](../setup.md) and <Badge>[FAQ](../faq.markdown)</Badge>."
+        )
+    );
+    assert!(guide.contains("<Link to=\"./missing.mdx\" />"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_keeps_relative_prefix_on_imports() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join("docs/page.mdx"),
+        "import Table from '../shared/table.mdx';\n\n<Table />\n",
+    );
+    write_file(root.join("shared/table.mdx"), "| a |\n");
+
+    mv(
+        root.join("shared/table.mdx"),
+        root.join("docs/table.mdx"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("docs/page.mdx")),
+        "import Table from './table.mdx';\n\n<Table />\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_directory_updates_moved_markdown_files_of_every_extension() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("index.md"), "# Home\n");
+    write_file(root.join("docs/a.markdown"), "[Home](../index.md)\n");
+    write_file(
+        root.join("docs/b.mdx"),
+        "<Note>\n  [Home](../index.md)\n</Note>\n",
+    );
+
+    mv(
+        root.join("docs"),
+        root.join("manual/docs"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("manual/docs/a.markdown")),
+        "[Home](../../index.md)\n"
+    );
+    assert_eq!(
+        read_file(root.join("manual/docs/b.mdx")),
+        "<Note>\n  [Home](../../index.md)\n</Note>\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_import_right_after_frontmatter() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join("docs/page.mdx"),
+        "---\ntitle: A\n---\nimport Y from \"../comp/y.mdx\";\n\n<Y />\n",
+    );
+    write_file(root.join("comp/y.mdx"), "# Y\n");
+
+    let references = find_references(root.join("comp/y.mdx"), &root, &NoopProgress).unwrap();
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].line, 4);
+    assert_eq!(references[0].link_type, LinkType::Import);
+
+    mv(
+        root.join("comp/y.mdx"),
+        root.join("lib/y.mdx"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("docs/page.mdx")),
+        "---\ntitle: A\n---\nimport Y from \"../lib/y.mdx\";\n\n<Y />\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_wikilinks_to_other_markdown_extensions() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("page.markdown"), "# Page\n");
+    write_file(root.join("index.md"), "[[page]] [[page.markdown|Page]]\n");
+
+    mv(
+        root.join("page.markdown"),
+        root.join("moved.markdown"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[[moved]] [[moved.markdown|Page]]\n"
+    );
+}
+
+// ============= Removal =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_unlink_refuses_imported_file() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(
+        root.join("page.mdx"),
+        "import Intro from './intro.mdx';\n\n<Intro />\n",
+    );
+    write_file(root.join("intro.mdx"), "# Intro\n");
+
+    let result = rm(
+        root.join("intro.mdx"),
+        &root,
+        &RemoveMode::Unlink,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(result.is_err());
+    assert!(root.join("intro.mdx").exists());
+}
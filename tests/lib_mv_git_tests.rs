@@ -0,0 +1,249 @@
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::{fs, path::Path, process::Command};
+
+use mdref::{GitMode, MdrefError, NoopProgress, mv_with_git, transaction_log_path};
+
+mod common;
+
+use common::{commit_all, git, read_file, temp_dir, write_file};
+
+// Library tests for `mv_with_git` cover what ends up in the git index: renames
+// recorded by `git mv`, staged referrers, untracked sources, the refusal of
+// sources with uncommitted changes, and the index reset on rollback.
+
+/// `git status --porcelain` for the whole repository, one entry per line.
+fn git_status(root: &Path) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// ============= git mv tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_stages_rename_and_rewritten_referrers() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    let body = "# Guide\n\nInstall the tool.\n\nRun it from the root.\n\n";
+    write_file(root.join("guide.md"), &format!("{body}[Home](index.md)\n"));
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    commit_all(root);
+
+    mv_with_git(
+        root.join("guide.md"),
+        root.join("docs/guide.md"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("docs/guide.md")),
+        format!("{body}[Home](../index.md)\n")
+    );
+    assert_eq!(read_file(root.join("index.md")), "[Guide](docs/guide.md)\n");
+    assert_eq!(
+        git_status(root),
+        "R  guide.md -> docs/guide.md\nM  index.md\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_moves_tracked_directory() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](docs/guide.md)\n");
+    commit_all(root);
+
+    mv_with_git(
+        root.join("docs"),
+        root.join("manual"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Guide](manual/guide.md)\n"
+    );
+    assert_eq!(
+        git_status(root),
+        "M  index.md\nR  docs/guide.md -> manual/guide.md\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_moves_untracked_source_on_filesystem() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "# Index\n");
+    commit_all(root);
+    write_file(root.join("draft.md"), "# Draft\n");
+    write_file(root.join("index.md"), "[Draft](draft.md)\n");
+    git(root, &["add", "index.md"]);
+
+    mv_with_git(
+        root.join("draft.md"),
+        root.join("notes/draft.md"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(root.join("notes/draft.md").exists());
+    assert_eq!(git_status(root), "M  index.md\n?? notes/draft.md\n");
+}
+
+// ============= Detection tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_outside_repository() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+
+    let result = mv_with_git(
+        root.join("guide.md"),
+        root.join("docs/guide.md"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    );
+    assert!(matches!(result, Err(MdrefError::Git { .. })));
+    assert!(root.join("guide.md").exists());
+
+    mv_with_git(
+        root.join("guide.md"),
+        root.join("docs/guide.md"),
+        root,
+        GitMode::Auto,
+        false,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    assert!(root.join("docs/guide.md").exists());
+}
+
+// ============= Uncommitted change tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_refuses_source_with_uncommitted_changes_unless_forced() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+    commit_all(root);
+    write_file(root.join("guide.md"), "# Guide\n\nNew section.\n");
+
+    let result = mv_with_git(
+        root.join("guide.md"),
+        root.join("handbook.md"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    );
+    assert!(matches!(result, Err(MdrefError::UncommittedChanges { .. })));
+    assert!(root.join("guide.md").exists());
+
+    mv_with_git(
+        root.join("guide.md"),
+        root.join("handbook.md"),
+        root,
+        GitMode::Always,
+        true,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    assert_eq!(
+        read_file(root.join("handbook.md")),
+        "# Guide\n\nNew section.\n"
+    );
+    assert_eq!(git_status(root), "RM guide.md -> handbook.md\n");
+}
+
+// ============= Rollback tests =============
+
+#[test]
+#[cfg(unix)]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_rollback_resets_index() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    commit_all(root);
+    let index = root.join("index.md");
+    fs::set_permissions(&index, fs::Permissions::from_mode(0o444)).unwrap();
+
+    let result = mv_with_git(
+        root.join("guide.md"),
+        root.join("docs/guide.md"),
+        root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    );
+    fs::set_permissions(&index, fs::Permissions::from_mode(0o644)).unwrap();
+
+    assert!(result.is_err());
+    assert!(root.join("guide.md").exists());
+    assert!(!root.join("docs/guide.md").exists());
+    assert_eq!(git_status(root), "");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_git_failed_git_mv_restores_index_and_drops_log() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().join("repo");
+    write_file(root.join("guide.md"), "# Guide\n");
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    commit_all(&root);
+    let index_before = fs::read(root.join(".git/index")).unwrap();
+
+    // `git mv` refuses a destination outside the work tree.
+    let result = mv_with_git(
+        root.join("guide.md"),
+        temp_dir.path().join("elsewhere/guide.md"),
+        &root,
+        GitMode::Always,
+        false,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(matches!(result, Err(MdrefError::Git { .. })));
+    assert!(root.join("guide.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[Guide](guide.md)\n");
+    assert_eq!(fs::read(root.join(".git/index")).unwrap(), index_before);
+    assert!(!transaction_log_path(&root).exists());
+    assert_eq!(git_status(&root), "");
+}
@@ -2,43 +2,20 @@
 use std::os::unix::fs::PermissionsExt;
 use std::{
     fs,
-    path::{Path, PathBuf},
-    sync::{LazyLock, Mutex},
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
-use mdref::{MdrefError, NoopProgress, find_links, find_references, mv};
+use mdref::{
+    MdrefError, MovePreview, NoopProgress, ProgressReporter, apply_move_preview, find_links,
+    find_references, mv, mv_many, preview_move, preview_move_many,
+};
 use rstest::rstest;
 use tempfile::TempDir;
 
 mod common;
 
-use common::{fixture_directory_move, fixture_unicode_paths, write_file};
-
-static CURRENT_DIR_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
-
-struct CurrentDirGuard {
-    original_dir: PathBuf,
-}
-
-impl CurrentDirGuard {
-    fn enter(path: &Path) -> Self {
-        let original_dir = std::env::current_dir().unwrap();
-        std::env::set_current_dir(path).unwrap();
-        Self { original_dir }
-    }
-}
-
-impl Drop for CurrentDirGuard {
-    fn drop(&mut self) {
-        std::env::set_current_dir(&self.original_dir).unwrap();
-    }
-}
-
-fn with_current_dir<T>(path: &Path, operation: impl FnOnce() -> T) -> T {
-    let _lock = CURRENT_DIR_LOCK.lock().unwrap();
-    let _guard = CurrentDirGuard::enter(path);
-    operation()
-}
+use common::{fixture_directory_move, fixture_unicode_paths, with_current_dir, write_file};
 
 // Library tests for `mv` cover path rewriting and filesystem mutations.
 // CLI tests keep only representative command wiring and process-contract checks.
@@ -2786,6 +2763,758 @@ fn test_mv_directory_dry_run_with_images() {
     );
 }
 
+// ============= Wikilink tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_wikilink_bare_name_left_alone_when_still_unique() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("Topic.md");
+    let index = root.join("index.md");
+    write_file(&source, "# Topic");
+    write_file(&index, "[[Topic]] and [[Topic#Intro|intro]]\n");
+
+    mv(
+        &source,
+        root.join("archive/Topic.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&index).unwrap(),
+        "[[Topic]] and [[Topic#Intro|intro]]\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_wikilink_rename_rewrites_target_and_keeps_style() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("notes/Topic.md");
+    let index = root.join("index.md");
+    write_file(&source, "# Topic");
+    write_file(
+        &index,
+        "[[Topic]] [[Topic.md|alias]] [[notes/Topic#Intro]] ![[Topic]]\n| [[Topic\\|cell]] |\n",
+    );
+
+    mv(
+        &source,
+        root.join("notes/Subject.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&index).unwrap(),
+        "[[Subject]] [[Subject.md|alias]] [[notes/Subject#Intro]] ![[Subject]]\n| [[Subject\\|cell]] |\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_wikilink_uses_vault_path_when_name_becomes_ambiguous() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("Draft.md");
+    let index = root.join("index.md");
+    write_file(&source, "# Draft");
+    write_file(root.join("other/Final.md"), "# Other final");
+    write_file(&index, "[[Draft]]\n");
+
+    mv(
+        &source,
+        root.join("deep/dir/Final.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(fs::read_to_string(&index).unwrap(), "[[deep/dir/Final]]\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_wikilink_relative_links_in_moved_file_are_rewritten() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("notes/page.md");
+    write_file(&source, "[[./sibling]] [[sibling]] ![[../img/a.png]]\n");
+    write_file(root.join("notes/sibling.md"), "");
+    write_file(root.join("img/a.png"), "");
+
+    mv(
+        &source,
+        root.join("archive/2024/page.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("archive/2024/page.md")).unwrap(),
+        "[[../../notes/sibling]] [[sibling]] ![[../../img/a.png]]\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_directory_rewrites_wikilink_paths_into_moved_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(
+        root.join("projects/alpha/plan.md"),
+        "[[projects/alpha/notes]]\n",
+    );
+    write_file(root.join("projects/alpha/notes.md"), "");
+    write_file(root.join("index.md"), "[[projects/alpha/plan]] [[plan]]\n");
+
+    mv(
+        root.join("projects/alpha"),
+        root.join("archive/alpha"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("index.md")).unwrap(),
+        "[[archive/alpha/plan]] [[plan]]\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("archive/alpha/plan.md")).unwrap(),
+        "[[archive/alpha/notes]]\n"
+    );
+}
+
+// ============= HTML attribute tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_html_attributes_and_preserves_quoting() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("logo.png");
+    let readme = root.join("README.md");
+    write_file(&source, "");
+    write_file(
+        &readme,
+        "<p align=\"center\">\n  <img src=\"logo.png\" alt='logo.png'><img src='logo.png' width=100><img src=logo.png>\n</p>\n\n> <a href=\"logo.png\">Logo</a>\n",
+    );
+
+    mv(
+        &source,
+        root.join("assets/logo.png"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&readme).unwrap(),
+        "<p align=\"center\">\n  <img src=\"assets/logo.png\" alt='logo.png'><img src='assets/logo.png' width=100><img src=assets/logo.png>\n</p>\n\n> <a href=\"assets/logo.png\">Logo</a>\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_srcset_candidates_and_encodes_spaces() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("dark@2x.png");
+    let readme = root.join("README.md");
+    write_file(&source, "");
+    write_file(root.join("dark.png"), "");
+    write_file(
+        &readme,
+        "<picture>\n  <source srcset=\"dark.png 1x, dark@2x.png 2x\">\n</picture>\n",
+    );
+
+    mv(
+        &source,
+        root.join("dark images/dark@2x.png"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&readme).unwrap(),
+        "<picture>\n  <source srcset=\"dark.png 1x, dark%20images/dark@2x.png 2x\">\n</picture>\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_rewrites_html_links_inside_moved_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("guide.md");
+    write_file(root.join("logo.png"), "");
+    write_file(
+        &source,
+        "<img src=\"logo.png\"> and <a href=\"#top\">top</a>\n",
+    );
+
+    mv(
+        &source,
+        root.join("docs/guide.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("docs/guide.md")).unwrap(),
+        "<img src=\"../logo.png\"> and <a href=\"#top\">top</a>\n"
+    );
+}
+
+// ============= Link index tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_index_rewrites_references_across_consecutive_moves() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let index = root.join("index.md");
+    write_file(root.join("a.md"), "# A");
+    write_file(&index, "[A](a.md)\n");
+    mdref::build_index(root, &NoopProgress).unwrap();
+
+    mv(
+        root.join("a.md"),
+        root.join("docs/a.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    mv(
+        root.join("docs/a.md"),
+        root.join("b.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(fs::read_to_string(&index).unwrap(), "[A](b.md)\n");
+}
+
+// ============= Batch move tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_chain_moves_back_to_front_and_rewrites_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let index = root.join("index.md");
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("b.md"), "# B");
+    write_file(&index, "[A](a.md) [B](b.md)\n");
+
+    mv_many(
+        &[
+            (root.join("a.md"), root.join("b.md")),
+            (root.join("b.md"), root.join("c.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("a.md").exists());
+    assert_eq!(fs::read_to_string(root.join("b.md")).unwrap(), "# A");
+    assert_eq!(fs::read_to_string(root.join("c.md")).unwrap(), "# B");
+    assert_eq!(fs::read_to_string(&index).unwrap(), "[A](b.md) [B](c.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_swap_exchanges_files_and_links_between_them() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n[B](docs/b.md)\n");
+    write_file(root.join("docs/b.md"), "# B\n[A](../a.md)\n");
+
+    mv_many(
+        &[
+            (root.join("a.md"), root.join("docs/b.md")),
+            (root.join("docs/b.md"), root.join("a.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("a.md")).unwrap(),
+        "# B\n[A](docs/b.md)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("docs/b.md")).unwrap(),
+        "# A\n[B](../a.md)\n"
+    );
+    assert!(!root.join(".a.md.mdref-tmp").exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_directory_and_file_rewrite_links_between_moved_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "[Notes](../notes.md)\n");
+    write_file(root.join("notes.md"), "[Guide](docs/guide.md)\n");
+    write_file(
+        root.join("index.md"),
+        "[Guide](docs/guide.md) [Notes](notes.md)\n",
+    );
+
+    mv_many(
+        &[
+            (root.join("docs"), root.join("manual")),
+            (root.join("notes.md"), root.join("archive/notes.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("manual/guide.md")).unwrap(),
+        "[Notes](../archive/notes.md)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("archive/notes.md")).unwrap(),
+        "[Guide](../manual/guide.md)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("index.md")).unwrap(),
+        "[Guide](manual/guide.md) [Notes](archive/notes.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_shared_destination_returns_error_and_moves_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("b.md"), "# B");
+
+    let error = mv_many(
+        &[
+            (root.join("a.md"), root.join("c.md")),
+            (root.join("b.md"), root.join("c.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap_err();
+
+    assert!(
+        error
+            .to_string()
+            .contains("destination is used by more than one move")
+    );
+    assert!(root.join("a.md").exists());
+    assert!(root.join("b.md").exists());
+    assert!(!root.join("c.md").exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_existing_destination_outside_batch_returns_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("taken.md"), "# Taken");
+
+    let error = mv_many(
+        &[(root.join("a.md"), root.join("taken.md"))],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap_err();
+
+    assert!(
+        error
+            .to_string()
+            .contains("destination path already exists")
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("taken.md")).unwrap(),
+        "# Taken"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_many_failure_mid_batch_rolls_back_completed_moves() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let index = root.join("index.md");
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("b.md"), "# B");
+    write_file(root.join("blocker"), "not a directory");
+    write_file(&index, "[A](a.md) [B](b.md)\n");
+
+    // `blocker` is a file, so creating `blocker/b.md` fails after `a.md` moved.
+    let result = mv_many(
+        &[
+            (root.join("a.md"), root.join("docs/a.md")),
+            (root.join("b.md"), root.join("blocker/b.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(result.is_err());
+    assert!(root.join("a.md").exists());
+    assert!(root.join("b.md").exists());
+    assert!(!root.join("docs/a.md").exists());
+    assert_eq!(fs::read_to_string(&index).unwrap(), "[A](a.md) [B](b.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_move_many_lists_pairs_without_mutating() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let index = root.join("index.md");
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("b.md"), "# B");
+    write_file(&index, "[A](a.md) [B](b.md)\n");
+
+    let preview = preview_move_many(
+        &[
+            (root.join("a.md"), root.join("docs/a.md")),
+            (root.join("b.md"), root.join("docs/b.md")),
+        ],
+        root,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        preview.moves,
+        vec![
+            (root.join("a.md"), root.join("docs/a.md")),
+            (root.join("b.md"), root.join("docs/b.md")),
+        ]
+    );
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.changes[0].replacements.len(), 2);
+    assert!(root.join("a.md").exists());
+    assert_eq!(fs::read_to_string(&index).unwrap(), "[A](a.md) [B](b.md)\n");
+}
+
+// ============= Unified diff tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_move_preview_to_unified_diff_renders_rename_with_moved_file_hunk() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(
+        root.join("guide.md"),
+        "# Guide\n\nOne\nTwo\nThree\nFour\n[Other](other.md)\n",
+    );
+    write_file(root.join("other.md"), "# Other");
+
+    let preview = preview_move(
+        root.join("guide.md"),
+        root.join("docs/guide.md"),
+        root,
+        &NoopProgress,
+    )
+    .unwrap();
+    let diff = preview.to_unified_diff(root).unwrap();
+
+    assert_eq!(
+        diff,
+        "diff --git a/guide.md b/docs/guide.md\n\
+         rename from guide.md\n\
+         rename to docs/guide.md\n\
+         --- a/guide.md\n\
+         +++ b/docs/guide.md\n\
+         @@ -4,4 +4,4 @@\n \
+         Two\n \
+         Three\n \
+         Four\n\
+         -[Other](other.md)\n\
+         +[Other](../other.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_move_preview_to_unified_diff_lists_every_file_of_moved_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("docs/a.md"), "# A");
+    write_file(root.join("docs/img/logo.png"), "");
+    write_file(root.join("index.md"), "[A](docs/a.md)\r\n");
+
+    let preview =
+        preview_move(root.join("docs"), root.join("manual"), root, &NoopProgress).unwrap();
+    let diff = preview.to_unified_diff(root).unwrap();
+
+    assert_eq!(
+        diff,
+        "diff --git a/docs/a.md b/manual/a.md\n\
+         rename from docs/a.md\n\
+         rename to manual/a.md\n\
+         diff --git a/docs/img/logo.png b/manual/img/logo.png\n\
+         rename from docs/img/logo.png\n\
+         rename to manual/img/logo.png\n\
+         diff --git a/index.md b/index.md\n\
+         --- a/index.md\n\
+         +++ b/index.md\n\
+         @@ -1,1 +1,1 @@\n\
+         -[A](docs/a.md)\r\n\
+         +[A](manual/a.md)\r\n"
+    );
+}
+
+// ============= Saved plan tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_apply_move_preview_after_json_round_trip_moves_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "[Notes](../notes.md)\n");
+    write_file(root.join("docs/img/logo.png"), "");
+    write_file(root.join("notes.md"), "[Guide](docs/guide.md)\n");
+
+    let preview =
+        preview_move(root.join("docs"), root.join("manual"), root, &NoopProgress).unwrap();
+    let json = serde_json::to_string(&preview).unwrap();
+    let restored: MovePreview = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, preview);
+
+    apply_move_preview(&restored, root).unwrap();
+
+    assert!(!root.join("docs").exists());
+    assert!(root.join("manual/img/logo.png").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("manual/guide.md")).unwrap(),
+        "[Notes](../notes.md)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("notes.md")).unwrap(),
+        "[Guide](manual/guide.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_apply_move_preview_executes_batch_swap() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n[B](b.md)\n");
+    write_file(root.join("b.md"), "# B\n");
+
+    let preview = preview_move_many(
+        &[
+            (root.join("a.md"), root.join("b.md")),
+            (root.join("b.md"), root.join("a.md")),
+        ],
+        root,
+        &NoopProgress,
+    )
+    .unwrap();
+    apply_move_preview(&preview, root).unwrap();
+
+    assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "# B\n");
+    assert_eq!(
+        fs::read_to_string(root.join("b.md")).unwrap(),
+        "# A\n[B](a.md)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_apply_move_preview_refuses_when_moved_file_changed() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("a.md");
+    write_file(&source, "# A");
+    write_file(root.join("index.md"), "[A](a.md)");
+
+    let preview = preview_move(&source, root.join("docs/a.md"), root, &NoopProgress).unwrap();
+    write_file(&source, "# A\n[Index](index.md)");
+
+    let error = apply_move_preview(&preview, root).unwrap_err();
+
+    assert!(matches!(
+        error,
+        MdrefError::ConcurrentModification { ref paths } if paths.len() == 1
+    ));
+    assert!(source.exists());
+    assert_eq!(
+        fs::read_to_string(root.join("index.md")).unwrap(),
+        "[A](a.md)"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_apply_move_preview_reports_missing_file_as_missing() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("a.md");
+    write_file(&source, "# A");
+    write_file(root.join("index.md"), "[A](a.md)");
+
+    let preview = preview_move(&source, root.join("docs/a.md"), root, &NoopProgress).unwrap();
+    fs::remove_file(root.join("index.md")).unwrap();
+
+    let error = apply_move_preview(&preview, root).unwrap_err();
+
+    match error {
+        MdrefError::PathValidation { path, details } => {
+            assert_eq!(path, root.canonicalize().unwrap().join("index.md"));
+            assert_eq!(details, "file in the plan no longer exists");
+        }
+        other => panic!("expected a path validation error, got {other:?}"),
+    }
+    assert!(source.exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_apply_move_preview_into_absolute_applies_from_another_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("a.md"), "# A");
+    write_file(root.join("index.md"), "[A](a.md)");
+    write_file(root.join("notes/todo.md"), "# Todo");
+
+    let preview = with_current_dir(&root, || {
+        preview_move("a.md", "docs/a.md", ".", &NoopProgress)
+            .unwrap()
+            .into_absolute()
+    });
+    with_current_dir(&root.join("notes"), || {
+        apply_move_preview(&preview, &root).unwrap();
+    });
+
+    assert!(root.join("docs/a.md").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("index.md")).unwrap(),
+        "[A](docs/a.md)"
+    );
+}
+
+// ============= Concurrent edit tests =============
+
+/// Progress reporter that overwrites `path` with `content` once every file of
+/// the scan has been read, simulating an editor saving mid-operation.
+struct EditAfterScan {
+    path: PathBuf,
+    content: &'static str,
+    total: AtomicU64,
+    done: AtomicU64,
+}
+
+impl EditAfterScan {
+    fn new(path: PathBuf, content: &'static str) -> Self {
+        Self {
+            path,
+            content,
+            total: AtomicU64::new(0),
+            done: AtomicU64::new(0),
+        }
+    }
+}
+
+impl ProgressReporter for EditAfterScan {
+    fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::SeqCst);
+    }
+
+    fn inc(&self, delta: u64) {
+        let done = self.done.fetch_add(delta, Ordering::SeqCst) + delta;
+        if done == self.total.load(Ordering::SeqCst) {
+            write_file(&self.path, self.content);
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_reference_edited_during_scan_returns_concurrent_modification() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let source = root.join("a.md");
+    let index = root.join("index.md");
+    write_file(&source, "# A");
+    write_file(&index, "[A](a.md)");
+
+    let progress = EditAfterScan::new(index.clone(), "Intro\n[A](a.md)");
+    let result = mv(&source, root.join("docs/a.md"), root, false, &progress);
+
+    match result {
+        Err(MdrefError::ConcurrentModification { paths }) => assert_eq!(paths, vec![index.clone()]),
+        other => panic!("expected ConcurrentModification, got {other:?}"),
+    }
+    assert!(source.exists());
+    assert!(!root.join("docs/a.md").exists());
+    assert_eq!(fs::read_to_string(&index).unwrap(), "Intro\n[A](a.md)");
+    assert!(!root.join(".mdref/transaction").exists());
+
+    // Re-planning picks up the edit.
+    mv(&source, root.join("docs/a.md"), root, false, &NoopProgress).unwrap();
+    assert_eq!(fs::read_to_string(&index).unwrap(), "Intro\n[A](docs/a.md)");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_directory_file_edited_during_scan_returns_concurrent_modification() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "[Index](../index.md)");
+    write_file(root.join("index.md"), "[Guide](docs/guide.md)");
+
+    let progress = EditAfterScan::new(root.join("index.md"), "[Guide](docs/guide.md)\n");
+    let result = mv(
+        root.join("docs"),
+        root.join("manual"),
+        root,
+        false,
+        &progress,
+    );
+
+    assert!(matches!(
+        result,
+        Err(MdrefError::ConcurrentModification { .. })
+    ));
+    assert!(root.join("docs/guide.md").exists());
+    assert!(!root.join("manual").exists());
+}
+
 // ============= Regression tests for #6: relative source + self-reference =============
 
 /// Regression test for #6.
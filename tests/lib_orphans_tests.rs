@@ -0,0 +1,96 @@
+use mdref::{DEFAULT_ENTRYPOINTS, NoopProgress, find_orphans};
+
+mod common;
+
+use common::{paths, temp_dir, write_file};
+
+// Library tests for `find_orphans` cover each list of the report and how
+// entrypoints are matched. CLI tests only verify the end-to-end process
+// behavior.
+
+// ============= Report tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_orphans_reports_every_kind_of_problem() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(
+        root.join("README.md"),
+        "[Guide](docs/guide.md) ![Logo](img/logo.png)\n",
+    );
+    write_file(
+        root.join("docs/guide.md"),
+        "[Home](../README.md) [[leaf]]\n",
+    );
+    write_file(root.join("docs/leaf.md"), "![Logo](../img/logo.png)\n");
+    write_file(root.join("old/a.md"), "[B](b.md) [A](a.md)\n");
+    write_file(root.join("old/b.md"), "[Guide](../docs/guide.md)\n");
+    write_file(root.join("img/logo.png"), "png");
+    write_file(root.join("img/unused.png"), "png");
+
+    let report = find_orphans(root, DEFAULT_ENTRYPOINTS, &NoopProgress).unwrap();
+
+    assert_eq!(report.entrypoints, paths(&["README.md"]));
+    assert_eq!(report.orphans, paths(&["old/a.md"]));
+    assert_eq!(report.unreachable, vec![paths(&["old/a.md", "old/b.md"])]);
+    assert_eq!(report.unused_assets, paths(&["img/unused.png"]));
+    assert_eq!(report.dead_ends, paths(&["docs/leaf.md"]));
+    assert!(!report.is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_orphans_separates_unconnected_groups() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "# Home\n");
+    write_file(root.join("a.md"), "[B](b.md)\n");
+    write_file(root.join("b.md"), "# B\n");
+    write_file(root.join("c.md"), "# C\n");
+
+    let report = find_orphans(root, DEFAULT_ENTRYPOINTS, &NoopProgress).unwrap();
+
+    assert_eq!(
+        report.unreachable,
+        vec![paths(&["a.md", "b.md"]), paths(&["c.md"])]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_orphans_connected_tree_is_empty() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "[Guide](guide.md)\n");
+    write_file(root.join("guide.md"), "[Home](index.md)\n");
+
+    let report = find_orphans(root, DEFAULT_ENTRYPOINTS, &NoopProgress).unwrap();
+
+    assert!(report.is_empty());
+}
+
+// ============= Entrypoint tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_find_orphans_matches_entrypoint_names_in_every_directory() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("index.md"), "[Home](index.md)\n");
+    write_file(root.join("api/index.md"), "[Call](call.md)\n");
+    write_file(root.join("api/call.md"), "[API](index.md)\n");
+
+    let report = find_orphans(root, &["index.md"], &NoopProgress).unwrap();
+    assert_eq!(report.entrypoints, paths(&["api/index.md", "index.md"]));
+    assert!(report.orphans.is_empty());
+    assert!(report.unreachable.is_empty());
+
+    let report = find_orphans(root, &["./index.md"], &NoopProgress).unwrap();
+    assert_eq!(report.entrypoints, paths(&["index.md"]));
+    assert!(report.orphans.is_empty());
+    assert_eq!(
+        report.unreachable,
+        vec![paths(&["api/call.md", "api/index.md"])]
+    );
+}
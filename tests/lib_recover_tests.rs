@@ -0,0 +1,240 @@
+use std::{fs, path::Path};
+
+use mdref::{
+    MdrefError, NoopProgress, RecoveryAction, history, interrupted_operation, mv, recover,
+    transaction_log_path,
+};
+use serde_json::json;
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for the write-ahead log: a log left under
+// `<root>/.mdref/transaction` marks an interrupted operation, which `recover`
+// rolls back or forward.
+
+/// Leave `root` as a crash would during `mv a.md docs/a.md`: the move has
+/// happened but `index.md` still links to the old path.
+fn simulate_interrupted_mv(root: &Path) {
+    write_file(root.join("docs/a.md"), "# A\n");
+    write_file(root.join("index.md"), "[A](a.md)\n");
+
+    let header = json!({
+        "version": 1,
+        "operation": "mv",
+        "moves": [[root.join("a.md"), root.join("docs/a.md")]],
+        "files": [{
+            "original_path": root.join("index.md"),
+            "final_path": root.join("index.md"),
+            "original": "[A](a.md)\n",
+            "rewritten": "[A](docs/a.md)\n",
+        }],
+    });
+    let step = json!({ "from": root.join("a.md"), "to": root.join("docs/a.md") });
+    write_file(
+        transaction_log_path(root),
+        &format!("{header}\n{step}\n{{\"from\":"),
+    );
+}
+
+/// Leave `root` as a crash would during `rm gone.md --unlink`: the file is
+/// parked but `index.md` still links to it.
+fn simulate_interrupted_rm(root: &Path) {
+    let parked = root.join(".gone.md.mdref-tmp");
+    write_file(&parked, "# Gone\n");
+    write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+    let header = json!({
+        "version": 1,
+        "operation": "rm",
+        "moves": [[root.join("gone.md"), &parked]],
+        "files": [{
+            "original_path": root.join("index.md"),
+            "final_path": root.join("index.md"),
+            "original": "[Gone](gone.md)\n",
+            "rewritten": "Gone\n",
+        }],
+        "discard_moved": true,
+    });
+    let step = json!({ "from": root.join("gone.md"), "to": parked });
+    write_file(transaction_log_path(root), &format!("{header}\n{step}\n"));
+}
+
+// ============= Recover tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_interrupted_operation_reports_started_moves() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_mv(&root);
+
+    let interrupted = interrupted_operation(&root).unwrap().unwrap();
+
+    assert_eq!(interrupted.operation, "mv");
+    assert_eq!(interrupted.moves.len(), 1);
+    // The torn last line never reached the disk, so only one move started.
+    assert_eq!(interrupted.started_moves, 1);
+    assert_eq!(interrupted.files, vec![root.join("index.md")]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_recover_roll_back_restores_original_tree() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_mv(&root);
+
+    recover(&root, RecoveryAction::RollBack).unwrap();
+
+    assert_eq!(read_file(root.join("a.md")), "# A\n");
+    assert!(!root.join("docs/a.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[A](a.md)\n");
+    assert!(!transaction_log_path(&root).exists());
+    assert!(history(&root).unwrap().is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_recover_roll_forward_completes_operation() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_mv(&root);
+
+    let recovered = recover(&root, RecoveryAction::RollForward).unwrap();
+
+    assert_eq!(recovered.operation, "mv");
+    assert!(!root.join("a.md").exists());
+    assert_eq!(read_file(root.join("docs/a.md")), "# A\n");
+    assert_eq!(read_file(root.join("index.md")), "[A](docs/a.md)\n");
+    assert!(!transaction_log_path(&root).exists());
+    assert_eq!(history(&root).unwrap().len(), 1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_recover_roll_back_drops_interrupted_copy() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_mv(&root);
+    // A cross-device move interrupted after copying leaves both files.
+    write_file(root.join("a.md"), "# A\n");
+
+    recover(&root, RecoveryAction::RollBack).unwrap();
+
+    assert_eq!(read_file(root.join("a.md")), "# A\n");
+    assert!(!root.join("docs/a.md").exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_with_pending_log_returns_interrupted_error() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_mv(&root);
+    write_file(root.join("b.md"), "# B\n");
+
+    let result = mv(
+        root.join("b.md"),
+        root.join("c.md"),
+        &root,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(matches!(
+        result,
+        Err(MdrefError::InterruptedOperation { ref operation, .. }) if operation == "mv"
+    ));
+    assert!(root.join("b.md").exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_success_leaves_no_log() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n");
+    write_file(root.join("index.md"), "[A](a.md)\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("docs/a.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!transaction_log_path(root).exists());
+    assert!(interrupted_operation(root).unwrap().is_none());
+    assert!(fs::metadata(root.join(".mdref/history")).is_ok());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_failed_rename_leaves_no_log() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("a.md"), "# A\n");
+    write_file(root.join("index.md"), "[A](a.md)\n");
+    // No filesystem accepts a 300-byte file name, so the rename fails.
+    let too_long = format!("{}.md", "a".repeat(300));
+
+    let result = mv(
+        root.join("a.md"),
+        root.join("docs").join(too_long),
+        &root,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(result.is_err());
+    assert!(!transaction_log_path(&root).exists());
+    assert_eq!(read_file(root.join("index.md")), "[A](a.md)\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("b.md"),
+        &root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    assert_eq!(read_file(root.join("index.md")), "[A](b.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_recover_without_log_returns_error() {
+    let temp_dir = temp_dir();
+
+    let error = recover(temp_dir.path(), RecoveryAction::RollBack).unwrap_err();
+
+    assert!(error.to_string().contains("no interrupted operation"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_recover_interrupted_rm_back_restores_and_forward_removes() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    simulate_interrupted_rm(&root);
+
+    recover(&root, RecoveryAction::RollBack).unwrap();
+
+    assert_eq!(read_file(root.join("gone.md")), "# Gone\n");
+    assert!(!root.join(".gone.md.mdref-tmp").exists());
+    assert_eq!(read_file(root.join("index.md")), "[Gone](gone.md)\n");
+
+    simulate_interrupted_rm(&root);
+    fs::remove_file(root.join("gone.md")).unwrap();
+    recover(&root, RecoveryAction::RollForward).unwrap();
+
+    assert!(!root.join("gone.md").exists());
+    assert!(!root.join(".gone.md.mdref-tmp").exists());
+    assert_eq!(read_file(root.join("index.md")), "Gone\n");
+    // Removals are not journaled, so there is nothing to undo.
+    assert!(history(&root).unwrap().is_empty());
+}
@@ -0,0 +1,262 @@
+use mdref::{MdrefError, NoopProgress, preview_rename_heading, rename_heading};
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for `rename_heading` cover heading selection, anchor rewrites,
+// and the preview. CLI tests only verify the end-to-end process behavior.
+
+// ============= Heading rewrite tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_rewrites_inbound_and_same_file_links() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let index = temp_dir.path().join("docs/index.md");
+    write_file(
+        &guide,
+        "# Guide\n\n## Getting Started\n\nJump to [start](#getting-started).\n",
+    );
+    write_file(
+        &index,
+        "[Start](../guide.md#getting-started)\n[Top](../guide.md#guide)\n\n[ref]: ../guide.md#getting-started\n",
+    );
+
+    rename_heading(
+        &guide,
+        "Getting Started",
+        "Quick Start",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(&guide),
+        "# Guide\n\n## Quick Start\n\nJump to [start](#quick-start).\n"
+    );
+    assert_eq!(
+        read_file(&index),
+        "[Start](../guide.md#quick-start)\n[Top](../guide.md#guide)\n\n[ref]: ../guide.md#quick-start\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_preserves_markers_and_explicit_id() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let index = temp_dir.path().join("index.md");
+    write_file(&guide, "### Install ###\n\n## Setup {#setup}\n");
+    write_file(&index, "[A](guide.md#install) [B](guide.md#setup)\n");
+
+    rename_heading(
+        &guide,
+        "install",
+        "Installation",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    rename_heading(
+        &guide,
+        "setup",
+        "Configuration",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read_file(&guide),
+        "### Installation ###\n\n## Configuration {#setup}\n"
+    );
+    assert_eq!(
+        read_file(&index),
+        "[A](guide.md#installation) [B](guide.md#setup)\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_preserves_crlf_line_endings() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    write_file(&guide, "# Old\r\n\r\n[Self](#old)\r\n");
+
+    rename_heading(&guide, "Old", "New", temp_dir.path(), false, &NoopProgress).unwrap();
+
+    assert_eq!(read_file(&guide), "# New\r\n\r\n[Self](#new)\r\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_dry_run_does_not_modify_files() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let index = temp_dir.path().join("index.md");
+    write_file(&guide, "## Install\n");
+    write_file(&index, "[Install](guide.md#install)\n");
+
+    rename_heading(
+        &guide,
+        "Install",
+        "Setup",
+        temp_dir.path(),
+        true,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(read_file(&guide), "## Install\n");
+    assert_eq!(read_file(&index), "[Install](guide.md#install)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_rewrites_wikilink_heading_text() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let index = temp_dir.path().join("index.md");
+    write_file(&guide, "## Install\n\nSee [[#Install]].\n");
+    write_file(&index, "[[guide#Install|setup steps]] [[guide#Other]]\n");
+
+    rename_heading(
+        &guide,
+        "install",
+        "Setup",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(read_file(&guide), "## Setup\n\nSee [[#Setup]].\n");
+    assert_eq!(
+        read_file(&index),
+        "[[guide#Setup|setup steps]] [[guide#Other]]\n"
+    );
+}
+
+// ============= Preview tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_rename_heading_reports_anchor_change_and_files() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let index = temp_dir.path().join("index.md");
+    write_file(&guide, "## Install\n");
+    write_file(&index, "[Install](guide.md#install)\n[Other](guide.md)\n");
+
+    let preview =
+        preview_rename_heading(&guide, "Install", "Setup", temp_dir.path(), &NoopProgress).unwrap();
+
+    assert_eq!(preview.old_heading, "Install");
+    assert_eq!(preview.new_heading, "Setup");
+    assert_eq!(preview.old_anchor, "install");
+    assert_eq!(preview.new_anchor, "setup");
+    let paths: Vec<_> = preview.changes.iter().map(|c| c.path.clone()).collect();
+    assert_eq!(paths, vec![guide.clone(), index.clone()]);
+    assert_eq!(preview.changes[1].replacements.len(), 1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_preview_rename_heading_same_slug_only_rewrites_heading() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    write_file(&guide, "## install\n");
+    write_file(temp_dir.path().join("index.md"), "[I](guide.md#install)\n");
+
+    let preview =
+        preview_rename_heading(&guide, "install", "Install", temp_dir.path(), &NoopProgress)
+            .unwrap();
+
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.changes[0].path, guide);
+}
+
+// ============= Error tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_unknown_heading_returns_error() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    write_file(&guide, "# Guide\n");
+
+    let error = rename_heading(
+        &guide,
+        "Missing",
+        "New",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, MdrefError::PathValidation { .. }));
+    assert!(error.to_string().contains("no heading matches 'Missing'"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_rejects_empty_new_heading() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    write_file(&guide, "# Guide\n");
+
+    let result = rename_heading(&guide, "Guide", "  ", temp_dir.path(), false, &NoopProgress);
+
+    assert!(result.is_err());
+    assert_eq!(read_file(&guide), "# Guide\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_rejects_multi_line_setext_heading() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    let content = "Setext\nline two\n======\n\n[Top](#setext-line-two)\n";
+    write_file(&guide, content);
+
+    let error = rename_heading(
+        &guide,
+        "setext-line-two",
+        "Fresh",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap_err();
+
+    assert!(matches!(error, MdrefError::PathValidation { .. }));
+    assert!(error.to_string().contains("spans several lines"));
+    assert_eq!(read_file(&guide), content);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rename_heading_renames_setext_heading_in_blockquote() {
+    let temp_dir = temp_dir();
+    let guide = temp_dir.path().join("guide.md");
+    write_file(&guide, "> Quoted\n> ------\n\n[Top](#quoted)\n");
+
+    rename_heading(
+        &guide,
+        "Quoted",
+        "Cited",
+        temp_dir.path(),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert_eq!(read_file(&guide), "> Cited\n> ------\n\n[Top](#cited)\n");
+}
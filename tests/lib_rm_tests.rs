@@ -0,0 +1,287 @@
+use std::fs;
+
+use mdref::{MdrefError, NoopProgress, RemoveMode, preview_remove, rm, transaction_log_path};
+
+mod common;
+
+use common::{read_file, temp_dir, with_current_dir, write_file};
+
+// Library tests for `rm` cover the refuse, unlink, redirect and force modes,
+// directory removal, and rollback. CLI tests only verify the end-to-end
+// process behavior.
+
+// ============= Refuse and force tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_refuses_referenced_file() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+    let result = rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Refuse,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(matches!(
+        result,
+        Err(MdrefError::StillReferenced { count: 1, .. })
+    ));
+    assert!(root.join("gone.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[Gone](gone.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_removes_unreferenced_file() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "[Self](gone.md)\n");
+    write_file(root.join("index.md"), "# Index\n");
+
+    rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Refuse,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("gone.md").exists());
+    assert!(!transaction_log_path(root).exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_force_leaves_links_dangling() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+    rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Force,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("gone.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[Gone](gone.md)\n");
+}
+
+// ============= Unlink tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_unlink_replaces_every_link_form_with_text() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(
+        root.join("index.md"),
+        "See [the guide](gone.md#intro) and ![Icon](gone.md).\n\
+         Also [it][g] and [[gone|that page]] or [[gone#Intro]].\n\
+         [Keep](index.md)\n\
+         \n\
+         [g]: gone.md\n",
+    );
+
+    rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Unlink,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("gone.md").exists());
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "See the guide and Icon.\n\
+         Also it and that page or gone.\n\
+         [Keep](index.md)\n\
+         \n\
+         \n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_unlink_html_link_fails_without_changes() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("a.md"), "[Gone](gone.md)\n");
+    write_file(root.join("b.md"), "<a href=\"gone.md\">Gone</a>\n");
+
+    let result = rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Unlink,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(matches!(result, Err(MdrefError::PathValidation { .. })));
+    assert!(root.join("gone.md").exists());
+    assert_eq!(read_file(root.join("a.md")), "[Gone](gone.md)\n");
+}
+
+// ============= Redirect tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_redirect_points_links_at_replacement() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("old/gone.md"), "# Gone\n");
+    write_file(root.join("new/page.md"), "# Page\n");
+    write_file(
+        root.join("index.md"),
+        "[Gone](old/gone.md#intro)\n[[gone]]\n\n[ref]: old/gone.md\n",
+    );
+
+    rm(
+        root.join("old/gone.md"),
+        root,
+        &RemoveMode::RedirectTo(root.join("new/page.md")),
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("old/gone.md").exists());
+    assert_eq!(
+        read_file(root.join("index.md")),
+        "[Gone](new/page.md)\n[[page]]\n\n[ref]: new/page.md\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_redirect_with_default_root_keeps_root_level_links_relative() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("new.md"), "# New\n");
+    write_file(root.join("index.md"), "[g](gone.md)\n");
+
+    with_current_dir(&root, || {
+        rm(
+            "gone.md",
+            ".",
+            &RemoveMode::RedirectTo("new.md".into()),
+            false,
+            &NoopProgress,
+        )
+    })
+    .unwrap();
+
+    assert_eq!(read_file(root.join("index.md")), "[g](new.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_redirect_into_removed_directory_returns_error() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("old/a.md"), "# A\n");
+
+    let result = rm(
+        root.join("old"),
+        root,
+        &RemoveMode::RedirectTo(root.join("old/a.md")),
+        false,
+        &NoopProgress,
+    );
+
+    assert!(matches!(result, Err(MdrefError::PathValidation { .. })));
+    assert!(root.join("old/a.md").exists());
+}
+
+// ============= Directory tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_directory_ignores_links_inside_it() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("old/a.md"), "[B](b.md)\n");
+    write_file(root.join("old/b.md"), "[A](a.md)\n");
+    write_file(root.join("index.md"), "[A](old/a.md) and [B](old/b.md)\n");
+
+    let preview =
+        preview_remove(root.join("old"), root, &RemoveMode::Unlink, &NoopProgress).unwrap();
+    assert_eq!(preview.references.len(), 2);
+
+    rm(
+        root.join("old"),
+        root,
+        &RemoveMode::Unlink,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(!root.join("old").exists());
+    assert_eq!(read_file(root.join("index.md")), "A and B\n");
+}
+
+// ============= Dry-run and rollback tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_dry_run_changes_nothing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("index.md"), "[Gone](gone.md)\n");
+
+    rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Unlink,
+        true,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(root.join("gone.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[Gone](gone.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_rm_failed_rewrite_restores_removed_file() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("gone.md"), "# Gone\n");
+    write_file(root.join("a.md"), "[Gone](gone.md)\n");
+    write_file(root.join("b.md"), "[Gone](gone.md)\n");
+    let mut permissions = fs::metadata(root.join("b.md")).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(root.join("b.md"), permissions).unwrap();
+
+    let result = rm(
+        root.join("gone.md"),
+        root,
+        &RemoveMode::Unlink,
+        false,
+        &NoopProgress,
+    );
+
+    assert!(result.is_err());
+    assert_eq!(read_file(root.join("gone.md")), "# Gone\n");
+    assert_eq!(read_file(root.join("a.md")), "[Gone](gone.md)\n");
+    assert_eq!(read_file(root.join("b.md")), "[Gone](gone.md)\n");
+}
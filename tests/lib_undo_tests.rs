@@ -0,0 +1,196 @@
+use std::fs;
+
+use mdref::{NoopProgress, history, mv, mv_many, rename, rename_heading, undo};
+
+mod common;
+
+use common::{read_file, temp_dir, write_file};
+
+// Library tests for the undo journal: every completed operation is recorded
+// under `<root>/.mdref/history` and `undo` reverts the newest one.
+
+// ============= Undo tests =============
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_after_mv_restores_file_and_references() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n[Index](index.md)\n");
+    write_file(root.join("index.md"), "[A](a.md)\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("docs/a.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    let entry = undo(root).unwrap();
+
+    assert_eq!(entry.operation, "mv");
+    assert!(!root.join("docs/a.md").exists());
+    assert_eq!(read_file(root.join("a.md")), "# A\n[Index](index.md)\n");
+    assert_eq!(read_file(root.join("index.md")), "[A](a.md)\n");
+    assert!(history(root).unwrap().is_empty());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_after_directory_move_restores_directory() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("docs/guide.md"), "[Notes](../notes.md)\n");
+    write_file(root.join("docs/img/logo.png"), "");
+    write_file(root.join("notes.md"), "[Guide](docs/guide.md)\n");
+
+    mv(
+        root.join("docs"),
+        root.join("archive/docs"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    undo(root).unwrap();
+
+    assert!(!root.join("archive/docs").exists());
+    assert!(root.join("docs/img/logo.png").exists());
+    assert_eq!(
+        read_file(root.join("docs/guide.md")),
+        "[Notes](../notes.md)\n"
+    );
+    assert_eq!(read_file(root.join("notes.md")), "[Guide](docs/guide.md)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_batch_swap_restores_both_files() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n[B](b.md)\n");
+    write_file(root.join("b.md"), "# B\n");
+
+    mv_many(
+        &[
+            (root.join("a.md"), root.join("b.md")),
+            (root.join("b.md"), root.join("a.md")),
+        ],
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    undo(root).unwrap();
+
+    assert_eq!(read_file(root.join("a.md")), "# A\n[B](b.md)\n");
+    assert_eq!(read_file(root.join("b.md")), "# B\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_reverts_operations_in_reverse_order() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# Intro\n");
+    write_file(root.join("index.md"), "[A](a.md#intro)\n");
+
+    rename(root.join("a.md"), "b.md", root, false, &NoopProgress).unwrap();
+    rename_heading(
+        root.join("b.md"),
+        "Intro",
+        "Overview",
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    let operations: Vec<String> = history(root)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.operation)
+        .collect();
+    assert_eq!(operations, vec!["rename-heading", "mv"]);
+
+    undo(root).unwrap();
+    assert_eq!(read_file(root.join("b.md")), "# Intro\n");
+    assert_eq!(read_file(root.join("index.md")), "[A](b.md#intro)\n");
+
+    undo(root).unwrap();
+    assert!(!root.join("b.md").exists());
+    assert_eq!(read_file(root.join("a.md")), "# Intro\n");
+    assert_eq!(read_file(root.join("index.md")), "[A](a.md#intro)\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_refuses_when_rewritten_file_changed() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n");
+    write_file(root.join("index.md"), "[A](a.md)\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("b.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    write_file(root.join("index.md"), "[A](b.md)\nNew line\n");
+
+    let error = undo(root).unwrap_err();
+
+    assert!(error.to_string().contains("1 file(s) changed since mv #1"));
+    assert!(root.join("b.md").exists());
+    assert!(!root.join("a.md").exists());
+    assert_eq!(read_file(root.join("index.md")), "[A](b.md)\nNew line\n");
+    assert_eq!(history(root).unwrap().len(), 1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_undo_refuses_when_source_path_was_reused() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("b.md"),
+        root,
+        false,
+        &NoopProgress,
+    )
+    .unwrap();
+    write_file(root.join("a.md"), "# Another A\n");
+
+    let error = undo(root).unwrap_err();
+
+    assert!(error.to_string().contains("created again after the move"));
+    assert_eq!(read_file(root.join("a.md")), "# Another A\n");
+    assert!(root.join("b.md").exists());
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn test_mv_dry_run_records_nothing() {
+    let temp_dir = temp_dir();
+    let root = temp_dir.path();
+    write_file(root.join("a.md"), "# A\n");
+
+    mv(
+        root.join("a.md"),
+        root.join("b.md"),
+        root,
+        true,
+        &NoopProgress,
+    )
+    .unwrap();
+
+    assert!(history(root).unwrap().is_empty());
+    assert!(!fs::exists(root.join(".mdref")).unwrap());
+}